@@ -1,43 +1,25 @@
+use std::path::Path;
 use std::sync::Arc;
-use std::sync::mpsc::{channel, Sender};
+use std::sync::mpsc::Sender;
 
-use crate::share::classfile::class_loader::{BootstrapClassLoader, ResourceLocator};
-use crate::share::memory::heap::JvmHeap;
-use crate::share::native::native_method_repo::NativeMethodRepo;
+use crate::share::classfile::class_loader::BootstrapClassLoader;
+use crate::share::classfile::manifest::ExecutableJar;
+use crate::share::memory::heap_dump;
 use crate::share::runtime::api_event::ApiEvent;
-use crate::share::runtime::thread::MainJavaThread;
 use crate::share::utilities::context::GlobalContext;
 use std::thread::JoinHandle;
 use crate::share::utilities::jvm_exception::JvmException;
 
-pub fn init_jvm() -> impl JvmApi {
-    let locator = ResourceLocator::new(String::from(
-        "/home/barnab/projects/rust-jvm/resources",
-    ));
-    let heap = Arc::new(JvmHeap::new());
-    let context = Arc::new(GlobalContext::new(heap));
-    let loader = Arc::new(BootstrapClassLoader::new(locator, context.clone()));
-    context.set_class_loader(loader);
-
-    let native_method_repo = Arc::new(NativeMethodRepo::new());
-    context.set_native_method_repo(native_method_repo);
-
-    let (sender, receiver) = channel::<ApiEvent>();
-
-    let main_thread = MainJavaThread::new(context.clone());
-    let handle = main_thread.start(receiver);
-
-    JvmApiImpl::new(sender, handle)
-}
-
-struct JvmApiImpl {
+pub(crate) struct JvmApiImpl {
+    context: Arc<GlobalContext>,
+    class_loader: Arc<BootstrapClassLoader>,
     api_event_sender: Sender<ApiEvent>,
     jvm_handle: Option<JoinHandle<Result<i32, JvmException>>>,
 }
 
 impl JvmApiImpl {
-    fn new(api_event_sender: Sender<ApiEvent>, jvm_handle: JoinHandle<Result<i32, JvmException>>) -> Self {
-        JvmApiImpl { api_event_sender, jvm_handle: Some(jvm_handle) }
+    pub(crate) fn new(context: Arc<GlobalContext>, class_loader: Arc<BootstrapClassLoader>, api_event_sender: Sender<ApiEvent>, jvm_handle: JoinHandle<Result<i32, JvmException>>) -> Self {
+        JvmApiImpl { context, class_loader, api_event_sender, jvm_handle: Some(jvm_handle) }
     }
 }
 
@@ -49,16 +31,33 @@ impl JvmApi for JvmApiImpl {
         ).unwrap()
     }
 
-    fn call_main_method(&mut self, init_class_name: String) -> Result<i32, JvmException> {
-        self.api_event_sender.send(ApiEvent::CallMainMethodEvent { init_class: init_class_name }).unwrap();
+    fn call_main_method(&mut self, init_class_name: String, args: Vec<String>) -> Result<i32, JvmException> {
+        self.api_event_sender.send(ApiEvent::CallMainMethodEvent { init_class: init_class_name, args }).unwrap();
         self.jvm_handle.take().map(
             |main_thread| main_thread.join().unwrap()
         ).unwrap()
     }
+
+    fn call_jar_main_method(&mut self, jar_path: &Path, args: Vec<String>) -> Result<i32, JvmException> {
+        let jar = ExecutableJar::open(jar_path).map_err(|err| JvmException::from(err.to_string()))?;
+        self.class_loader.append_to_class_path(jar.class_path);
+        self.call_main_method(jar.main_class, args)
+    }
+
+    fn dump_heap(&self, path: &Path) -> Result<(), JvmException> {
+        heap_dump::dump_heap(&self.context, path)
+    }
 }
 
 
 pub trait JvmApi {
     fn shutdown(&mut self) -> Result<i32, JvmException>;
-    fn call_main_method(&mut self, init_class_name: String) -> Result<i32, JvmException>;
+    /// Calls the main method of the class, passing the arguments in its String[] parameter.
+    fn call_main_method(&mut self, init_class_name: String, args: Vec<String>) -> Result<i32, JvmException>;
+    /// Calls the main method of the class declared by the Main-Class attribute of the jar's manifest, loading
+    /// classes from the jar and the entries of its Class-Path attribute, like java -jar.
+    fn call_jar_main_method(&mut self, jar_path: &Path, args: Vec<String>) -> Result<i32, JvmException>;
+    /// Writes the objects of the heap to the given file in HPROF format, it can be opened by heap analyzers
+    /// like VisualVM or Eclipse MAT.
+    fn dump_heap(&self, path: &Path) -> Result<(), JvmException>;
 }
\ No newline at end of file
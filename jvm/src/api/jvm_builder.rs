@@ -0,0 +1,196 @@
+use std::sync::Arc;
+use std::sync::mpsc::channel;
+
+use crate::api::jvm_api::{JvmApi, JvmApiImpl};
+use crate::share::classfile::class_loader::{BootstrapClassLoader, ResourceLocator};
+use crate::share::classfile::class_path::{ClassPath, ClassPathEntry};
+use crate::share::memory::garbage_collector;
+use crate::share::memory::generational_heap::GenerationalHeap;
+use crate::share::memory::heap::{Heap, JvmHeap};
+use crate::share::native::native_method_repo::NativeMethodRepo;
+use crate::share::native::native_methods::NativeMethod;
+use crate::share::runtime::api_event::ApiEvent;
+use crate::share::runtime::options::JvmOptions;
+use crate::share::runtime::thread::MainJavaThread;
+use crate::share::utilities::context::GlobalContext;
+use crate::share::utilities::jvm_exception::JvmException;
+
+//the directory holding the reduced classes of the JDK, looked up next to the executable
+const RESOURCES_DIRECTORY: &str = "resources";
+
+//the generational heap needs a limit to size its generations, used when no maximum heap size is given
+const DEFAULT_GENERATIONAL_HEAP_SIZE: usize = 256 * 1024 * 1024;
+
+/// Configures and starts a JVM, e.g.
+///
+/// ```ignore
+/// let mut jvm = JvmBuilder::new()
+///     .class_path(ClassPath::parse("app.jar")?)
+///     .max_heap_size(64 * 1024 * 1024)
+///     .system_property("app.mode", "test")
+///     .build()?;
+/// jvm.call_main_method(String::from("app/Main"), Vec::new())
+/// ```
+pub struct JvmBuilder {
+    boot_class_path: Option<ClassPath>,
+    class_path: ClassPath,
+    heap: Option<Arc<dyn Heap>>,
+    generational_heap: bool,
+    max_heap_size: Option<usize>,
+    options: JvmOptions,
+    log_sink: Option<(Box<dyn log::Log>, log::LevelFilter)>,
+    native_methods: Vec<(String, NativeMethod)>,
+}
+
+impl JvmBuilder {
+    /// A builder of a JVM loading the classes of the JDK from the default boot class path, with an empty class path
+    /// and an unlimited JvmHeap.
+    pub fn new() -> JvmBuilder {
+        JvmBuilder {
+            boot_class_path: None,
+            class_path: ClassPath::new(Vec::new()),
+            heap: None,
+            generational_heap: false,
+            max_heap_size: None,
+            options: JvmOptions::default(),
+            log_sink: None,
+            native_methods: Vec::new(),
+        }
+    }
+
+    /// The entries the classes of the JDK are loaded from, searched before the class path, like -Xbootclasspath.
+    /// By default it's the resources directory next to the executable or in one of its parent directories, so it's
+    /// found from the target directory of the workspace as well.
+    pub fn boot_class_path(mut self, boot_class_path: ClassPath) -> JvmBuilder {
+        self.boot_class_path = Some(boot_class_path);
+        self
+    }
+
+    /// The entries the classes of the application are loaded from.
+    pub fn class_path(mut self, class_path: ClassPath) -> JvmBuilder {
+        self.class_path = class_path;
+        self
+    }
+
+    /// Allocates the objects on the given heap, the generational heap and the maximum heap size are ignored then.
+    pub fn heap<H: Heap + 'static>(mut self, heap: H) -> JvmBuilder {
+        self.heap = Some(Arc::new(heap));
+        self
+    }
+
+    /// Allocates the objects on a GenerationalHeap instead of a JvmHeap, like -XX:+UseGenerationalGC.
+    pub fn generational_heap(mut self, generational_heap: bool) -> JvmBuilder {
+        self.generational_heap = generational_heap;
+        self
+    }
+
+    /// The number of bytes the objects may occupy, like -Xmx. Without it a JvmHeap is unlimited, and a
+    /// GenerationalHeap is limited to 256MB.
+    pub fn max_heap_size(mut self, max_heap_size: usize) -> JvmBuilder {
+        self.max_heap_size = Some(max_heap_size);
+        self
+    }
+
+    /// The size of the native stack of every Java thread in bytes, like -Xss, which limits the depth of the
+    /// Java stack.
+    pub fn thread_stack_size(mut self, thread_stack_size: usize) -> JvmBuilder {
+        self.options.thread_stack_size = Some(thread_stack_size);
+        self
+    }
+
+    /// The maximum number of frames on the Java stack of every thread. Deeper invocations throw StackOverflowError
+    /// instead of exhausting the native stack, which would abort the process.
+    pub fn max_stack_depth(mut self, max_stack_depth: usize) -> JvmBuilder {
+        self.options.max_stack_depth = Some(max_stack_depth);
+        self
+    }
+
+    /// Sets a property returned by System.getProperty, like -D.
+    pub fn system_property(mut self, name: &str, value: &str) -> JvmBuilder {
+        self.options.system_properties.insert(String::from(name), String::from(value));
+        self
+    }
+
+    /// Prints every loaded class, like -verbose:class.
+    pub fn verbose_class(mut self, verbose_class: bool) -> JvmBuilder {
+        self.options.verbose_class = verbose_class;
+        self
+    }
+
+    /// Installs the logger receiving the log of the JVM up to the given level. There's only one logger per
+    /// process, so building fails if one is installed already.
+    pub fn log_sink(mut self, sink: Box<dyn log::Log>, level: log::LevelFilter) -> JvmBuilder {
+        self.log_sink = Some((sink, level));
+        self
+    }
+
+    /// Implements a native method of a class loaded by the JVM, e.g. class_name tests/Main, method_name compute
+    /// and descriptor (I)I. Replaces the native method of the JDK if it has the same name.
+    pub fn native_method(mut self, class_name: &str, method_name: &str, descriptor: &str, native_method: NativeMethod) -> JvmBuilder {
+        self.native_methods.push((format!("{}_{}{}", class_name, method_name, descriptor), native_method));
+        self
+    }
+
+    /// Starts the main thread of the JVM, which waits for the main method to call.
+    pub fn build(self) -> Result<impl JvmApi, JvmException> {
+        if let Some((sink, level)) = self.log_sink {
+            log::set_boxed_logger(sink).map_err(|_| JvmException::from("A logger is installed already"))?;
+            log::set_max_level(level);
+        }
+
+        let mut class_path = match self.boot_class_path {
+            Some(boot_class_path) => boot_class_path,
+            None => JvmBuilder::default_boot_class_path()?,
+        };
+        class_path.append(self.class_path);
+        let locator = ResourceLocator::with_class_path(class_path);
+
+        let heap = match (self.heap, self.generational_heap, self.max_heap_size) {
+            (Some(heap), _, _) => heap,
+            (None, true, max_heap_size) => Arc::new(GenerationalHeap::with_max_heap_size(max_heap_size.unwrap_or(DEFAULT_GENERATIONAL_HEAP_SIZE))),
+            (None, false, Some(max_heap_size)) => Arc::new(JvmHeap::with_max_heap_size(max_heap_size)),
+            (None, false, None) => Arc::new(JvmHeap::new()),
+        };
+        let context = Arc::new(GlobalContext::with_options(heap.clone(), self.options));
+        //the heap doesn't keep the context alive, it's dropped together with the JVM
+        let gc_context = Arc::downgrade(&context);
+        heap.set_garbage_collector(Box::new(move |collection| {
+            gc_context.upgrade().map_or(0, |context| garbage_collector::run_collection(&context, collection))
+        }));
+
+        let loader = Arc::new(BootstrapClassLoader::new(locator, context.clone()));
+        context.set_class_loader(loader.clone());
+
+        let mut native_method_repo = NativeMethodRepo::new();
+        for (symbol, native_method) in self.native_methods {
+            native_method_repo.register(symbol, native_method);
+        }
+        context.set_native_method_repo(Arc::new(native_method_repo));
+
+        let (sender, receiver) = channel::<ApiEvent>();
+
+        let main_thread = MainJavaThread::new(context.clone());
+        let handle = main_thread.start(receiver);
+
+        Ok(JvmApiImpl::new(context, loader, sender, handle))
+    }
+
+    fn default_boot_class_path() -> Result<ClassPath, JvmException> {
+        let executable = std::env::current_exe()
+            .map_err(|err| JvmException::from(format!("The location of the executable is unknown: {}", err)))?;
+        executable.ancestors()
+            .skip(1)
+            .map(|directory| directory.join(RESOURCES_DIRECTORY))
+            .find(|resources| resources.is_dir())
+            .map(|resources| ClassPath::new(vec![ClassPathEntry::Directory(resources)]))
+            .ok_or_else(|| JvmException::from(format!(
+                "The classes of the JDK weren't found in a {} directory next to {}, the boot class path has to be set",
+                RESOURCES_DIRECTORY, executable.display())))
+    }
+}
+
+impl Default for JvmBuilder {
+    fn default() -> Self {
+        JvmBuilder::new()
+    }
+}
@@ -1 +1,2 @@
-pub mod jvm_api;
\ No newline at end of file
+pub mod jvm_api;
+pub mod jvm_builder;
@@ -1,26 +1,28 @@
 use std::collections::HashMap;
 
 use crate::share::classfile::class_parser::ClassParser;
+use crate::share::classfile::class_path::{ClassPath, ClassPathEntry};
 use crate::share::classfile::constant_pool::Qualifier;
 use crate::share::classfile::klass::ClassLoadingStatus::{
-    BeingInitialized, Initialized, Linked, Loaded,
+    Erroneous, Initialized, Linked, Loaded,
 };
-use crate::share::classfile::klass::Klass;
+use crate::share::classfile::klass::{InitializationRequest, Klass};
 use crate::share::classfile::method::MethodInfo;
+use crate::share::runtime::safepoint::Safepoint;
 use crate::share::runtime::stack_frame::{JvmStackFrame, StackFrame};
 use crate::share::utilities::context::GlobalContext;
-use crate::share::utilities::global_symbols::Symbols::{java_lang_Object, java_lang_Class};
+use crate::share::utilities::global_symbols::Symbols::{java_lang_Object, java_lang_Class, java_lang_Error};
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::utilities::jvm_value::JvmValue;
 use std::borrow::BorrowMut;
-use std::io::{Error, ErrorKind};
-use std::sync::{Arc, Mutex};
+use std::io::Error;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
 use utils::ResultIterator;
 
-static RESOURCES_PATH: &str = "resources/";
-
 type ClassKey = String;
 
+#[cfg_attr(test, mockall::automock)]
 pub trait ClassLoader: Send + Sync {
     fn lookup_static_method(
         &self,
@@ -32,36 +34,45 @@ pub trait ClassLoader: Send + Sync {
         qualified_name: Qualifier,
     ) -> Result<Arc<MethodInfo>, JvmException>;
 
-    fn lookup_interface_method(&self,
-                               calling_class: Arc<Klass>,
-                               qualified_name: Qualifier) -> Result<Arc<MethodInfo>, JvmException>;
-
     fn load_class(&self, qualified_name: &Qualifier) -> Result<Arc<Klass>, JvmException>;
 
     fn load_and_init_class(&self, qualified_name: &String) -> Result<Arc<Klass>, JvmException>;
 
     fn bootstrap(&self) -> Result<(), JvmException>;
+
+    /// Every class loaded by this class loader.
+    fn loaded_classes(&self) -> Vec<Arc<Klass>>;
 }
 
 pub struct ResourceLocator {
-    resource_root: String,
+    class_path: RwLock<ClassPath>,
 }
 
 impl ResourceLocator {
+    /// Creates a locator reading the class files under the given directory.
     pub fn new(root: String) -> ResourceLocator {
+        ResourceLocator::with_class_path(ClassPath::new(vec![ClassPathEntry::Directory(PathBuf::from(root))]))
+    }
+
+    /// Creates a locator searching the entries of the class path in order.
+    pub fn with_class_path(class_path: ClassPath) -> ResourceLocator {
         log::trace!("ClassLoader initialized.");
-        ResourceLocator {
-            resource_root: root,
-        }
+        ResourceLocator { class_path: RwLock::new(class_path) }
+    }
+
+    /// Appends the entries of the class path, they are searched after the current ones.
+    pub fn append(&self, class_path: ClassPath) {
+        self.class_path.write().unwrap().append(class_path);
     }
 
-    fn read_from_resource(&self, class_name: &String) -> Result<Vec<u8>, Error> {
+    /// Reads the class file of the class, together with the location of the class path entry containing it.
+    fn read_from_resource(&self, class_name: &String) -> Result<(Vec<u8>, PathBuf), Error> {
         let path = ResourceLocator::class_name_to_path(class_name);
-        let absolute_path = format!("{}/{}", self.resource_root, path);
-        log::trace!("Reading absolute file: {}", absolute_path);
-        std::fs::read(absolute_path.clone()).map_err(|err| {
-            Error::new(ErrorKind::NotFound, format!("Class file not found at: {}", absolute_path))
-        })
+        self.class_path
+            .read()
+            .unwrap()
+            .locate_resource(&path)
+            .map(|(contents, entry)| (contents, entry.location().to_path_buf()))
     }
 
     fn class_name_to_path(class_name: &String) -> String {
@@ -90,12 +101,7 @@ impl ClassLoader for BootstrapClassLoader {
                 name: _,
                 descriptor: _,
             } => {
-                let klass = self.load_class(class_name)?;
-
-                if !klass.is_being_initialized() && !klass.is_initialized() {
-                    self.load_and_init_class(class_name)?;
-                }
-                assert!(klass.is_initialized() || klass.is_being_initialized());
+                let klass = self.load_and_init_class(class_name)?;
 
                 klass
                     .get_method_by_qualified_name(&qualified_name)
@@ -109,37 +115,18 @@ impl ClassLoader for BootstrapClassLoader {
         &self,
         qualified_name: Qualifier,
     ) -> Result<Arc<MethodInfo>, JvmException> {
-        self.lookup_static_method(qualified_name)
-    }
-
-    fn lookup_interface_method(&self, calling_class: Arc<Klass>, qualified_name: Qualifier) -> Result<Arc<MethodInfo>, JvmException> {
         match &qualified_name {
             Qualifier::MethodRef {
                 class_name,
-                name: _,
-                descriptor: _,
+                name,
+                descriptor,
             } => {
                 let klass = self.load_class(class_name)?;
+                self.link_class(klass.clone())?;
 
-                if !klass.is_being_initialized() && !klass.is_initialized() {
-                    self.load_and_init_class(class_name)?;
-                }
-                assert!(klass.is_initialized() || klass.is_being_initialized());
-                assert!(klass.is_interface());
-
-                let resolved_method = klass
-                    .get_method_by_qualified_name(&qualified_name)
-                    .filter(|m| !m.is_abstract())
-                    .or(
-                        {
-                            let declared_method = calling_class
-                                .get_method_by_qualified_name(&qualified_name);
-
-                            declared_method
-                        }
-                    ).ok_or(JvmException::from(format!("Method {:?} not found on class {:?}", qualified_name, klass)))?;
-
-                Ok(resolved_method)
+                klass
+                    .resolve_method(&format!("{}{}", name, descriptor))
+                    .ok_or(JvmException::from(format!("Method {:?} not found on class {:?}", qualified_name, klass)))
             }
             _ => Err(JvmException::from(format!("Expected MethodRef but got {:?}", qualified_name))),
         }
@@ -166,6 +153,10 @@ impl ClassLoader for BootstrapClassLoader {
 
         Ok(())
     }
+
+    fn loaded_classes(&self) -> Vec<Arc<Klass>> {
+        self.lookup_table.lock().unwrap().values().cloned().collect()
+    }
 }
 
 impl BootstrapClassLoader {
@@ -180,6 +171,11 @@ impl BootstrapClassLoader {
         }
     }
 
+    /// Appends the entries of the class path to the ones classes are loaded from, e.g. the ones of a jar.
+    pub fn append_to_class_path(&self, class_path: ClassPath) {
+        self.resource_locator.append(class_path);
+    }
+
     /// Loads a class from the bootstrap classpath or returns a `JvmException` if the class lookup
     /// or the parsing fails.
     ///
@@ -204,7 +200,7 @@ impl BootstrapClassLoader {
     }
 
     fn do_load(&self, class_name: &String) -> Result<Arc<Klass>, JvmException> {
-        let raw_class = self
+        let (raw_class, location) = self
             .resource_locator
             .read_from_resource(&class_name)
             .map_err(|err| JvmException::from(err.to_string()))?;
@@ -212,15 +208,22 @@ impl BootstrapClassLoader {
         let derived_class = self.derive_class(raw_class)?;
         derived_class.set_status(Loaded);
 
-        //record the resolved class in the cache
-        self.lookup_table
+        //record the resolved class in the cache, if another thread has loaded the same class meanwhile
+        //its instance is used instead, so each class has a single initialization lock
+        let loaded_class = self.lookup_table
             .lock()
             .unwrap()
             .borrow_mut()
-            .insert(class_name.clone(), derived_class.clone());
+            .entry(class_name.clone())
+            .or_insert_with(|| derived_class.clone())
+            .clone();
+        if Arc::ptr_eq(&loaded_class, &derived_class) && self.context.options().verbose_class {
+            //same format as the one of HotSpot before unified logging
+            println!("[Loaded {} from {}]", class_name.replace('/', "."), location.display());
+        }
 
         //return a pointer to it
-        Ok(derived_class)
+        Ok(loaded_class)
     }
 
     /// Tries to parse a class from the given bytes. If succeeds returns a `Klass` wrapped in an `Arc`,
@@ -234,7 +237,8 @@ impl BootstrapClassLoader {
         //we've got the class, now need to check its superclass
         match klass.qualified_super_name() {
             Some(super_name) => {
-                self.load_class(&super_name)?;
+                let super_class = self.load_class(&super_name)?;
+                klass.set_super_class(super_class);
                 //TODO: 5.4.3.1 steps are missing to load Reference Type for array classes
                 //as well as applying access control to this class and its superclass
 
@@ -253,11 +257,12 @@ impl BootstrapClassLoader {
         }
 
         //loading up all direct interfaces
-        let _loaded_interfaces = klass
+        let loaded_interfaces = klass
             .interfaces()
             .iter()
             .map(|interface| self.load_class(interface))
             .collect_to_result()?;
+        klass.set_super_interfaces(loaded_interfaces);
 
         //TODO add extra checks over resolved interfaces: IncompatibleClassChangeError, and ClassCircularityError
 
@@ -271,6 +276,10 @@ impl BootstrapClassLoader {
         );
 
         if !class_to_link.is_linked() {
+            //the method tables of a class are built on top of its superclass'
+            if let Some(super_class) = class_to_link.super_class() {
+                self.link_class(super_class)?;
+            }
             self.verify_class(class_to_link.clone())?;
             self.prepare_class(class_to_link.clone())?;
             class_to_link.set_status(Linked);
@@ -287,36 +296,107 @@ impl BootstrapClassLoader {
 
         // Register bootstrap native method. Probably non-standard... Will need to check
         class_to_prepare.register_natives(self.context.native_method_repo().as_ref());
+
+        class_to_prepare.initialize_vtable();
+        class_to_prepare.initialize_itable();
         Ok(())
     }
 
+    /// Initializes a class by executing its class initialization method, after initializing its superclass
+    /// and the superinterfaces declaring default methods.
+    ///
+    /// 5.5 Section of JVM Specification
     fn initialize_class(&self, class_to_init: Arc<Klass>) -> Result<(), JvmException> {
         assert!(
             class_to_init.is_linked(),
             "Class should be linked before calling init!"
         );
 
-        //we should not just be ignoring the call in case it's being initialized in the future,
-        //what if another thread is initializing it. We should identify that case and retry this block.
-        if !class_to_init.is_initialized() && !class_to_init.is_being_initialized() {
-            class_to_init.set_status(BeingInitialized);
-
-            //initialize static fields to their default values
-            class_to_init.initialize_static_fields();
-
-            let mirror = self.context.heap().allocate_class(class_to_init.clone())?;
-            class_to_init.set_java_mirror(mirror);
-
-            class_to_init
-                .get_cl_init()
-                .map(|init: Arc<MethodInfo>| -> Result<(), JvmException> {
-                    let frame = StackFrame::new(&self.context, class_to_init.clone());
-                    frame.execute_method(init, Vec::new())?;
-                    Ok(())
-                })
-                .unwrap_or_else(|| Ok(()))?;
-            class_to_init.set_status(Initialized);
+        if class_to_init.is_initialized() {
+            return Ok(());
+        }
+
+        //steps 1-6, waits for other threads initializing the same class, stopped at a safepoint meanwhile
+        if Safepoint::blocking(|| class_to_init.begin_initialization())? == InitializationRequest::Completed {
+            return Ok(());
         }
+
+        //step 7, a failing superclass initialization is propagated as is
+        if let Err(exception) = self.initialize_super_types(&class_to_init) {
+            class_to_init.finish_initialization(Erroneous);
+            return Err(exception);
+        }
+
+        //steps 8-12
+        match self.run_class_initializer(&class_to_init) {
+            Ok(()) => {
+                class_to_init.finish_initialization(Initialized);
+                Ok(())
+            }
+            Err(exception) => {
+                //the class is marked erroneous first, so waiting threads are woken up even if wrapping fails
+                class_to_init.finish_initialization(Erroneous);
+                Err(self.exception_in_initializer(exception).unwrap_or_else(|wrapping_error| wrapping_error))
+            }
+        }
+    }
+
+    fn initialize_super_types(&self, class_to_init: &Arc<Klass>) -> Result<(), JvmException> {
+        if class_to_init.is_interface() {
+            return Ok(());
+        }
+
+        let super_interfaces = class_to_init.all_super_interfaces()
+            .into_iter()
+            .filter(|interface| interface.declares_default_methods());
+
+        class_to_init.super_class()
+            .into_iter()
+            .chain(super_interfaces)
+            .map(|super_type| {
+                self.link_class(super_type.clone())?;
+                self.initialize_class(super_type)
+            })
+            .collect_to_result()?;
         Ok(())
     }
+
+    fn run_class_initializer(&self, class_to_init: &Arc<Klass>) -> Result<(), JvmException> {
+        //initialize static fields to their default values
+        class_to_init.initialize_static_fields();
+
+        let mirror = self.context.heap().allocate_class(class_to_init.clone())?;
+        class_to_init.set_java_mirror(mirror);
+
+        class_to_init
+            .get_cl_init()
+            .map(|init: Arc<MethodInfo>| -> Result<(), JvmException> {
+                let frame = StackFrame::new(&self.context, class_to_init.clone());
+                frame.execute_method(init, Vec::new())?;
+                Ok(())
+            })
+            .unwrap_or_else(|| Ok(()))
+    }
+
+    /// Wraps an exception thrown by a class initialization method into an ExceptionInInitializerError,
+    /// unless it's already an Error.
+    fn exception_in_initializer(&self, exception: JvmException) -> Result<JvmException, JvmException> {
+        //internal errors of the VM are not visible to Java code
+        let thrown_class_name = match exception.exception_class() {
+            Some(thrown_class_name) => thrown_class_name,
+            None => return Ok(exception),
+        };
+
+        let thrown_class = self.load_class(thrown_class_name)?;
+        let error_class = self.load_class(&java_lang_Error)?;
+        if thrown_class.is_subclass_of(&error_class) {
+            return Ok(exception);
+        }
+
+        let wrapped_exception = JvmException::exception_in_initializer();
+        Ok(match exception.throwable() {
+            Some(thrown) => wrapped_exception.with_cause(thrown.clone()),
+            None => wrapped_exception,
+        })
+    }
 }
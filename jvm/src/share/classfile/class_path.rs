@@ -0,0 +1,106 @@
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::share::utilities::zip::ZipArchive;
+
+#[cfg(test)]
+#[path = "./class_path_test.rs"]
+mod class_path_test;
+
+/// A location classes are loaded from: a directory whose subdirectories follow the packages of the classes, or
+/// a jar or ZIP archive.
+#[derive(Debug)]
+pub enum ClassPathEntry {
+    Directory(PathBuf),
+    Archive(ZipArchive),
+}
+
+impl ClassPathEntry {
+    /// Opens the entry at the given path, files other than directories are read as archives.
+    pub fn open(path: &Path) -> Result<ClassPathEntry, Error> {
+        if path.is_dir() {
+            Ok(ClassPathEntry::Directory(path.to_path_buf()))
+        } else if path.is_file() {
+            Ok(ClassPathEntry::Archive(ZipArchive::open(path)?))
+        } else {
+            Err(Error::new(ErrorKind::NotFound, format!("Class path entry not found: {}", path.display())))
+        }
+    }
+
+    /// The directory or archive of the entry.
+    pub fn location(&self) -> &Path {
+        match self {
+            ClassPathEntry::Directory(directory) => directory,
+            ClassPathEntry::Archive(archive) => archive.path(),
+        }
+    }
+
+    /// Reads the resource with the given '/' separated name, or returns None if the entry doesn't contain it.
+    fn read(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        match self {
+            ClassPathEntry::Directory(directory) => {
+                let path = directory.join(name);
+                log::trace!("Reading absolute file: {}", path.display());
+                match std::fs::read(&path) {
+                    Ok(contents) => Ok(Some(contents)),
+                    Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+                    Err(err) => Err(err),
+                }
+            }
+            ClassPathEntry::Archive(archive) => {
+                log::trace!("Reading {} from archive: {}", name, archive.path().display());
+                archive.read(name)
+            }
+        }
+    }
+}
+
+/// The entries classes are loaded from, searched in order.
+#[derive(Debug)]
+pub struct ClassPath {
+    entries: Vec<ClassPathEntry>,
+}
+
+impl ClassPath {
+    pub fn new(entries: Vec<ClassPathEntry>) -> ClassPath {
+        ClassPath { entries }
+    }
+
+    /// Opens the entries of a class path separated like by the -classpath option of java, e.g. with ':' on
+    /// Unix platforms. The entries which don't exist are ignored like by HotSpot.
+    pub fn parse(class_path: &str) -> Result<ClassPath, Error> {
+        let mut entries = Vec::new();
+        for path in std::env::split_paths(class_path).filter(|path| !path.as_os_str().is_empty()) {
+            if path.exists() {
+                entries.push(ClassPathEntry::open(&path)?);
+            } else {
+                log::warn!("Ignoring missing class path entry {}", path.display());
+            }
+        }
+        Ok(ClassPath::new(entries))
+    }
+
+    /// Appends the entries of the other class path, they are searched after the ones of this class path.
+    pub fn append(&mut self, other: ClassPath) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn entries(&self) -> &Vec<ClassPathEntry> {
+        &self.entries
+    }
+
+    /// Reads the resource with the given '/' separated name from the first entry containing it.
+    pub fn read_resource(&self, name: &str) -> Result<Vec<u8>, Error> {
+        self.locate_resource(name).map(|(contents, _)| contents)
+    }
+
+    /// Reads the resource like `read_resource`, together with the entry it was found in.
+    pub fn locate_resource(&self, name: &str) -> Result<(Vec<u8>, &ClassPathEntry), Error> {
+        for entry in &self.entries {
+            if let Some(contents) = entry.read(name)? {
+                return Ok((contents, entry));
+            }
+        }
+        Err(Error::new(ErrorKind::NotFound, format!("{} not found on the class path", name)))
+    }
+}
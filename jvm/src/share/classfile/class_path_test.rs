@@ -0,0 +1,69 @@
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::share::classfile::class_path::{ClassPath, ClassPathEntry};
+
+const RESOURCES: &str = "/home/barnab/projects/rust-jvm/resources";
+const CLASS_FILE_MAGIC: [u8; 4] = [0xCA, 0xFE, 0xBA, 0xBE];
+
+fn test_class_path() -> ClassPath {
+    ClassPath::parse(&format!(
+        "{0}/tests/classpath/packaged.jar:{0}/tests/classpath/library.zip:{0}",
+        RESOURCES
+    )).unwrap()
+}
+
+#[test]
+pub fn reads_deflated_entries_of_jars() {
+    let class_file = test_class_path().read_resource("tests/classpath/Packaged.class").unwrap();
+
+    assert_eq!(609, class_file.len());
+    assert_eq!(CLASS_FILE_MAGIC, class_file[..4]);
+}
+
+#[test]
+pub fn reads_stored_entries_of_zips() {
+    let class_file = test_class_path().read_resource("tests/classpath/lib/Library.class").unwrap();
+
+    assert_eq!(424, class_file.len());
+    assert_eq!(CLASS_FILE_MAGIC, class_file[..4]);
+}
+
+#[test]
+pub fn entries_are_searched_in_order() {
+    let class_path = test_class_path();
+
+    let manifest = class_path.read_resource("META-INF/MANIFEST.MF").unwrap();
+    assert!(manifest.starts_with(b"Manifest-Version: 1.0"));
+    let object_class = class_path.read_resource("java/lang/Object.class").unwrap();
+    assert_eq!(std::fs::read(format!("{}/java/lang/Object.class", RESOURCES)).unwrap(), object_class);
+}
+
+#[test]
+pub fn missing_resources_are_not_found() {
+    let error = test_class_path().read_resource("tests/classpath/Missing.class").unwrap_err();
+
+    assert_eq!(ErrorKind::NotFound, error.kind());
+}
+
+#[test]
+pub fn missing_entries_are_rejected() {
+    let error = ClassPathEntry::open(Path::new("/home/barnab/projects/rust-jvm/resources/missing.jar")).unwrap_err();
+
+    assert_eq!(ErrorKind::NotFound, error.kind());
+}
+
+#[test]
+pub fn missing_entries_of_class_paths_are_ignored() {
+    let class_path = ClassPath::parse(&format!("{0}/missing.jar:{0}:{0}/missing", RESOURCES)).unwrap();
+
+    assert_eq!(1, class_path.entries().len());
+    assert_eq!(Path::new(RESOURCES), class_path.entries()[0].location());
+}
+
+#[test]
+pub fn files_other_than_archives_are_rejected() {
+    let error = ClassPathEntry::open(Path::new("/home/barnab/projects/rust-jvm/resources/tests/classpath/Packaged.java")).unwrap_err();
+
+    assert_eq!(ErrorKind::InvalidData, error.kind());
+}
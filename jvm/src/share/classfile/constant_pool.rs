@@ -25,7 +25,7 @@ pub struct ConstantPool {
     pool: Vec<CpInfo>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Qualifier {
     String {
         val: String,
@@ -61,8 +61,8 @@ impl ConstantPool {
         while i < count {
             let cp_info = CpInfo::create(cursor)?;
             match cp_info {
-                CpInfo::Long { .. } => {
-                    //need this bespoke logic for Longs as they occupy 2 places in CP.
+                CpInfo::Long { .. } | CpInfo::Double { .. } => {
+                    //need this bespoke logic for Longs and Doubles as they occupy 2 places in CP.
                     constant_pool.push(cp_info.clone());
                     i += 2;
                 }
@@ -32,6 +32,14 @@ impl FieldInfo {
         &self.name == name && &self.descriptor == type_descriptor
     }
 
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn descriptor(&self) -> &String {
+        &self.descriptor
+    }
+
     pub fn is_static(&self) -> bool {
         crate::share::classfile::access_flags::flag_matches(self.access_flags, ACC_STATIC)
     }
@@ -48,6 +56,12 @@ impl FieldInfo {
             .clone()
     }
 
+    /// Returns the stored static value, or None if the static fields of the class are not initialized yet.
+    pub fn initialized_static_value(&self) -> Option<JvmValue> {
+        assert!(self.is_static());
+        self.static_value.lock().unwrap().clone()
+    }
+
     pub fn set_static_value(&self, value: JvmValue) {
         assert!(self.is_static());
         self.static_value.lock().unwrap().replace(value);
@@ -58,7 +72,7 @@ impl FieldInfo {
             "Z" => JvmValue::Boolean { val: false },
             "B" => JvmValue::Byte { val: 0 },
             "S" => JvmValue::Short { val: 0 },
-            "C" => JvmValue::Char { val: '\0' },
+            "C" => JvmValue::Char { val: 0 },
             "I" => JvmValue::Int { val: 0 },
             "J" => JvmValue::Long { val: 0 },
             "F" => JvmValue::Float { val: 0.0 },
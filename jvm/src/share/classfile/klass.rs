@@ -2,16 +2,23 @@ use crate::share::classfile::attribute::AttributeInfo;
 use crate::share::classfile::constant_pool::{ConstantPool, Qualifier};
 use crate::share::classfile::field::FieldInfo;
 use crate::share::classfile::klass::ClassLoadingStatus::{
-    BeingInitialized, Initialized, Linked, Loaded, Mentioned,
+    BeingInitialized, Erroneous, Initialized, Linked, Loaded, Mentioned,
 };
 use crate::share::classfile::method::MethodInfo;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::ThreadId;
 use crate::share::native::native_method_repo::NativeMethodRepo;
-use crate::share::parser::descriptors::FieldDescriptor;
 use std::fmt::{Debug, Formatter};
 use crate::share::memory::oop::oops::ObjectOopDesc;
 use crate::share::classfile::access_flags;
 use crate::share::classfile::access_flags::ACC_INTERFACE;
+use crate::share::utilities::jvm_exception::JvmException;
+use crate::share::utilities::jvm_value::JvmValue;
+
+#[cfg(test)]
+#[path = "./klass_test.rs"]
+mod klass_test;
 
 #[derive(Clone, Copy, PartialEq, PartialOrd)]
 pub enum ClassLoadingStatus {
@@ -20,6 +27,17 @@ pub enum ClassLoadingStatus {
     Linked,
     BeingInitialized,
     Initialized,
+    //the initialization of the class has failed, it can't be used anymore
+    Erroneous,
+}
+
+/// The outcome of requesting the initialization of a class, see steps 1-6 of 5.5 Section of JVM Specification.
+#[derive(Debug, PartialEq)]
+pub enum InitializationRequest {
+    /// The current thread is responsible for initializing the class.
+    Initialize,
+    /// The class is either initialized, or it's being initialized by the current thread.
+    Completed,
 }
 
 pub struct Klass {
@@ -31,11 +49,17 @@ pub struct Klass {
     super_class_name: Option<String>,
     super_class: Mutex<Option<Arc<Klass>>>,
     interfaces: Vec<String>,
+    super_interfaces: Mutex<Vec<Arc<Klass>>>,
     instance_fields: Vec<Arc<FieldInfo>>,
     static_fields: Vec<Arc<FieldInfo>>,
     methods: Vec<Arc<MethodInfo>>,
+    vtable: Mutex<Vec<Arc<MethodInfo>>>,
+    itable: Mutex<HashMap<String, Arc<MethodInfo>>>,
     attributes: Vec<AttributeInfo>,
     status: Mutex<ClassLoadingStatus>,
+    //the thread initializing the class, only accessed while holding the lock of status
+    initialization_thread: Mutex<Option<ThreadId>>,
+    initialization_finished: Condvar,
     java_mirror: Mutex<Option<ObjectOopDesc>>,
 }
 
@@ -54,6 +78,7 @@ impl PartialEq for Klass {
 
 
 impl Klass {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         minor_version: u16,
         major_version: u16,
@@ -66,7 +91,7 @@ impl Klass {
         mut methods: Vec<MethodInfo>,
         attributes: Vec<AttributeInfo>,
     ) -> Klass {
-        let methods: Vec<Arc<MethodInfo>> = methods.drain(0..).map(|m| Arc::new(m)).collect();
+        let methods: Vec<Arc<MethodInfo>> = methods.drain(0..).map(Arc::new).collect();
 
         let mut instance_fields: Vec<Arc<FieldInfo>> = Vec::new();
         let mut static_fields: Vec<Arc<FieldInfo>> = Vec::new();
@@ -87,11 +112,16 @@ impl Klass {
             super_class_name,
             super_class: Mutex::new(None),
             interfaces,
+            super_interfaces: Mutex::new(Vec::new()),
             instance_fields,
             static_fields,
             methods,
+            vtable: Mutex::new(Vec::new()),
+            itable: Mutex::new(HashMap::new()),
             attributes,
             status: Mutex::new(Mentioned),
+            initialization_thread: Mutex::new(None),
+            initialization_finished: Condvar::new(),
             java_mirror: Mutex::new(None),
         }
     }
@@ -104,9 +134,9 @@ impl Klass {
         self.super_class_name.clone()
     }
 
-    // pub fn super_class(&self) -> Ref<Option<Arc<Klass>>> {
-    //     self.super_class.borrow()
-    // }
+    pub fn super_class(&self) -> Option<Arc<Klass>> {
+        self.super_class.lock().unwrap().clone()
+    }
 
     pub fn set_super_class(&self, super_class: Arc<Klass>) {
         self.super_class.lock().unwrap().replace(super_class);
@@ -120,14 +150,83 @@ impl Klass {
         self.java_mirror.lock().unwrap().as_ref().unwrap().clone()
     }
 
+    /// The references held by this class: the values of its static fields and its java/lang/Class instance.
+    pub fn gc_roots(&self) -> Vec<JvmValue> {
+        let mut roots: Vec<JvmValue> = self.static_fields
+            .iter()
+            .filter_map(|field| field.initialized_static_value())
+            .collect();
+        if let Some(mirror) = self.java_mirror.lock().unwrap().as_ref() {
+            roots.push(JvmValue::from(mirror.clone()));
+        }
+        roots
+    }
+
     pub fn interfaces(&self) -> Vec<String> {
-        self.interfaces.iter().cloned().collect()
+        self.interfaces.to_vec()
+    }
+
+    pub fn set_super_interfaces(&self, super_interfaces: Vec<Arc<Klass>>) {
+        *self.super_interfaces.lock().unwrap() = super_interfaces;
+    }
+
+    /// The resolved direct superinterfaces of this class.
+    pub fn super_interfaces(&self) -> Vec<Arc<Klass>> {
+        self.super_interfaces.lock().unwrap().clone()
+    }
+
+    /// All superinterfaces of this class and its superclasses, more specific interfaces come first.
+    pub fn all_super_interfaces(&self) -> Vec<Arc<Klass>> {
+        let mut interfaces = Vec::new();
+        for interface in self.super_interfaces() {
+            interfaces.push(interface.clone());
+            interfaces.extend(interface.all_super_interfaces());
+        }
+
+        if let Some(super_class) = self.super_class() {
+            interfaces.extend(super_class.all_super_interfaces());
+        }
+        interfaces
+    }
+
+    /// Returns true if this class is the given class or one of its subclasses.
+    pub fn is_subclass_of(&self, other: &Klass) -> bool {
+        if self == other {
+            return true;
+        }
+        self.super_class().is_some_and(|super_class| super_class.is_subclass_of(other))
+    }
+
+    /// Returns true if a reference to an instance of this class can be assigned to a variable of the given class
+    /// or interface type, see the rules of CHECKCAST in 6.5 Section of JVM Specification.
+    pub fn is_assignable_to(&self, other: &Klass) -> bool {
+        if other.is_interface() {
+            self == other || self.all_super_interfaces().iter().any(|interface| interface.as_ref() == other)
+        } else {
+            self.is_subclass_of(other)
+        }
+    }
+
+    pub fn static_fields(&self) -> &Vec<Arc<FieldInfo>> {
+        &self.static_fields
     }
 
     pub fn instance_fields(&self) -> &Vec<Arc<FieldInfo>> {
         &self.instance_fields
     }
 
+    /// All instance fields of an object of this class, the fields declared by superclasses come first so the
+    /// offset of an inherited field is the same in every subclass.
+    pub fn instance_field_layout(&self) -> Vec<Arc<FieldInfo>> {
+        let mut layout = self.super_class().map_or(Vec::new(), |super_class| super_class.instance_field_layout());
+        layout.extend(self.instance_fields.iter().cloned());
+        layout
+    }
+
+    pub fn instance_field_count(&self) -> usize {
+        self.super_class().map_or(0, |super_class| super_class.instance_field_count()) + self.instance_fields.len()
+    }
+
     pub fn initialize_static_fields(&self) {
         self.static_fields
             .iter()
@@ -138,22 +237,36 @@ impl Klass {
         self.static_fields
             .iter()
             .find(|f| f.matches_name_and_type(name, type_descriptor))
-            .map(|f| f.clone())
+            .cloned()
     }
 
+    /// Resolves the offset of a field declared by this class or any of its superclasses, see 5.4.3.2 Section of
+    /// JVM Specification.
     pub fn get_instance_field_offset(&self, name: &String, type_descriptor: &String) -> Option<usize> {
+        let super_class = self.super_class();
+        let inherited_fields = super_class.as_ref().map_or(0, |super_class| super_class.instance_field_count());
+
         for i in 0..self.instance_fields.len() {
             if self.instance_fields[i].matches_name_and_type(name, type_descriptor) {
-                return Some(i);
+                return Some(inherited_fields + i);
             }
         }
-        None
+        super_class.and_then(|super_class| super_class.get_instance_field_offset(name, type_descriptor))
     }
 
     pub fn constant_pool(&self) -> &ConstantPool {
         &self.constant_pool
     }
 
+    /// The version of the class file format, major first.
+    pub fn version(&self) -> (u16, u16) {
+        (self.major_version, self.minor_version)
+    }
+
+    pub fn attributes(&self) -> &Vec<AttributeInfo> {
+        &self.attributes
+    }
+
     pub fn referenced_classes(&self) -> Vec<String> {
         self.super_class_name
             .as_ref()
@@ -184,39 +297,80 @@ impl Klass {
         *self.status.lock().unwrap() == Initialized
     }
 
+    pub fn is_erroneous(&self) -> bool {
+        *self.status.lock().unwrap() == Erroneous
+    }
+
     pub fn set_status(&self, status: ClassLoadingStatus) {
         *self.status.lock().unwrap() = status;
     }
 
+    /// Acquires the initialization of this class for the current thread. Blocks while another thread is
+    /// initializing the class, and fails with NoClassDefFoundError if a previous initialization has failed.
+    pub fn begin_initialization(&self) -> Result<InitializationRequest, JvmException> {
+        let current_thread = std::thread::current().id();
+        let mut status = self.status.lock().unwrap();
+        loop {
+            match *status {
+                BeingInitialized if *self.initialization_thread.lock().unwrap() == Some(current_thread) => {
+                    //recursive request for initialization, e.g. <clinit> refers to its own class
+                    return Ok(InitializationRequest::Completed);
+                }
+                BeingInitialized => status = self.initialization_finished.wait(status).unwrap(),
+                Initialized => return Ok(InitializationRequest::Completed),
+                Erroneous => return Err(JvmException::no_class_def_found(&self.this_class)),
+                _ => {
+                    *status = BeingInitialized;
+                    *self.initialization_thread.lock().unwrap() = Some(current_thread);
+                    return Ok(InitializationRequest::Initialize);
+                }
+            }
+        }
+    }
+
+    /// Records the outcome of the initialization started by `begin_initialization`, which is either
+    /// Initialized or Erroneous, and wakes up the threads waiting for it.
+    pub fn finish_initialization(&self, outcome: ClassLoadingStatus) {
+        let mut status = self.status.lock().unwrap();
+        *status = outcome;
+        *self.initialization_thread.lock().unwrap() = None;
+        self.initialization_finished.notify_all();
+    }
+
+    /// Returns true if the interface declares a non-abstract, non-static method, such interfaces are
+    /// initialized together with the classes implementing them.
+    pub fn declares_default_methods(&self) -> bool {
+        self.methods.iter().any(|method| !method.is_abstract() && !method.is_static())
+    }
+
     pub fn get_method_by_qualified_name(
         &self,
         qualified_name: &Qualifier,
     ) -> Option<Arc<MethodInfo>> {
-        return match qualified_name {
+        match qualified_name {
             Qualifier::MethodRef {
                 class_name: _,
                 name,
                 descriptor,
             } => self.get_method_by_name_desc(format!("{}{}", name, descriptor)),
             _ => None,
-        };
+        }
     }
 
     fn get_method_by_name_desc(&self, name_desc: String) -> Option<Arc<MethodInfo>> {
         self.methods
             .iter()
             .find(|method| name_desc == method.name_desc())
-            .map(|method| Arc::clone(method))
+            .cloned()
     }
 
+    /// Binds the native methods of this class to their implementations in the repository.
     pub fn register_natives(&self, native_method_repo: &NativeMethodRepo) {
-        self.get_method_by_name_desc("registerNatives()V".to_string())
-            .map(|method| {
-                native_method_repo.find_method(method.as_ref())
-                    .map(|native_method| {
-                        method.set_native_method(native_method)
-                    })
-            });
+        for method in self.methods.iter().filter(|method| method.is_native()) {
+            if let Some(native_method) = native_method_repo.find_method(method.as_ref()) {
+                method.set_native_method(native_method)
+            }
+        }
     }
 
     pub fn get_cl_init(&self) -> Option<Arc<MethodInfo>> {
@@ -226,4 +380,95 @@ impl Klass {
     pub fn methods(&self) -> &Vec<Arc<MethodInfo>> {
         &self.methods
     }
+
+    /// Builds the virtual method table, which starts with the entries of the superclass' vtable so that
+    /// overriding methods take the index of the method they override, see 5.4.5 Section of JVM Specification.
+    /// The superclass has to be prepared before calling this. Interfaces have no vtable, their methods are
+    /// dispatched through the itable of the receiver's class.
+    pub fn initialize_vtable(&self) {
+        if self.is_interface() {
+            return;
+        }
+        let mut vtable = self.super_class().map_or(Vec::new(), |super_class| super_class.vtable());
+
+        for method in self.methods.iter().filter(|method| method.is_virtual()) {
+            let index = vtable.iter()
+                .position(|inherited| inherited.name_desc() == method.name_desc())
+                .unwrap_or(vtable.len());
+            method.set_vtable_index(index);
+            if index < vtable.len() {
+                vtable[index] = method.clone();
+            } else {
+                vtable.push(method.clone());
+            }
+        }
+
+        *self.vtable.lock().unwrap() = vtable;
+    }
+
+    /// Builds the interface method table from all the superinterfaces of this class and its superclasses, mapping
+    /// every interface method to the implementation selected for this class. Implementations in the class hierarchy
+    /// take precedence over default methods. Has to be called after `initialize_vtable`.
+    pub fn initialize_itable(&self) {
+        let mut itable = HashMap::new();
+        let interfaces = self.all_super_interfaces();
+
+        for interface_method in interfaces.iter().flat_map(|interface| interface.methods.iter()) {
+            if !interface_method.is_virtual() {
+                continue;
+            }
+            let name_desc = interface_method.name_desc();
+
+            let selected_method = self.find_virtual_method(&name_desc)
+                .filter(|method| !method.is_abstract())
+                .or(itable.get(&name_desc).cloned().filter(|method: &Arc<MethodInfo>| !method.is_abstract()))
+                .unwrap_or(interface_method.clone());
+
+            itable.insert(name_desc, selected_method);
+        }
+
+        *self.itable.lock().unwrap() = itable;
+    }
+
+    pub fn vtable(&self) -> Vec<Arc<MethodInfo>> {
+        self.vtable.lock().unwrap().clone()
+    }
+
+    /// Selects the method to invoke by INVOKEVIRTUAL on an instance of this class by the vtable index of the
+    /// resolved method. None if this class isn't a subclass of the class declaring the resolved method, since the
+    /// index is only meaningful in the vtables of its subclasses.
+    pub fn virtual_method(&self, resolved_method: &MethodInfo) -> Option<Arc<MethodInfo>> {
+        match resolved_method.vtable_index() {
+            Some(vtable_index) if self.is_subclass_of(&resolved_method.get_klass()) => {
+                self.vtable.lock().unwrap().get(vtable_index).cloned()
+            }
+            Some(_) => None,
+            //methods an abstract class inherits from its superinterfaces only have no vtable entry
+            None => self.find_interface_method(&resolved_method.name_desc()),
+        }
+    }
+
+    /// Looks up the entry of the vtable by name and descriptor, for callers which have no resolved method.
+    pub fn find_virtual_method(&self, name_desc: &String) -> Option<Arc<MethodInfo>> {
+        self.vtable
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|method| method.name_desc() == *name_desc)
+            .cloned()
+    }
+
+    /// Selects the method to invoke by INVOKEINTERFACE on an instance of this class, the implementations are
+    /// selected when the itable is built. None if this class doesn't implement an interface declaring the method.
+    pub fn find_interface_method(&self, name_desc: &String) -> Option<Arc<MethodInfo>> {
+        self.itable.lock().unwrap().get(name_desc).cloned()
+    }
+
+    /// Looks up a method declared by this class, its superclasses or superinterfaces in the order of method
+    /// resolution, see 5.4.3.3 Section of JVM Specification.
+    pub fn resolve_method(&self, name_desc: &String) -> Option<Arc<MethodInfo>> {
+        self.get_method_by_name_desc(name_desc.clone())
+            .or_else(|| self.super_class().and_then(|super_class| super_class.resolve_method(name_desc)))
+            .or_else(|| self.itable.lock().unwrap().get(name_desc).cloned())
+    }
 }
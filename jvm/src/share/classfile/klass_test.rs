@@ -0,0 +1,115 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::share::classfile::klass::ClassLoadingStatus::{Erroneous, Initialized, Linked};
+use crate::share::classfile::klass::InitializationRequest;
+use crate::share::utilities::testing::{java_lang_object_class, java_lang_string_class, java_lang_throwable_class, test_class};
+
+#[test]
+pub fn begin_initialization_of_linked_class() {
+    let klass = test_class();
+    klass.set_status(Linked);
+
+    assert_eq!(Ok(InitializationRequest::Initialize), klass.begin_initialization());
+    assert!(klass.is_being_initialized());
+}
+
+#[test]
+pub fn recursive_initialization_request_completes() {
+    let klass = test_class();
+    klass.set_status(Linked);
+
+    assert_eq!(Ok(InitializationRequest::Initialize), klass.begin_initialization());
+    assert_eq!(Ok(InitializationRequest::Completed), klass.begin_initialization());
+}
+
+#[test]
+pub fn initialized_class_completes() {
+    let klass = test_class();
+    klass.set_status(Linked);
+    klass.begin_initialization().unwrap();
+    klass.finish_initialization(Initialized);
+
+    assert!(klass.is_initialized());
+    assert_eq!(Ok(InitializationRequest::Completed), klass.begin_initialization());
+}
+
+#[test]
+pub fn erroneous_class_throws_no_class_def_found_error() {
+    let klass = test_class();
+    klass.set_status(Linked);
+    klass.begin_initialization().unwrap();
+    klass.finish_initialization(Erroneous);
+
+    let exception = klass.begin_initialization().unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/NoClassDefFoundError")), exception.exception_class());
+    assert!(klass.is_erroneous());
+}
+
+#[test]
+pub fn other_thread_waits_for_initialization() {
+    let klass = test_class();
+    klass.set_status(Linked);
+    klass.begin_initialization().unwrap();
+
+    let completed = Arc::new(AtomicBool::new(false));
+    let (other_klass, other_completed) = (klass.clone(), completed.clone());
+    let other_thread = thread::spawn(move || {
+        let request = other_klass.begin_initialization();
+        other_completed.store(true, Ordering::SeqCst);
+        request
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    assert!(!completed.load(Ordering::SeqCst));
+
+    klass.finish_initialization(Initialized);
+    assert_eq!(Ok(InitializationRequest::Completed), other_thread.join().unwrap());
+}
+
+#[test]
+pub fn overriding_method_takes_the_vtable_index_of_the_overridden_method() {
+    let object = java_lang_object_class();
+    let string = java_lang_string_class();
+    string.set_super_class(object.clone());
+    object.initialize_vtable();
+    string.initialize_vtable();
+
+    let to_string = String::from("toString()Ljava/lang/String;");
+    let overridden = object.find_virtual_method(&to_string).unwrap();
+    let overriding = string.find_virtual_method(&to_string).unwrap();
+    assert!(!Arc::ptr_eq(&overridden, &overriding));
+    assert_eq!(overridden.vtable_index(), overriding.vtable_index());
+    assert!(Arc::ptr_eq(&overriding, &string.virtual_method(&overridden).unwrap()));
+}
+
+#[test]
+pub fn new_virtual_methods_are_appended_to_the_vtable() {
+    let throwable = java_lang_throwable_class();
+    let object = throwable.super_class().unwrap();
+    object.initialize_vtable();
+    throwable.initialize_vtable();
+
+    let get_message = throwable.find_virtual_method(&String::from("getMessage()Ljava/lang/String;")).unwrap();
+    assert!(get_message.vtable_index().unwrap() >= object.vtable().len());
+    assert!(object.virtual_method(&get_message).is_none());
+}
+
+#[test]
+pub fn receiver_not_subclass_of_the_declaring_class_selects_no_method() {
+    let object = java_lang_object_class();
+    let string = java_lang_string_class();
+    let throwable = java_lang_throwable_class();
+    string.set_super_class(object.clone());
+    throwable.set_super_class(object.clone());
+    object.initialize_vtable();
+    string.initialize_vtable();
+    throwable.initialize_vtable();
+
+    let get_message = throwable.find_virtual_method(&String::from("getMessage()Ljava/lang/String;")).unwrap();
+    //String has a method at the same vtable index, but it's unrelated to Throwable.getMessage
+    assert!(string.vtable().len() > get_message.vtable_index().unwrap());
+    assert!(string.virtual_method(&get_message).is_none());
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::share::classfile::class_path::{ClassPath, ClassPathEntry};
+
+#[cfg(test)]
+#[path = "./manifest_test.rs"]
+mod manifest_test;
+
+const MANIFEST_NAME: &str = "META-INF/MANIFEST.MF";
+
+/// The main section of a jar manifest, the attributes of the individual entries are ignored.
+pub struct Manifest {
+    //the names of attributes are case-insensitive, so they are stored in lower case
+    main_attributes: HashMap<String, String>,
+}
+
+impl Manifest {
+    /// Parses the manifest, whose lines are terminated by CR LF, LF or CR, and longer values are continued on
+    /// the next line starting with a single space. The main section ends with the first empty line.
+    pub fn parse(contents: &[u8]) -> Result<Manifest, Error> {
+        let contents = String::from_utf8_lossy(contents);
+        let mut lines: Vec<String> = Vec::new();
+        for line in contents.split("\r\n").flat_map(|line| line.split(['\n', '\r'])) {
+            if line.is_empty() {
+                break;
+            }
+            match (line.strip_prefix(' '), lines.last_mut()) {
+                (Some(continuation), Some(previous)) => previous.push_str(continuation),
+                (Some(_), None) => return Err(invalid_manifest("Continuation line without an attribute")),
+                (None, _) => lines.push(String::from(line)),
+            }
+        }
+
+        let mut main_attributes = HashMap::new();
+        for line in lines {
+            let separator = line.find(": ").ok_or_else(|| invalid_manifest(&format!("Invalid attribute: {}", line)))?;
+            main_attributes.insert(line[..separator].to_lowercase(), String::from(&line[separator + 2..]));
+        }
+        Ok(Manifest { main_attributes })
+    }
+
+    pub fn main_attribute(&self, name: &str) -> Option<&String> {
+        self.main_attributes.get(&name.to_lowercase())
+    }
+
+    /// The class whose main method is called when the jar is executed, in internal form, e.g. java/lang/Object.
+    pub fn main_class(&self) -> Option<String> {
+        self.main_attribute("Main-Class").map(|main_class| main_class.trim().replace('.', "/"))
+    }
+
+    /// The relative URLs of the jars and directories the classes of the jar depend on.
+    pub fn class_path(&self) -> Vec<String> {
+        self.main_attribute("Class-Path")
+            .map_or(Vec::new(), |class_path| class_path.split_whitespace().map(String::from).collect())
+    }
+}
+
+/// A jar declaring its main class in its manifest, which can be run like by java -jar.
+pub struct ExecutableJar {
+    pub main_class: String,
+    /// The jar itself followed by the entries of the Class-Path attribute of its manifest.
+    pub class_path: ClassPath,
+}
+
+impl ExecutableJar {
+    pub fn open(path: &Path) -> Result<ExecutableJar, Error> {
+        let jar = match ClassPathEntry::open(path)? {
+            ClassPathEntry::Archive(archive) => archive,
+            ClassPathEntry::Directory(_) => return Err(Error::new(ErrorKind::InvalidInput, format!("{} is not a jar", path.display()))),
+        };
+        let manifest = match jar.read(MANIFEST_NAME)? {
+            Some(contents) => Manifest::parse(&contents)?,
+            None => return Err(ExecutableJar::missing_main_class(path)),
+        };
+        let main_class = manifest.main_class().ok_or_else(|| ExecutableJar::missing_main_class(path))?;
+
+        //relative entries are resolved against the directory of the jar, the ones which don't exist are ignored
+        //like by HotSpot
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut entries = vec![ClassPathEntry::Archive(jar)];
+        for url in manifest.class_path() {
+            let entry_path = directory.join(decode_url_path(&url));
+            if entry_path.exists() {
+                entries.push(ClassPathEntry::open(&entry_path)?);
+            } else {
+                log::warn!("Ignoring missing Class-Path entry {} of {}", url, path.display());
+            }
+        }
+        Ok(ExecutableJar { main_class, class_path: ClassPath::new(entries) })
+    }
+
+    fn missing_main_class(path: &Path) -> Error {
+        //same message as the one of java
+        invalid_manifest(&format!("no main manifest attribute, in {}", path.display()))
+    }
+}
+
+/// The path of a file URL, with its percent-encoded bytes decoded.
+fn decode_url_path(url: &str) -> String {
+    let path = url.strip_prefix("file:").unwrap_or(url);
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = match (bytes[index], bytes.get(index + 1..index + 3)) {
+            (b'%', Some(hex)) => std::str::from_utf8(hex).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn invalid_manifest(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,54 @@
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::share::classfile::manifest::{ExecutableJar, Manifest};
+
+#[test]
+pub fn main_attributes_are_parsed() {
+    let manifest = Manifest::parse(b"Manifest-Version: 1.0\r\nMain-Class: com.example.Main\r\nClass-Path: lib/a.jar  b.jar\r\n\r\n").unwrap();
+
+    assert_eq!(Some(&String::from("1.0")), manifest.main_attribute("Manifest-Version"));
+    assert_eq!(Some(String::from("com/example/Main")), manifest.main_class());
+    assert_eq!(vec![String::from("lib/a.jar"), String::from("b.jar")], manifest.class_path());
+}
+
+#[test]
+pub fn attribute_names_are_case_insensitive() {
+    let manifest = Manifest::parse(b"main-class: Main\n").unwrap();
+
+    assert_eq!(Some(String::from("Main")), manifest.main_class());
+}
+
+#[test]
+pub fn continuation_lines_are_joined() {
+    let manifest = Manifest::parse(b"Class-Path: first.jar sec\n ond.jar\n").unwrap();
+
+    assert_eq!(vec![String::from("first.jar"), String::from("second.jar")], manifest.class_path());
+}
+
+#[test]
+pub fn attributes_of_entries_are_ignored() {
+    let manifest = Manifest::parse(b"Manifest-Version: 1.0\n\nName: Main.class\nMain-Class: Other\n").unwrap();
+
+    assert_eq!(None, manifest.main_class());
+}
+
+#[test]
+pub fn executable_jar_class_path_is_resolved_relative_to_the_jar() {
+    let jar = ExecutableJar::open(Path::new("/home/barnab/projects/rust-jvm/resources/tests/classpath/executable.jar")).unwrap();
+
+    assert_eq!("tests/classpath/Packaged", jar.main_class);
+    //the missing entry of the Class-Path attribute is ignored
+    assert_eq!(2, jar.class_path.entries().len());
+    assert!(jar.class_path.read_resource("tests/classpath/lib/Library.class").is_ok());
+}
+
+#[test]
+pub fn jar_without_main_class_is_rejected() {
+    let error = ExecutableJar::open(Path::new("/home/barnab/projects/rust-jvm/resources/tests/classpath/packaged.jar"))
+        .err()
+        .unwrap();
+
+    assert_eq!(ErrorKind::InvalidData, error.kind());
+    assert!(error.to_string().starts_with("no main manifest attribute"));
+}
@@ -1,6 +1,6 @@
 use crate::share::classfile::access_flags;
-use crate::share::classfile::access_flags::{ACC_NATIVE, ACC_STATIC, ACC_ABSTRACT};
-use crate::share::classfile::attribute::AttributeInfo;
+use crate::share::classfile::access_flags::{ACC_NATIVE, ACC_STATIC, ACC_ABSTRACT, ACC_PRIVATE, ACC_SYNCHRONIZED};
+use crate::share::classfile::attribute::{AttributeInfo, ExceptionHandler};
 use crate::share::classfile::klass::Klass;
 use crate::share::native::native_methods::NativeMethod;
 use crate::share::parser::descriptors::{
@@ -9,7 +9,7 @@ use crate::share::parser::descriptors::{
 use crate::share::parser::parser::Parser;
 use std::fmt;
 use std::fmt::Formatter;
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 use std::sync::{Arc, RwLock, Weak};
 use core::fmt::Display;
 
@@ -27,6 +27,7 @@ pub struct MethodInfo {
     native_method: RwLock<Option<NativeMethod>>,
     code: Option<CodeInfo>,
     klass: RwLock<Option<Weak<Klass>>>,
+    vtable_index: RwLock<Option<usize>>,
 }
 
 impl fmt::Display for MethodInfo {
@@ -51,22 +52,28 @@ impl fmt::Debug for MethodInfo {
 pub struct CodeInfo {
     bytes: Vec<u8>,
     local_variables: u16,
+    exception_table: Vec<ExceptionHandler>,
 }
 
 impl CodeInfo {
-    pub fn new(bytes: Vec<u8>, local_variables: u16) -> Self {
+    pub fn new(bytes: Vec<u8>, local_variables: u16, exception_table: Vec<ExceptionHandler>) -> Self {
         CodeInfo {
             bytes,
             local_variables,
+            exception_table,
         }
     }
 
     pub fn bytes(&self) -> &Vec<u8> {
-        return &self.bytes;
+        &self.bytes
     }
 
     pub fn local_variables(&self) -> u16 {
-        return self.local_variables.clone();
+        self.local_variables
+    }
+
+    pub fn exception_table(&self) -> &Vec<ExceptionHandler> {
+        &self.exception_table
     }
 }
 
@@ -81,7 +88,7 @@ impl MethodInfo {
 
         let descriptor = MethodDescriptorParser::new()
             .parse(raw_descriptor.as_str())
-            .map_err(|err| Error::new(ErrorKind::Other, format!("{:?}", err)))?;
+            .map_err(|err| Error::other(format!("{:?}", err)))?;
 
         Ok(MethodInfo {
             access_flags,
@@ -92,21 +99,27 @@ impl MethodInfo {
             native_method: RwLock::new(None),
             code,
             klass: RwLock::new(None),
+            vtable_index: RwLock::new(None),
         })
     }
 
-    fn resolve_code(attributes: &Vec<AttributeInfo>) -> Option<CodeInfo> {
-        let code = attributes.iter().find(|att| match att {
-            AttributeInfo::Code { .. } => true,
-            _ => false,
-        });
+    fn resolve_code(attributes: &[AttributeInfo]) -> Option<CodeInfo> {
+        let code = attributes.iter().find(|att| matches!(att, AttributeInfo::Code { .. }));
 
-        return match code {
+        match code {
             Some(AttributeInfo::Code {
-                code, max_locals, ..
-            }) => Some(CodeInfo::new(code.clone(), max_locals.clone())),
+                code, max_locals, exception_table, ..
+            }) => Some(CodeInfo::new(code.clone(), *max_locals, exception_table.clone())),
             _ => None,
-        };
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn attributes(&self) -> &Vec<AttributeInfo> {
+        &self.attributes
     }
 
     pub fn name_desc(&self) -> String {
@@ -129,6 +142,23 @@ impl MethodInfo {
         access_flags::flag_matches(self.access_flags, ACC_ABSTRACT)
     }
 
+    pub fn is_private(&self) -> bool {
+        access_flags::flag_matches(self.access_flags, ACC_PRIVATE)
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        access_flags::flag_matches(self.access_flags, ACC_SYNCHRONIZED)
+    }
+
+    pub fn is_instance_initializer(&self) -> bool {
+        self.name == "<init>"
+    }
+
+    /// Methods which can be overridden, and so are dispatched through the vtable of the receiver's class.
+    pub fn is_virtual(&self) -> bool {
+        !self.is_static() && !self.is_private() && !self.is_instance_initializer()
+    }
+
     pub fn set_native_method(&self, method: NativeMethod) {
         *self.native_method.write().unwrap() = Some(method)
     }
@@ -151,6 +181,18 @@ impl MethodInfo {
             .expect("Klass has been unloaded but it's been referenced from code!")
     }
 
+    /// Assigns the entry of the vtable of the declaring class which holds this method, and the overriding methods
+    /// in the vtables of its subclasses.
+    pub fn set_vtable_index(&self, vtable_index: usize) {
+        *self.vtable_index.write().unwrap() = Some(vtable_index)
+    }
+
+    /// The entry of the vtables this method is dispatched through, none if it's not virtual or it's declared by
+    /// an interface.
+    pub fn vtable_index(&self) -> Option<usize> {
+        *self.vtable_index.read().unwrap()
+    }
+
     pub fn number_of_parameters(&self) -> u8 {
         self.descriptor.parameters.len() as u8
     }
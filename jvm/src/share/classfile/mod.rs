@@ -2,8 +2,10 @@ pub mod access_flags;
 pub mod attribute;
 pub mod class_loader;
 pub mod class_parser;
+pub mod class_path;
 pub mod constant_pool;
 pub mod descriptor;
 pub mod field;
 pub mod klass;
+pub mod manifest;
 pub mod method;
@@ -1,5 +1,6 @@
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::utilities::jvm_value::{JvmValue, ObjectRef};
+use std::cmp::Ordering;
 
 #[cfg(test)]
 #[path = "./evaluation_stack_test.rs"]
@@ -7,48 +8,266 @@ mod evaluation_stack_test;
 
 pub struct EvaluationStack {
     stack: Vec<JvmValue>,
+    //references popped by the current instruction, they are still in use until the instruction completes
+    operands: Vec<JvmValue>,
+}
+
+impl Default for EvaluationStack {
+    fn default() -> Self {
+        EvaluationStack::new()
+    }
 }
 
 #[cfg_attr(test, mockall::automock)]
 impl EvaluationStack {
     pub fn new() -> EvaluationStack {
-        EvaluationStack { stack: Vec::new() }
+        EvaluationStack { stack: Vec::new(), operands: Vec::new() }
+    }
+
+    pub fn add(&mut self) -> Result<(), JvmException> {
+        let rhs = self.pop();
+        let lhs = self.pop();
+
+        match (lhs, rhs) {
+            (JvmValue::Int { val: lhs_val }, JvmValue::Int { val: rhs_val }) => {
+                self.i_constant(lhs_val.wrapping_add(rhs_val))
+            }
+            (JvmValue::Long { val: lhs_val }, JvmValue::Long { val: rhs_val }) => {
+                self.l_constant(lhs_val.wrapping_add(rhs_val))
+            }
+            (JvmValue::Double { val: lhs_val }, JvmValue::Double { val: rhs_val }) => {
+                self.d_constant(lhs_val + rhs_val)
+            }
+            (JvmValue::Float { val: lhs_val }, JvmValue::Float { val: rhs_val }) => {
+                self.f_constant(lhs_val + rhs_val)
+            }
+            (lhs, rhs) => return Err(JvmException::from(format!("Cannot add 2 values of type: {:?} {:?}", lhs, rhs))),
+        }
+        Ok(())
+    }
+
+    pub fn sub(&mut self) -> Result<(), JvmException> {
+        let rhs = self.pop();
+        let lhs = self.pop();
+
+        match (lhs, rhs) {
+            (JvmValue::Int { val: lhs_val }, JvmValue::Int { val: rhs_val }) => {
+                self.i_constant(lhs_val.wrapping_sub(rhs_val))
+            }
+            (JvmValue::Long { val: lhs_val }, JvmValue::Long { val: rhs_val }) => {
+                self.l_constant(lhs_val.wrapping_sub(rhs_val))
+            }
+            (JvmValue::Double { val: lhs_val }, JvmValue::Double { val: rhs_val }) => {
+                self.d_constant(lhs_val - rhs_val)
+            }
+            (JvmValue::Float { val: lhs_val }, JvmValue::Float { val: rhs_val }) => {
+                self.f_constant(lhs_val - rhs_val)
+            }
+            (lhs, rhs) => return Err(JvmException::from(format!("Cannot subtract 2 values of type: {:?} {:?}", lhs, rhs))),
+        }
+        Ok(())
     }
 
-    pub fn add(&mut self) -> () {
-        let rhs = self.stack.pop().expect("A value is expected in the stack!");
-        let lhs = self.stack.pop().expect("A value is expected in the stack!");
+    pub fn mul(&mut self) -> Result<(), JvmException> {
+        let rhs = self.pop();
+        let lhs = self.pop();
 
         match (lhs, rhs) {
             (JvmValue::Int { val: lhs_val }, JvmValue::Int { val: rhs_val }) => {
-                self.stack.push(JvmValue::Int {
-                    val: lhs_val + rhs_val,
-                })
+                self.i_constant(lhs_val.wrapping_mul(rhs_val))
+            }
+            (JvmValue::Long { val: lhs_val }, JvmValue::Long { val: rhs_val }) => {
+                self.l_constant(lhs_val.wrapping_mul(rhs_val))
             }
-            (lhs, rhs) => panic!(format!("Cannot add 2 values of type: {:?} {:?}", lhs, rhs)),
+            (JvmValue::Double { val: lhs_val }, JvmValue::Double { val: rhs_val }) => {
+                self.d_constant(lhs_val * rhs_val)
+            }
+            (JvmValue::Float { val: lhs_val }, JvmValue::Float { val: rhs_val }) => {
+                self.f_constant(lhs_val * rhs_val)
+            }
+            (lhs, rhs) => return Err(JvmException::from(format!("Cannot multiply 2 values of type: {:?} {:?}", lhs, rhs))),
         }
+        Ok(())
     }
 
-    pub fn mul(&mut self) -> () {
-        let rhs = self.stack.pop().expect("A value is expected in the stack!");
-        let lhs = self.stack.pop().expect("A value is expected in the stack!");
+    /// Integer division by zero results in a java/lang/ArithmeticException, dividing MIN_VALUE by -1 overflows to
+    /// MIN_VALUE as described for IDIV and LDIV.
+    pub fn div(&mut self) -> Result<(), JvmException> {
+        let rhs = self.pop();
+        let lhs = self.pop();
 
         match (lhs, rhs) {
+            (JvmValue::Int { .. }, JvmValue::Int { val: 0 }) | (JvmValue::Long { .. }, JvmValue::Long { val: 0 }) => {
+                return Err(JvmException::division_by_zero());
+            }
             (JvmValue::Int { val: lhs_val }, JvmValue::Int { val: rhs_val }) => {
-                self.stack.push(JvmValue::Int {
-                    val: lhs_val * rhs_val,
-                })
+                self.i_constant(lhs_val.wrapping_div(rhs_val))
+            }
+            (JvmValue::Long { val: lhs_val }, JvmValue::Long { val: rhs_val }) => {
+                self.l_constant(lhs_val.wrapping_div(rhs_val))
             }
-            (lhs, rhs) => panic!(format!("Cannot add 2 values of type: {:?} {:?}", lhs, rhs)),
+            (JvmValue::Double { val: lhs_val }, JvmValue::Double { val: rhs_val }) => {
+                self.d_constant(lhs_val / rhs_val)
+            }
+            (JvmValue::Float { val: lhs_val }, JvmValue::Float { val: rhs_val }) => {
+                self.f_constant(lhs_val / rhs_val)
+            }
+            (lhs, rhs) => return Err(JvmException::from(format!("Cannot divide 2 values of type: {:?} {:?}", lhs, rhs))),
         }
+        Ok(())
     }
 
-    pub fn i_constant(&mut self, constant: i32) -> () {
+    /// Floating point remainder is the truncating fmod, not the IEEE 754 remainder, see FREM and DREM.
+    pub fn rem(&mut self) -> Result<(), JvmException> {
+        let rhs = self.pop();
+        let lhs = self.pop();
+
+        match (lhs, rhs) {
+            (JvmValue::Int { .. }, JvmValue::Int { val: 0 }) | (JvmValue::Long { .. }, JvmValue::Long { val: 0 }) => {
+                return Err(JvmException::division_by_zero());
+            }
+            (JvmValue::Int { val: lhs_val }, JvmValue::Int { val: rhs_val }) => {
+                self.i_constant(lhs_val.wrapping_rem(rhs_val))
+            }
+            (JvmValue::Long { val: lhs_val }, JvmValue::Long { val: rhs_val }) => {
+                self.l_constant(lhs_val.wrapping_rem(rhs_val))
+            }
+            (JvmValue::Double { val: lhs_val }, JvmValue::Double { val: rhs_val }) => {
+                self.d_constant(lhs_val % rhs_val)
+            }
+            (JvmValue::Float { val: lhs_val }, JvmValue::Float { val: rhs_val }) => {
+                self.f_constant(lhs_val % rhs_val)
+            }
+            (lhs, rhs) => return Err(JvmException::from(format!("Cannot take the remainder of 2 values of type: {:?} {:?}", lhs, rhs))),
+        }
+        Ok(())
+    }
+
+    pub fn neg(&mut self) -> Result<(), JvmException> {
+        match self.pop() {
+            JvmValue::Int { val } => self.i_constant(val.wrapping_neg()),
+            JvmValue::Long { val } => self.l_constant(val.wrapping_neg()),
+            JvmValue::Float { val } => self.f_constant(-val),
+            JvmValue::Double { val } => self.d_constant(-val),
+            value => return Err(JvmException::from(format!("Cannot negate value of type: {:?}", value))),
+        }
+        Ok(())
+    }
+
+    /// Implements ISHL and LSHL, the shift distance is always an int of which only the low 5 (int) or 6 (long) bits are used.
+    pub fn shl(&mut self) -> Result<(), JvmException> {
+        self.shift(|val, distance| val.wrapping_shl(distance), |val, distance| val.wrapping_shl(distance))
+    }
+
+    /// Implements ISHR and LSHR, the sign bit is extended.
+    pub fn shr(&mut self) -> Result<(), JvmException> {
+        self.shift(|val, distance| val.wrapping_shr(distance), |val, distance| val.wrapping_shr(distance))
+    }
+
+    /// Implements IUSHR and LUSHR, zeros are shifted in.
+    pub fn ushr(&mut self) -> Result<(), JvmException> {
+        self.shift(|val, distance| (val as u32).wrapping_shr(distance) as i32,
+                   |val, distance| (val as u64).wrapping_shr(distance) as i64)
+    }
+
+    fn shift(&mut self, int_shift: fn(i32, u32) -> i32, long_shift: fn(i64, u32) -> i64) -> Result<(), JvmException> {
+        //wrapping shifts mask the distance with the bit width of the operand exactly as the JVMS requires
+        let distance = self.pop_int()? as u32;
+
+        match self.pop() {
+            JvmValue::Int { val } => self.i_constant(int_shift(val, distance)),
+            JvmValue::Long { val } => self.l_constant(long_shift(val, distance)),
+            value => return Err(JvmException::from(format!("Cannot shift value of type: {:?}", value))),
+        }
+        Ok(())
+    }
+
+    pub fn and(&mut self) -> Result<(), JvmException> {
+        self.bitwise(|lhs, rhs| lhs & rhs, |lhs, rhs| lhs & rhs)
+    }
+
+    pub fn or(&mut self) -> Result<(), JvmException> {
+        self.bitwise(|lhs, rhs| lhs | rhs, |lhs, rhs| lhs | rhs)
+    }
+
+    pub fn xor(&mut self) -> Result<(), JvmException> {
+        self.bitwise(|lhs, rhs| lhs ^ rhs, |lhs, rhs| lhs ^ rhs)
+    }
+
+    fn bitwise(&mut self, int_op: fn(i32, i32) -> i32, long_op: fn(i64, i64) -> i64) -> Result<(), JvmException> {
+        let rhs = self.pop();
+        let lhs = self.pop();
+
+        match (lhs, rhs) {
+            (JvmValue::Int { val: lhs_val }, JvmValue::Int { val: rhs_val }) => {
+                self.i_constant(int_op(lhs_val, rhs_val))
+            }
+            (JvmValue::Long { val: lhs_val }, JvmValue::Long { val: rhs_val }) => {
+                self.l_constant(long_op(lhs_val, rhs_val))
+            }
+            (lhs, rhs) => return Err(JvmException::from(format!("Cannot apply bitwise operation to values of type: {:?} {:?}", lhs, rhs))),
+        }
+        Ok(())
+    }
+
+    /// Implements LCMP, pushes 1, 0 or -1 depending on the comparison of the top two longs.
+    pub fn l_compare(&mut self) -> Result<(), JvmException> {
+        let rhs = self.pop_long()?;
+        let lhs = self.pop_long()?;
+
+        self.push_comparison(Some(lhs.cmp(&rhs)), 0);
+        Ok(())
+    }
+
+    /// Implements FCMPL and FCMPG, which only differ in the value pushed when any of the operands is NaN.
+    pub fn f_compare(&mut self, nan_result: i32) -> Result<(), JvmException> {
+        let rhs = self.pop_float()?;
+        let lhs = self.pop_float()?;
+
+        self.push_comparison(lhs.partial_cmp(&rhs), nan_result);
+        Ok(())
+    }
+
+    /// Implements DCMPL and DCMPG, which only differ in the value pushed when any of the operands is NaN.
+    pub fn d_compare(&mut self, nan_result: i32) -> Result<(), JvmException> {
+        let rhs = self.pop_double()?;
+        let lhs = self.pop_double()?;
+
+        self.push_comparison(lhs.partial_cmp(&rhs), nan_result);
+        Ok(())
+    }
+
+    fn push_comparison(&mut self, ordering: Option<Ordering>, nan_result: i32) {
+        self.i_constant(match ordering {
+            Some(Ordering::Greater) => 1,
+            Some(Ordering::Equal) => 0,
+            Some(Ordering::Less) => -1,
+            None => nan_result,
+        });
+    }
+
+    pub fn i_constant(&mut self, constant: i32) {
         self.stack.push(JvmValue::Int { val: constant })
     }
 
+    pub fn l_constant(&mut self, constant: i64) {
+        self.stack.push(JvmValue::Long { val: constant })
+    }
+
+    pub fn f_constant(&mut self, constant: f32) {
+        self.stack.push(JvmValue::Float { val: constant })
+    }
+
+    pub fn d_constant(&mut self, constant: f64) {
+        self.stack.push(JvmValue::Double { val: constant })
+    }
+
     pub fn pop(&mut self) -> JvmValue {
-        self.stack.pop().expect("Cannot pop from empty stack!")
+        let value = self.stack.pop().expect("Cannot pop from empty stack!");
+        if let JvmValue::ObjRef(ObjectRef::Ref(_)) = value {
+            self.operands.push(value.clone());
+        }
+        value
     }
 
     pub fn pop_int(&mut self) -> Result<i32, JvmException> {
@@ -61,6 +280,36 @@ impl EvaluationStack {
         }
     }
 
+    pub fn pop_long(&mut self) -> Result<i64, JvmException> {
+        match self.pop() {
+            JvmValue::Long { val } => Ok(val),
+            other => Err(JvmException::from(format!(
+                "JvmValue::Long expected but got: {:?}",
+                other
+            ))),
+        }
+    }
+
+    pub fn pop_float(&mut self) -> Result<f32, JvmException> {
+        match self.pop() {
+            JvmValue::Float { val } => Ok(val),
+            other => Err(JvmException::from(format!(
+                "JvmValue::Float expected but got: {:?}",
+                other
+            ))),
+        }
+    }
+
+    pub fn pop_double(&mut self) -> Result<f64, JvmException> {
+        match self.pop() {
+            JvmValue::Double { val } => Ok(val),
+            other => Err(JvmException::from(format!(
+                "JvmValue::Double expected but got: {:?}",
+                other
+            ))),
+        }
+    }
+
     pub fn pop_ref(&mut self) -> Result<ObjectRef, JvmException> {
         if let JvmValue::ObjRef(object_ref) = self.pop() {
             Ok(object_ref)
@@ -73,9 +322,58 @@ impl EvaluationStack {
         self.stack.push(value);
     }
 
+    /// Pops values occupying the given number of slots in the JVM Specification's stack model, where longs and
+    /// doubles take up two slots. The values are returned in the order they were pushed.
+    pub fn pop_slots(&mut self, slots: usize) -> Result<Vec<JvmValue>, JvmException> {
+        let mut values = Vec::new();
+        let mut popped_slots = 0;
+        while popped_slots < slots {
+            let value = self.pop();
+            popped_slots += value.category();
+            values.push(value);
+        }
+
+        if popped_slots > slots {
+            return Err(JvmException::from(format!("Cannot split a category 2 value when popping {} slots: {:?}", slots, values)));
+        }
+        values.reverse();
+        Ok(values)
+    }
+
+    pub fn push_all(&mut self, values: Vec<JvmValue>) {
+        self.stack.extend(values);
+    }
+
+    /// Duplicates the values occupying the top `slots` and inserts the copy beneath the next `depth` slots,
+    /// which covers DUP2 and all the DUP_X forms.
+    pub fn duplicate(&mut self, slots: usize, depth: usize) -> Result<(), JvmException> {
+        let duplicated = self.pop_slots(slots)?;
+        let skipped = self.pop_slots(depth)?;
+
+        self.push_all(duplicated.clone());
+        self.push_all(skipped);
+        self.push_all(duplicated);
+        Ok(())
+    }
+
+    /// Discards all values, used when control is transferred to an exception handler.
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
     pub fn stack(&self) -> &Vec<JvmValue> {
         &self.stack
     }
+
+    /// Forgets the references popped by the previous instruction.
+    pub fn start_instruction(&mut self) {
+        self.operands.clear();
+    }
+
+    /// The references popped since the current instruction started.
+    pub fn operands(&self) -> &Vec<JvmValue> {
+        &self.operands
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,8 @@
 use crate::share::interpreter::evaluation_stack::EvaluationStack;
-use crate::share::memory::oop::Oop::ObjectOop;
 use crate::share::utilities::jvm_exception::JvmException;
-use crate::share::utilities::jvm_value::{JvmValue, ObjectRef};
-use crate::share::utilities::jvm_value::ObjectRef::{Null, Ref};
-use crate::share::memory::oop::oops::ObjectOopDesc;
+use crate::share::utilities::jvm_value::JvmValue;
+use crate::share::utilities::jvm_value::ObjectRef::Null;
 use crate::share::utilities::testing;
-use crate::share::memory::heap::HeapWord;
 
 #[test]
 pub fn push_and_then_pop() {
@@ -58,7 +55,7 @@ pub fn add_i_constants() {
     stack_under_test.i_constant(2);
     stack_under_test.i_constant(3);
 
-    stack_under_test.add();
+    assert_eq!(Ok(()), stack_under_test.add());
 
     assert_eq!(Ok(5), stack_under_test.pop_int());
     assert_eq!(Ok(1), stack_under_test.pop_int());
@@ -78,7 +75,7 @@ pub fn pop_ref() {
         assert_eq!(Ok(object_ref), stack_under_test.pop_ref());
         return;
     }
-    assert!(false, "Wrong type!")
+    panic!("Wrong type!")
 }
 
 
@@ -89,3 +86,95 @@ pub fn pop_from_empty_stack() {
     stack_under_test.pop();
 }
 
+
+#[test]
+pub fn add_l_constants() {
+    let mut stack_under_test = EvaluationStack::new();
+    stack_under_test.l_constant(1);
+    stack_under_test.l_constant(2);
+
+    assert_eq!(Ok(()), stack_under_test.add());
+
+    assert_eq!(Ok(3), stack_under_test.pop_long());
+}
+
+#[test]
+pub fn pop_double_not_double_in_stack() {
+    let mut stack_under_test = EvaluationStack::new();
+    stack_under_test.l_constant(1);
+
+    assert_eq!(Err(JvmException::from("JvmValue::Double expected but got: Long { val: 1 }")), stack_under_test.pop_double());
+}
+
+#[test]
+pub fn add_mismatching_types() {
+    let mut stack_under_test = EvaluationStack::new();
+    stack_under_test.i_constant(1);
+    stack_under_test.l_constant(2);
+
+    assert_eq!(Err(JvmException::from("Cannot add 2 values of type: Int { val: 1 } Long { val: 2 }")), stack_under_test.add());
+}
+
+#[test]
+pub fn pop_slots_counts_category_2_values_twice() {
+    let mut stack_under_test = EvaluationStack::new();
+    stack_under_test.push(JvmValue::Int { val: 1 });
+    stack_under_test.push(JvmValue::Int { val: 2 });
+    stack_under_test.push(JvmValue::Long { val: 3 });
+
+    assert_eq!(Ok(vec![JvmValue::Long { val: 3 }]), stack_under_test.pop_slots(2));
+    assert_eq!(Ok(vec![JvmValue::Int { val: 1 }, JvmValue::Int { val: 2 }]), stack_under_test.pop_slots(2));
+}
+
+#[test]
+pub fn pop_slots_cannot_split_category_2_values() {
+    let mut stack_under_test = EvaluationStack::new();
+    stack_under_test.push(JvmValue::Double { val: 1.0 });
+
+    assert!(stack_under_test.pop_slots(1).is_err());
+}
+
+fn duplicate(stack: Vec<JvmValue>, slots: usize, depth: usize) -> Vec<JvmValue> {
+    let mut stack_under_test = EvaluationStack::new();
+    stack_under_test.push_all(stack);
+    assert_eq!(Ok(()), stack_under_test.duplicate(slots, depth));
+    stack_under_test.stack().clone()
+}
+
+#[test]
+pub fn duplicate_forms() {
+    let (v1, v2, v3, v4) = (JvmValue::Int { val: 1 }, JvmValue::Int { val: 2 }, JvmValue::Int { val: 3 }, JvmValue::Int { val: 4 });
+    let (l1, l2) = (JvmValue::Long { val: 5 }, JvmValue::Double { val: 6.0 });
+
+    //DUP_X1
+    assert_eq!(vec![v1.clone(), v2.clone(), v1.clone()], duplicate(vec![v2.clone(), v1.clone()], 1, 1));
+    //DUP_X2 form 1 and 2
+    assert_eq!(vec![v1.clone(), v3.clone(), v2.clone(), v1.clone()], duplicate(vec![v3.clone(), v2.clone(), v1.clone()], 1, 2));
+    assert_eq!(vec![v1.clone(), l2.clone(), v1.clone()], duplicate(vec![l2.clone(), v1.clone()], 1, 2));
+    //DUP2 form 1 and 2
+    assert_eq!(vec![v2.clone(), v1.clone(), v2.clone(), v1.clone()], duplicate(vec![v2.clone(), v1.clone()], 2, 0));
+    assert_eq!(vec![l1.clone(), l1.clone()], duplicate(vec![l1.clone()], 2, 0));
+    //DUP2_X1 form 1 and 2
+    assert_eq!(vec![v2.clone(), v1.clone(), v3.clone(), v2.clone(), v1.clone()], duplicate(vec![v3.clone(), v2.clone(), v1.clone()], 2, 1));
+    assert_eq!(vec![l1.clone(), v2.clone(), l1.clone()], duplicate(vec![v2.clone(), l1.clone()], 2, 1));
+    //DUP2_X2 forms 1 to 4
+    assert_eq!(vec![v2.clone(), v1.clone(), v4.clone(), v3.clone(), v2.clone(), v1.clone()],
+               duplicate(vec![v4.clone(), v3.clone(), v2.clone(), v1.clone()], 2, 2));
+    assert_eq!(vec![l1.clone(), v3.clone(), v2.clone(), l1.clone()], duplicate(vec![v3.clone(), v2.clone(), l1.clone()], 2, 2));
+    assert_eq!(vec![v2.clone(), v1.clone(), l2.clone(), v2.clone(), v1.clone()], duplicate(vec![l2.clone(), v2.clone(), v1.clone()], 2, 2));
+    assert_eq!(vec![l1.clone(), l2.clone(), l1.clone()], duplicate(vec![l2, l1], 2, 2));
+}
+
+#[test]
+pub fn popped_references_are_operands_until_the_next_instruction() {
+    let mut stack_under_test = EvaluationStack::new();
+    stack_under_test.push(testing::test_object_ref());
+    stack_under_test.push(JvmValue::null_obj());
+    stack_under_test.i_constant(1);
+
+    stack_under_test.pop_slots(3).unwrap();
+    assert_eq!(&vec![testing::test_object_ref()], stack_under_test.operands());
+
+    stack_under_test.start_instruction();
+    assert!(stack_under_test.operands().is_empty());
+}
@@ -1,17 +1,25 @@
+use crate::share::classfile::attribute::ExceptionHandler;
 use crate::share::classfile::constant_pool::{CpInfo, Qualifier};
+use crate::share::classfile::klass::Klass;
+use crate::share::classfile::method::MethodInfo;
 use crate::share::interpreter::evaluation_stack::EvaluationStack;
 use crate::share::interpreter::local_variables::JvmLocalVariableStore;
 use crate::share::interpreter::opcode;
+use crate::share::runtime::safepoint::Safepoint;
 use crate::share::runtime::stack_frame::JvmStackFrame;
+use crate::share::runtime::thread::{JavaThread, PublishedFrameRoots};
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType, ObjectRef};
 use crate::share::utilities::jvm_value::JvmValue::ObjRef;
 use crate::share::utilities::jvm_value::ObjectRef::Ref;
-use crate::share::native::native_helper_classes::java_lang_String;
+use crate::share::memory::heap::HeapWord;
+use crate::share::native::native_helper_classes::{java_lang_String, java_lang_Throwable};
+use crate::share::memory::oop::oops::ObjectOopDesc;
 use crate::share::utilities::global_symbols::Symbols;
 use std::ops::Deref;
 use crate::share::memory::oop::Oop;
-use std::mem;
+use std::cell::Cell;
+use std::sync::Arc;
 
 
 #[cfg(test)]
@@ -32,7 +40,14 @@ pub struct Interpreter<'a> {
     byte_codes: &'a Vec<u8>,
     local_variables: &'a mut dyn JvmLocalVariableStore,
     ip: usize,
+    //the start of the currently executed instruction, the pc exception handlers are matched against
+    pc: usize,
     eval_stack: EvaluationStack,
+    exception_table: &'a [ExceptionHandler],
+    //set by WIDE, the next instruction reads a 16 bit local variable index
+    wide: bool,
+    //whether the current instruction has already forgotten the recent allocations of the thread
+    recent_allocations_forgotten: Cell<bool>,
 }
 
 #[cfg(test)]
@@ -42,7 +57,7 @@ impl<'a> Interpreter<'a> {
     }
 
     pub fn set_stack_contents(&mut self, mut contents: Vec<JvmValue>) {
-        mem::swap(self.eval_stack.stack_mut(), &mut contents);
+        std::mem::swap(self.eval_stack.stack_mut(), &mut contents);
     }
 }
 
@@ -55,265 +70,728 @@ impl<'a> Interpreter<'a> {
             byte_codes,
             local_variables,
             ip: 0,
+            pc: 0,
             eval_stack: EvaluationStack::new(),
+            exception_table: &[],
+            wide: false,
+            recent_allocations_forgotten: Cell::new(false),
         }
     }
 
+    pub fn with_exception_table(mut self, exception_table: &'a [ExceptionHandler]) -> Interpreter<'a> {
+        self.exception_table = exception_table;
+        self
+    }
+
     pub fn interpret(current_frame: &impl JvmStackFrame,
                      byte_codes: &Vec<u8>,
                      local_variables: &mut impl JvmLocalVariableStore) -> Result<JvmValue, JvmException> {
         Interpreter::new(current_frame, byte_codes, local_variables).do_interpret()
     }
 
+    /// Executes the byte codes and dispatches thrown exceptions to the matching handler of the exception table.
+    /// Exceptions without a handler in this method are returned to the caller frame, unwinding the stack.
     pub fn do_interpret(&mut self) -> Result<JvmValue, JvmException> {
+        loop {
+            match self.execute_byte_codes() {
+                Err(exception) if exception.is_java_exception() => {
+                    let exception = self.materialize_exception(exception)?;
+                    let _frame_roots = self.publish_frame_roots(exception.throwable().cloned().map(JvmValue::from));
+                    match self.find_exception_handler(&exception)? {
+                        Some(handler_pc) => {
+                            let throwable = exception.throwable().unwrap().clone();
+                            self.eval_stack.clear();
+                            self.eval_stack.push(JvmValue::from(throwable));
+                            self.ip = handler_pc;
+                        }
+                        None => return Err(exception),
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Allocates the java/lang/Throwable instance for exceptions raised by the VM itself, e.g. NullPointerException.
+    /// Similarly to HotSpot no constructor is invoked, only the detail message is set.
+    fn materialize_exception(&self, exception: JvmException) -> Result<JvmException, JvmException> {
+        if exception.throwable().is_some() {
+            return Ok(exception);
+        }
+
+        let _cause_roots = self.publish_frame_roots(exception.cause().cloned().map(JvmValue::from));
+        let exception_class = self.call_out(|frame| frame.class_loader().load_and_init_class(exception.exception_class().unwrap()))?;
+        let throwable = self.call_out(|frame| frame.heap().allocate_object(exception_class))?;
+        let _throwable_roots = self.publish_frame_roots(Some(JvmValue::from(throwable.clone())));
+
+        if let Some(message) = exception.message() {
+            let detail_message = self.allocate_string(message.clone())?;
+            java_lang_Throwable::put_detail_message(self.current_frame.heap().as_ref(), throwable.clone(), detail_message)?;
+        }
+        if let Some(cause) = exception.cause() {
+            java_lang_Throwable::put_cause(self.current_frame.heap().as_ref(), throwable.clone(), cause.clone())?;
+        }
+
+        Ok(exception.with_throwable(Oop::ObjectOop(throwable)))
+    }
+
+    /// Passes a reference stored into a field or array element of the given object to the write barrier of the heap.
+    fn write_barrier(&self, holder: &HeapWord, value: &JvmValue) {
+        if let JvmValue::ObjRef(Ref(_)) = value {
+            self.current_frame.heap().write_barrier(holder, value);
+        }
+    }
+
+    /// Publishes the values of this frame, together with the given one, as roots of the garbage collector until
+    /// the returned guard is dropped.
+    fn publish_frame_roots(&self, extra_root: Option<JvmValue>) -> PublishedFrameRoots {
+        JavaThread::publish_frame_roots(|| {
+            let mut roots = self.local_variables.values();
+            roots.extend(self.eval_stack.stack().iter().cloned());
+            roots.extend(self.eval_stack.operands().iter().cloned());
+            roots.extend(extra_root);
+            roots
+        })
+    }
+
+    /// Calls code outside of the interpreter which may collect garbage: allocations, class loading and
+    /// initialization, method invocations and blocking on monitors all have to go through here. Meanwhile the
+    /// values of this frame are published as roots, so whatever this thread allocated before is referenced by them.
+    fn call_out<T>(&self, call: impl FnOnce(&dyn JvmStackFrame) -> T) -> T {
+        let _frame_roots = self.publish_frame_roots(None);
+        //allocations of the current instruction may not be referenced by the frame yet, e.g. the outer
+        //dimensions of MULTIANEWARRAY, so only the ones of the previous instructions are forgotten
+        if !self.recent_allocations_forgotten.replace(true) {
+            self.current_frame.heap().forget_recent_allocations();
+        }
+        call(self.current_frame)
+    }
+
+    /// Pushes the int, float, String or Class constant of LDC and LDC_W, they only differ in the size of the index.
+    fn load_constant(&mut self, index: u16) -> Result<(), JvmException> {
+        match self.current_frame.constant_pool().get(index as usize) {
+            CpInfo::Integer { bytes } => self.eval_stack.push(JvmValue::Int { val: *bytes as i32 }),
+            CpInfo::Float { bytes } => self.eval_stack.push(JvmValue::Float { val: f32::from_bits(*bytes) }),
+            CpInfo::String { string_index } => {
+                let string_contents = self.current_frame.constant_pool().get_utf8(*string_index as usize)
+                    .expect("No String reference was found!");
+
+                let string_ref = self.allocate_string(string_contents)?;
+                self.eval_stack.push(JvmValue::from(string_ref));
+            }
+            CpInfo::Class { .. } => {
+                let qualifier = self.current_frame.constant_pool().get_qualified_name(index);
+
+                let klass = self.call_out(|frame| frame.class_loader().load_class(&qualifier))?;
+
+                self.eval_stack.push(JvmValue::from(klass.get_java_mirror()));
+            }
+            invalid => return Err(JvmException::from(format!("LDC index should refer to an int, float, String or Class not a {:?}", invalid))),
+        }
+        Ok(())
+    }
+
+    fn allocate_string(&self, contents: String) -> Result<ObjectOopDesc, JvmException> {
+        let string_klass = self.call_out(|frame| frame.class_loader().load_and_init_class(&Symbols::java_lang_String))?;
+
+        self.call_out(|frame| java_lang_String::allocate(frame.heap().deref(), string_klass, contents))
+    }
+
+    /// Returns the pc of the first handler covering the current instruction whose catch type is a superclass
+    /// of the thrown exception, see 2.10 and 3.12 of the JVM Specification.
+    fn find_exception_handler(&self, exception: &JvmException) -> Result<Option<usize>, JvmException> {
+        let thrown_class = exception.throwable().unwrap().java_klass_or_fail();
+
+        for handler in self.exception_table {
+            if self.pc < handler.start_pc as usize || self.pc >= handler.end_pc as usize {
+                continue;
+            }
+
+            //catch_type of 0 is used for finally blocks and matches every exception
+            if handler.catch_type == 0 {
+                return Ok(Some(handler.handler_pc as usize));
+            }
+
+            let catch_type = match self.current_frame.constant_pool().get_qualified_name(handler.catch_type) {
+                Qualifier::Class { name } => name,
+                other => return Err(JvmException::from(format!("Expected Class as catch_type, but got {:?}", other))),
+            };
+
+            let catch_class = self.call_out(|frame| frame.class_loader().load_class(&Qualifier::Class { name: catch_type }))?;
+
+            if thrown_class.is_subclass_of(&catch_class) {
+                return Ok(Some(handler.handler_pc as usize));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decides whether the given object is an instance of the named class, interface or array type, following
+    /// the rules of CHECKCAST and INSTANCEOF in 6.5 Section of JVM Specification.
+    fn is_instance_of(&self, object: &Oop, type_name: &String) -> Result<bool, JvmException> {
+        let component_type = match type_name.strip_prefix('[') {
+            Some(component_type) => component_type,
+            None if !matches!(object, Oop::ObjectOop(_)) => {
+                //arrays implement only java/lang/Cloneable and java/io/Serializable
+                return Ok(*type_name == *Symbols::java_lang_Object
+                    || *type_name == *Symbols::java_lang_Cloneable
+                    || *type_name == *Symbols::java_io_Serializable);
+            }
+            None => {
+                let target_class = self.call_out(|frame| frame.class_loader().load_class(&Qualifier::Class { name: type_name.clone() }))?;
+                return Ok(object.java_klass_or_fail().is_assignable_to(&target_class));
+            }
+        };
+
+        match object {
+            Oop::ObjectOop(_) => Ok(false),
+            Oop::PrimitiveArrayOop(array) => Ok(component_type.len() == 1 && component_type.starts_with(array.inner_type.descriptor())),
+            //arrays of arrays are only known to have java/lang/Object as component class
+            Oop::ArrayOop(array) if component_type.starts_with('[') => Ok(array.klass().qualified_name() == *Symbols::java_lang_Object),
+            Oop::ArrayOop(array) if component_type.starts_with('L') => {
+                let component_class = self.array_component_class(component_type)?;
+                Ok(array.klass().is_assignable_to(&component_class))
+            }
+            Oop::ArrayOop(_) => Ok(false),
+        }
+    }
+
+    /// The binary name of the class of an object as it appears in the message of a ClassCastException.
+    fn class_name_of(object: &Oop) -> String {
+        match object {
+            Oop::ObjectOop(object) => object.klass().qualified_name(),
+            Oop::ArrayOop(array) => format!("[L{};", array.klass().qualified_name()),
+            Oop::PrimitiveArrayOop(array) => format!("[{}", array.inner_type.descriptor()),
+        }
+    }
+
+    fn pop_arguments_with_receiver(&mut self, method: &MethodInfo) -> Vec<JvmValue> {
+        let number_of_parameters = method.number_of_parameters() as usize + 1;
+
+        let mut args: Vec<JvmValue> = (0..number_of_parameters).map(|_| self.eval_stack.pop()).collect();
+        args.reverse();
+        args
+    }
+
+    /// The class methods are selected from for the receiver of an instance method invocation. Arrays inherit
+    /// all their methods from java/lang/Object.
+    fn receiver_class(&self, receiver: &JvmValue) -> Result<Arc<Klass>, JvmException> {
+        match receiver {
+            ObjRef(object_ref) => match object_ref.dereference()? {
+                Oop::ObjectOop(object) => Ok(object.klass()),
+                Oop::ArrayOop(_) | Oop::PrimitiveArrayOop(_) => {
+                    self.call_out(|frame| frame.class_loader().load_and_init_class(&Symbols::java_lang_Object))
+                }
+            },
+            invalid => Err(JvmException::from(format!("Receiver should be a Reference, but was {:?}", invalid)))
+        }
+    }
+
+    /// Checks the method selected for the receiver of INVOKEVIRTUAL or INVOKEINTERFACE: no method is selected if the
+    /// receiver's class doesn't inherit the resolved method, and an abstract one if it doesn't implement it.
+    fn implementation_of(selected_method: Option<Arc<MethodInfo>>, resolved_method: &MethodInfo, receiver_class: &Klass) -> Result<Arc<MethodInfo>, JvmException> {
+        match selected_method {
+            Some(method) if method.is_abstract() => Err(JvmException::abstract_method(&format!(
+                "Receiver class {} does not define or inherit an implementation of the resolved method {}",
+                receiver_class.qualified_name(), resolved_method))),
+            Some(method) => Ok(method),
+            None => Err(JvmException::incompatible_class_change(format!(
+                "Class {} does not implement the requested method {}", receiver_class.qualified_name(), resolved_method))),
+        }
+    }
+
+    /// Implements the method selection of INVOKESPECIAL: methods referenced through a superclass of the current
+    /// class, other than instance initializers, are looked up starting from the direct superclass of the current
+    /// class. Since Java SE 8 every class is treated as if ACC_SUPER was set.
+    fn select_special_method(&self, resolved_method: Arc<MethodInfo>, referenced_class_name: &String) -> Result<Arc<MethodInfo>, JvmException> {
+        let current_class = self.current_frame.current_class();
+        if resolved_method.is_instance_initializer() || current_class.qualified_name() == *referenced_class_name {
+            return Ok(resolved_method);
+        }
+
+        let referenced_class = self.call_out(|frame| {
+            frame.class_loader().load_class(&Qualifier::Class { name: referenced_class_name.clone() })
+        })?;
+
+        match current_class.super_class() {
+            Some(super_class) if !referenced_class.is_interface() && super_class.is_subclass_of(&referenced_class) => {
+                Ok(super_class.resolve_method(&resolved_method.name_desc()).unwrap_or(resolved_method))
+            }
+            _ => Ok(resolved_method)
+        }
+    }
+
+    /// Pops an index and a primitive array, then pushes the element at that index widened to its
+    /// computational type.
+    fn primitive_array_load(&mut self) -> Result<(), JvmException> {
+        let index = self.eval_stack.pop_int()?;
+        let array = self.eval_stack.pop_ref()?.dereference()?;
+        array.check_array_index(index)?;
+
+        let element = array.instance_data().get_field(index as usize)?;
+        self.eval_stack.push(element.widen());
+        Ok(())
+    }
+
+    /// Pops a value, an index and a primitive array, then stores the value narrowed to the element type of
+    /// the array at that index.
+    fn primitive_array_store(&mut self) -> Result<(), JvmException> {
+        let value = self.eval_stack.pop();
+        let index = self.eval_stack.pop_int()?;
+        let array = self.eval_stack.pop_ref()?.dereference()?;
+        array.check_array_index(index)?;
+
+        let element_type = match &array {
+            Oop::PrimitiveArrayOop(primitive_array) => &primitive_array.inner_type,
+            other => return Err(JvmException::from(format!("Expected primitive array, but got {:?}", other))),
+        };
+        array.instance_data().put_field(index as usize, value.narrow_to(element_type))
+    }
+
+    /// Only references assignable to the component type can be stored in an array of references, otherwise
+    /// AASTORE throws ArrayStoreException.
+    fn check_array_store(&self, array: &Oop, value: &JvmValue) -> Result<(), JvmException> {
+        let component_class = match array {
+            Oop::ArrayOop(array) => array.klass(),
+            other => return Err(JvmException::from(format!("Expected array of references, but got {:?}", other))),
+        };
+
+        let stored_object = match value {
+            JvmValue::ObjRef(Ref(object)) => object,
+            JvmValue::ObjRef(ObjectRef::Null) => return Ok(()),
+            other => return Err(JvmException::from(format!("Expected Reference to store, but got {:?}", other))),
+        };
+
+        let is_assignable = match stored_object {
+            Oop::ObjectOop(object) => object.klass().is_assignable_to(&component_class),
+            Oop::ArrayOop(_) | Oop::PrimitiveArrayOop(_) => component_class.qualified_name() == *Symbols::java_lang_Object
+                || component_class.qualified_name() == *Symbols::java_lang_Cloneable
+                || component_class.qualified_name() == *Symbols::java_io_Serializable,
+        };
+
+        if !is_assignable {
+            return Err(JvmException::array_store(&Interpreter::class_name_of(stored_object)));
+        }
+        Ok(())
+    }
+
+    /// The class of the elements of a reference array. Arrays don't have classes of their own yet, so arrays
+    /// of arrays are represented with java/lang/Object as their component class.
+    fn array_component_class(&self, component_type: &str) -> Result<Arc<Klass>, JvmException> {
+        let component_class_name = if component_type.starts_with('[') {
+            &*Symbols::java_lang_Object
+        } else {
+            component_type.strip_prefix('L').and_then(|name| name.strip_suffix(';')).unwrap_or(component_type)
+        };
+
+        self.call_out(|frame| frame.class_loader().load_class(&Qualifier::Class { name: String::from(component_class_name) }))
+    }
+
+    /// Allocates an array of the given array type descriptor whose first dimensions have the given lengths,
+    /// the remaining dimensions are left as null, see MULTIANEWARRAY in 6.5 Section of JVM Specification.
+    fn allocate_multi_array(&self, array_type: &str, counts: &[i32]) -> Result<JvmValue, JvmException> {
+        let component_type = array_type.strip_prefix('[')
+            .ok_or(JvmException::from(format!("Expected array type, but got {}", array_type)))?;
+
+        if let Some(primitive_type) = PrimitiveType::from_descriptor(component_type) {
+            let array = self.call_out(|frame| frame.heap().allocate_primitive_array(primitive_type, counts[0]))?;
+            return Ok(JvmValue::from(array));
+        }
+
+        let component_class = self.array_component_class(component_type)?;
+        let array = self.call_out(|frame| frame.heap().allocate_array(component_class, counts[0]))?;
+
+        if counts.len() > 1 {
+            for index in 0..counts[0] {
+                let sub_array = self.allocate_multi_array(component_type, &counts[1..])?;
+                array.instance_data.put_field(index as usize, sub_array.clone())?;
+                self.write_barrier(&array.instance_data, &sub_array);
+            }
+        }
+        Ok(JvmValue::from(array))
+    }
+
+    fn invoke(&mut self, method: Arc<MethodInfo>, args: Vec<JvmValue>) -> Result<(), JvmException> {
+        let void_method = method.is_void();
+        let method_return_value = self.call_out(|frame| frame.execute_method(method, args))?;
+
+        if !void_method {
+            self.eval_stack.push(method_return_value);
+        }
+        Ok(())
+    }
+
+    fn execute_byte_codes(&mut self) -> Result<JvmValue, JvmException> {
         {
             loop {
+                if !self.wide {
+                    self.pc = self.ip;
+                    self.eval_stack.start_instruction();
+                    self.recent_allocations_forgotten.set(false);
+                }
+                if Safepoint::is_requested() {
+                    let _frame_roots = self.publish_frame_roots(None);
+                    Safepoint::block();
+                }
+
                 match self.byte_codes.get(self.ip) {
-                    Some(byte_code) => match byte_code {
-                        &opcode::NOP => {}
-                        &opcode::ACONST_NULL => self.eval_stack.push(JvmValue::null_obj()),
-                        &opcode::ICONST_M1 => self.eval_stack.i_constant(-1),
-                        &opcode::ICONST_0 => self.eval_stack.i_constant(0),
-                        &opcode::ICONST_1 => self.eval_stack.i_constant(1),
-                        &opcode::ICONST_2 => self.eval_stack.i_constant(2),
-                        &opcode::ICONST_3 => self.eval_stack.i_constant(3),
-                        &opcode::ICONST_4 => self.eval_stack.i_constant(4),
-                        &opcode::ICONST_5 => self.eval_stack.i_constant(5),
-                        &opcode::LCONST_0 => panic!("UnImplemented byte-code: LCONST_0"),
-                        &opcode::LCONST_1 => panic!("UnImplemented byte-code: LCONST_1"),
-                        &opcode::FCONST_0 => panic!("UnImplemented byte-code: FCONST_0"),
-                        &opcode::FCONST_1 => panic!("UnImplemented byte-code: FCONST_1"),
-                        &opcode::FCONST_2 => panic!("UnImplemented byte-code: FCONST_2"),
-                        &opcode::DCONST_0 => panic!("UnImplemented byte-code: DCONST_0"),
-                        &opcode::DCONST_1 => panic!("UnImplemented byte-code: DCONST_1"),
-                        &opcode::BIPUSH => self.eval_stack.push(JvmValue::Int {
-                            val: read_u8(self.byte_codes, &mut self.ip) as i32,
+                    Some(byte_code) => match *byte_code {
+                        opcode::NOP => {}
+                        opcode::ACONST_NULL => self.eval_stack.push(JvmValue::null_obj()),
+                        opcode::ICONST_M1 => self.eval_stack.i_constant(-1),
+                        opcode::ICONST_0 => self.eval_stack.i_constant(0),
+                        opcode::ICONST_1 => self.eval_stack.i_constant(1),
+                        opcode::ICONST_2 => self.eval_stack.i_constant(2),
+                        opcode::ICONST_3 => self.eval_stack.i_constant(3),
+                        opcode::ICONST_4 => self.eval_stack.i_constant(4),
+                        opcode::ICONST_5 => self.eval_stack.i_constant(5),
+                        opcode::LCONST_0 => self.eval_stack.l_constant(0),
+                        opcode::LCONST_1 => self.eval_stack.l_constant(1),
+                        opcode::FCONST_0 => self.eval_stack.f_constant(0.0),
+                        opcode::FCONST_1 => self.eval_stack.f_constant(1.0),
+                        opcode::FCONST_2 => self.eval_stack.f_constant(2.0),
+                        opcode::DCONST_0 => self.eval_stack.d_constant(0.0),
+                        opcode::DCONST_1 => self.eval_stack.d_constant(1.0),
+                        opcode::BIPUSH => self.eval_stack.push(JvmValue::Int {
+                            val: read_u8(self.byte_codes, &mut self.ip) as i8 as i32,
+                        }),
+                        opcode::SIPUSH => self.eval_stack.push(JvmValue::Int {
+                            val: read_u16(self.byte_codes, &mut self.ip) as i16 as i32,
                         }),
-                        &opcode::SIPUSH => panic!("UnImplemented byte-code: SIPUSH"),
-                        &opcode::LDC => {
+                        opcode::LDC => {
                             let index = read_u8(self.byte_codes, &mut self.ip);
+                            self.load_constant(index as u16)?;
+                        }
+                        opcode::LDC_W => {
+                            let index = read_u16(self.byte_codes, &mut self.ip);
+                            self.load_constant(index)?;
+                        }
+                        opcode::LDC2_W => {
+                            let index = read_u16(self.byte_codes, &mut self.ip);
                             let referenced_cp_entry = self.current_frame.constant_pool().get(index as usize);
 
                             match referenced_cp_entry {
-                                CpInfo::Integer { bytes } => self.eval_stack.push(JvmValue::Int { val: bytes.clone() as i32 }),
-                                CpInfo::Float { bytes } => self.eval_stack.push(JvmValue::Float { val: f32::from_bits(bytes.clone()) }),
-                                CpInfo::String { string_index } => {
-                                    let string_klass = self.current_frame.class_loader()
-                                        .load_and_init_class(&Symbols::java_lang_String)?;
-
-                                    let string_ref = self.current_frame.heap()
-                                        .allocate_object(string_klass)?;
-
-                                    let string_contents = self.current_frame.constant_pool().get_utf8(string_index.clone() as usize)
-                                        .map(|str| str.into_bytes())
-                                        .expect("No String reference was found!");
-
-                                    let buffer = self.current_frame.heap().allocate_primitive_array(PrimitiveType::Byte, string_contents.len() as i32)?;
-                                    buffer.copy_bytes(self.current_frame.heap().deref(), string_contents);
-                                    java_lang_String::put_buffer(string_ref.clone(), buffer)?;
-                                    self.eval_stack.push(JvmValue::from(string_ref));
-                                }
-                                CpInfo::Class { name_index: _ } => {
-                                    let qualifier = self.current_frame.constant_pool().get_qualified_name(index as u16);
-
-                                    let klass = self.current_frame
-                                        .class_loader()
-                                        .load_class(&qualifier)?;
-
-                                    self.eval_stack.push(JvmValue::from(klass.get_java_mirror()));
-                                }
-                                unknown => panic!("Unknown tag: {:?}", unknown),
-                                _ => {
-                                    let qualifier = self.current_frame.constant_pool().get_qualified_name(index as u16);
-
-                                    let klass = self.current_frame
-                                        .class_loader()
-                                        .load_class(&qualifier)?;
-
-                                    self.eval_stack.push(JvmValue::from(klass.get_java_mirror()));
-                                }
+                                CpInfo::Long { high_bytes, low_bytes } => self.eval_stack.push(JvmValue::Long {
+                                    val: ((*high_bytes as u64) << 32 | *low_bytes as u64) as i64
+                                }),
+                                CpInfo::Double { high_bytes, low_bytes } => self.eval_stack.push(JvmValue::Double {
+                                    val: f64::from_bits((*high_bytes as u64) << 32 | *low_bytes as u64)
+                                }),
+                                invalid => return Err(JvmException::from(format!("LDC2_W index should refer to a long or double not a {:?}", invalid)))
                             }
                         }
-                        &opcode::LDC_W => panic!("UnImplemented byte-code: LDC_W"),
-                        &opcode::LDC2_W => panic!("UnImplemented byte-code: LDC2_W"),
-                        &opcode::ILOAD => {
-                            self.eval_stack.push(self.local_variables.load(read_u8(self.byte_codes, &mut self.ip)))
-                        }
-                        &opcode::LLOAD => panic!("UnImplemented byte-code: LLOAD"),
-                        &opcode::FLOAD => panic!("UnImplemented byte-code: FLOAD"),
-                        &opcode::DLOAD => panic!("UnImplemented byte-code: DLOAD"),
-                        &opcode::ALOAD => panic!("UnImplemented byte-code: ALOAD"),
-                        &opcode::ILOAD_0 => self.eval_stack.push(self.local_variables.load(0)),
-                        &opcode::ILOAD_1 => self.eval_stack.push(self.local_variables.load(1)),
-                        &opcode::ILOAD_2 => self.eval_stack.push(self.local_variables.load(2)),
-                        &opcode::ILOAD_3 => self.eval_stack.push(self.local_variables.load(3)),
-                        &opcode::LLOAD_0 => panic!("UnImplemented byte-code: LLOAD_0"),
-                        &opcode::LLOAD_1 => panic!("UnImplemented byte-code: LLOAD_1"),
-                        &opcode::LLOAD_2 => panic!("UnImplemented byte-code: LLOAD_2"),
-                        &opcode::LLOAD_3 => panic!("UnImplemented byte-code: LLOAD_3"),
-                        &opcode::FLOAD_0 => panic!("UnImplemented byte-code: FLOAD_0"),
-                        &opcode::FLOAD_1 => panic!("UnImplemented byte-code: FLOAD_1"),
-                        &opcode::FLOAD_2 => panic!("UnImplemented byte-code: FLOAD_2"),
-                        &opcode::FLOAD_3 => panic!("UnImplemented byte-code: FLOAD_3"),
-                        &opcode::DLOAD_0 => panic!("UnImplemented byte-code: DLOAD_0"),
-                        &opcode::DLOAD_1 => panic!("UnImplemented byte-code: DLOAD_1"),
-                        &opcode::DLOAD_2 => panic!("UnImplemented byte-code: DLOAD_2"),
-                        &opcode::DLOAD_3 => panic!("UnImplemented byte-code: DLOAD_3"),
-                        &opcode::ALOAD_0 => self.eval_stack.push(self.local_variables.load(0)),
-                        &opcode::ALOAD_1 => self.eval_stack.push(self.local_variables.load(1)),
-                        &opcode::ALOAD_2 => self.eval_stack.push(self.local_variables.load(2)),
-                        &opcode::ALOAD_3 => self.eval_stack.push(self.local_variables.load(3)),
-                        &opcode::IALOAD => panic!("UnImplemented byte-code: IALOAD"),
-                        &opcode::LALOAD => panic!("UnImplemented byte-code: LALOAD"),
-                        &opcode::FALOAD => panic!("UnImplemented byte-code: FALOAD"),
-                        &opcode::DALOAD => panic!("UnImplemented byte-code: DALOAD"),
-                        &opcode::AALOAD => {
+                        opcode::ILOAD => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.eval_stack.push(self.local_variables.load(index))
+                        }
+                        opcode::LLOAD => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.eval_stack.push(self.local_variables.load(index))
+                        }
+                        opcode::FLOAD => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.eval_stack.push(self.local_variables.load(index))
+                        }
+                        opcode::DLOAD => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.eval_stack.push(self.local_variables.load(index))
+                        }
+                        opcode::ALOAD => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.eval_stack.push(self.local_variables.load(index))
+                        }
+                        opcode::ILOAD_0 => self.eval_stack.push(self.local_variables.load(0)),
+                        opcode::ILOAD_1 => self.eval_stack.push(self.local_variables.load(1)),
+                        opcode::ILOAD_2 => self.eval_stack.push(self.local_variables.load(2)),
+                        opcode::ILOAD_3 => self.eval_stack.push(self.local_variables.load(3)),
+                        opcode::LLOAD_0 => self.eval_stack.push(self.local_variables.load(0)),
+                        opcode::LLOAD_1 => self.eval_stack.push(self.local_variables.load(1)),
+                        opcode::LLOAD_2 => self.eval_stack.push(self.local_variables.load(2)),
+                        opcode::LLOAD_3 => self.eval_stack.push(self.local_variables.load(3)),
+                        opcode::FLOAD_0 => self.eval_stack.push(self.local_variables.load(0)),
+                        opcode::FLOAD_1 => self.eval_stack.push(self.local_variables.load(1)),
+                        opcode::FLOAD_2 => self.eval_stack.push(self.local_variables.load(2)),
+                        opcode::FLOAD_3 => self.eval_stack.push(self.local_variables.load(3)),
+                        opcode::DLOAD_0 => self.eval_stack.push(self.local_variables.load(0)),
+                        opcode::DLOAD_1 => self.eval_stack.push(self.local_variables.load(1)),
+                        opcode::DLOAD_2 => self.eval_stack.push(self.local_variables.load(2)),
+                        opcode::DLOAD_3 => self.eval_stack.push(self.local_variables.load(3)),
+                        opcode::ALOAD_0 => self.eval_stack.push(self.local_variables.load(0)),
+                        opcode::ALOAD_1 => self.eval_stack.push(self.local_variables.load(1)),
+                        opcode::ALOAD_2 => self.eval_stack.push(self.local_variables.load(2)),
+                        opcode::ALOAD_3 => self.eval_stack.push(self.local_variables.load(3)),
+                        opcode::IALOAD => self.primitive_array_load()?,
+                        opcode::LALOAD => self.primitive_array_load()?,
+                        opcode::FALOAD => self.primitive_array_load()?,
+                        opcode::DALOAD => self.primitive_array_load()?,
+                        opcode::AALOAD => {
                             let index = self.eval_stack.pop_int()?;
-                            let array_ref = self.eval_stack.pop_ref()?;
-                            let object_ref = array_ref.dereference()?.instance_data().get_field(index as usize)?;
+                            let array = self.eval_stack.pop_ref()?.dereference()?;
+                            array.check_array_index(index)?;
+                            let object_ref = array.instance_data().get_field(index as usize)?;
                             self.eval_stack.push(object_ref);
                         }
-                        &opcode::BALOAD => panic!("UnImplemented byte-code: BALOAD"),
-                        &opcode::CALOAD => panic!("UnImplemented byte-code: CALOAD"),
-                        &opcode::SALOAD => panic!("UnImplemented byte-code: SALOAD"),
-                        &opcode::ISTORE => {
-                            self.local_variables.store(self.eval_stack.pop(), read_u8(self.byte_codes, &mut self.ip))
-                        }
-                        &opcode::LSTORE => panic!("UnImplemented byte-code: LSTORE"),
-                        &opcode::FSTORE => panic!("UnImplemented byte-code: FSTORE"),
-                        &opcode::DSTORE => panic!("UnImplemented byte-code: DSTORE"),
-                        &opcode::ASTORE => panic!("UnImplemented byte-code: ASTORE"),
-                        &opcode::ISTORE_0 => self.local_variables.store(self.eval_stack.pop(), 0),
-                        &opcode::ISTORE_1 => self.local_variables.store(self.eval_stack.pop(), 1),
-                        &opcode::ISTORE_2 => self.local_variables.store(self.eval_stack.pop(), 2),
-                        &opcode::ISTORE_3 => self.local_variables.store(self.eval_stack.pop(), 3),
-                        &opcode::LSTORE_0 => panic!("UnImplemented byte-code: LSTORE_0"),
-                        &opcode::LSTORE_1 => panic!("UnImplemented byte-code: LSTORE_1"),
-                        &opcode::LSTORE_2 => panic!("UnImplemented byte-code: LSTORE_2"),
-                        &opcode::LSTORE_3 => panic!("UnImplemented byte-code: LSTORE_3"),
-                        &opcode::FSTORE_0 => panic!("UnImplemented byte-code: FSTORE_0"),
-                        &opcode::FSTORE_1 => panic!("UnImplemented byte-code: FSTORE_1"),
-                        &opcode::FSTORE_2 => panic!("UnImplemented byte-code: FSTORE_2"),
-                        &opcode::FSTORE_3 => panic!("UnImplemented byte-code: FSTORE_3"),
-                        &opcode::DSTORE_0 => panic!("UnImplemented byte-code: DSTORE_0"),
-                        &opcode::DSTORE_1 => panic!("UnImplemented byte-code: DSTORE_1"),
-                        &opcode::DSTORE_2 => panic!("UnImplemented byte-code: DSTORE_2"),
-                        &opcode::DSTORE_3 => panic!("UnImplemented byte-code: DSTORE_3"),
-                        &opcode::ASTORE_0 => self.local_variables.store(self.eval_stack.pop(), 0),
-                        &opcode::ASTORE_1 => self.local_variables.store(self.eval_stack.pop(), 1),
-                        &opcode::ASTORE_2 => self.local_variables.store(self.eval_stack.pop(), 2),
-                        &opcode::ASTORE_3 => self.local_variables.store(self.eval_stack.pop(), 3),
-                        &opcode::IASTORE => panic!("UnImplemented byte-code: IASTORE"),
-                        &opcode::LASTORE => panic!("UnImplemented byte-code: LASTORE"),
-                        &opcode::FASTORE => panic!("UnImplemented byte-code: FASTORE"),
-                        &opcode::DASTORE => panic!("UnImplemented byte-code: DASTORE"),
-                        &opcode::AASTORE => {
+                        opcode::BALOAD => self.primitive_array_load()?,
+                        opcode::CALOAD => self.primitive_array_load()?,
+                        opcode::SALOAD => self.primitive_array_load()?,
+                        opcode::ISTORE => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.local_variables.store(self.eval_stack.pop(), index)
+                        }
+                        opcode::LSTORE => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.local_variables.store(self.eval_stack.pop(), index)
+                        }
+                        opcode::FSTORE => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.local_variables.store(self.eval_stack.pop(), index)
+                        }
+                        opcode::DSTORE => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.local_variables.store(self.eval_stack.pop(), index)
+                        }
+                        opcode::ASTORE => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.local_variables.store(self.eval_stack.pop(), index)
+                        }
+                        opcode::ISTORE_0 => self.local_variables.store(self.eval_stack.pop(), 0),
+                        opcode::ISTORE_1 => self.local_variables.store(self.eval_stack.pop(), 1),
+                        opcode::ISTORE_2 => self.local_variables.store(self.eval_stack.pop(), 2),
+                        opcode::ISTORE_3 => self.local_variables.store(self.eval_stack.pop(), 3),
+                        opcode::LSTORE_0 => self.local_variables.store(self.eval_stack.pop(), 0),
+                        opcode::LSTORE_1 => self.local_variables.store(self.eval_stack.pop(), 1),
+                        opcode::LSTORE_2 => self.local_variables.store(self.eval_stack.pop(), 2),
+                        opcode::LSTORE_3 => self.local_variables.store(self.eval_stack.pop(), 3),
+                        opcode::FSTORE_0 => self.local_variables.store(self.eval_stack.pop(), 0),
+                        opcode::FSTORE_1 => self.local_variables.store(self.eval_stack.pop(), 1),
+                        opcode::FSTORE_2 => self.local_variables.store(self.eval_stack.pop(), 2),
+                        opcode::FSTORE_3 => self.local_variables.store(self.eval_stack.pop(), 3),
+                        opcode::DSTORE_0 => self.local_variables.store(self.eval_stack.pop(), 0),
+                        opcode::DSTORE_1 => self.local_variables.store(self.eval_stack.pop(), 1),
+                        opcode::DSTORE_2 => self.local_variables.store(self.eval_stack.pop(), 2),
+                        opcode::DSTORE_3 => self.local_variables.store(self.eval_stack.pop(), 3),
+                        opcode::ASTORE_0 => self.local_variables.store(self.eval_stack.pop(), 0),
+                        opcode::ASTORE_1 => self.local_variables.store(self.eval_stack.pop(), 1),
+                        opcode::ASTORE_2 => self.local_variables.store(self.eval_stack.pop(), 2),
+                        opcode::ASTORE_3 => self.local_variables.store(self.eval_stack.pop(), 3),
+                        opcode::IASTORE => self.primitive_array_store()?,
+                        opcode::LASTORE => self.primitive_array_store()?,
+                        opcode::FASTORE => self.primitive_array_store()?,
+                        opcode::DASTORE => self.primitive_array_store()?,
+                        opcode::AASTORE => {
                             let value = self.eval_stack.pop();
                             let index = self.eval_stack.pop_int()?;
                             if let JvmValue::ObjRef(array_ref) = self.eval_stack.pop() {
-                                //do a lots of checks here
-                                array_ref.dereference()?.instance_data().put_field(index as usize, value)?;
+                                let array = array_ref.dereference()?;
+                                array.check_array_index(index)?;
+                                self.check_array_store(&array, &value)?;
+                                array.instance_data().put_field(index as usize, value.clone())?;
+                                self.write_barrier(array.instance_data(), &value);
                             } else {
                                 return Err(JvmException::from("Stack should contain a Reference."));
                             }
                         }
-                        &opcode::BASTORE => panic!("UnImplemented byte-code: BASTORE"),
-                        &opcode::CASTORE => panic!("UnImplemented byte-code: CASTORE"),
-                        &opcode::SASTORE => panic!("UnImplemented byte-code: SASTORE"),
-                        &opcode::POP => panic!("UnImplemented byte-code: POP"),
-                        &opcode::POP2 => panic!("UnImplemented byte-code: POP2"),
-                        &opcode::DUP => {
+                        opcode::BASTORE => self.primitive_array_store()?,
+                        opcode::CASTORE => self.primitive_array_store()?,
+                        opcode::SASTORE => self.primitive_array_store()?,
+                        opcode::POP => {
+                            self.eval_stack.pop_slots(1)?;
+                        }
+                        opcode::POP2 => {
+                            self.eval_stack.pop_slots(2)?;
+                        }
+                        opcode::DUP => {
                             let val1 = self.eval_stack.pop();
                             self.eval_stack.push(val1.clone());
                             self.eval_stack.push(val1);
                         }
-                        &opcode::DUP_X1 => panic!("UnImplemented byte-code: DUP_X1"),
-                        &opcode::DUP_X2 => panic!("UnImplemented byte-code: DUP_X2"),
-                        &opcode::DUP2 => panic!("UnImplemented byte-code: DUP2"),
-                        &opcode::DUP2_X1 => panic!("UnImplemented byte-code: DUP2_X1"),
-                        &opcode::DUP2_X2 => panic!("UnImplemented byte-code: DUP2_X2"),
-                        &opcode::SWAP => panic!("UnImplemented byte-code: SWAP"),
-                        &opcode::IADD => self.eval_stack.add(),
-                        &opcode::LADD => panic!("UnImplemented byte-code: LADD"),
-                        &opcode::FADD => panic!("UnImplemented byte-code: FADD"),
-                        &opcode::DADD => panic!("UnImplemented byte-code: DADD"),
-                        &opcode::ISUB => panic!("UnImplemented byte-code: ISUB"),
-                        &opcode::LSUB => panic!("UnImplemented byte-code: LSUB"),
-                        &opcode::FSUB => panic!("UnImplemented byte-code: FSUB"),
-                        &opcode::DSUB => panic!("UnImplemented byte-code: DSUB"),
-                        &opcode::IMUL => self.eval_stack.mul(),
-                        &opcode::LMUL => panic!("UnImplemented byte-code: LMUL"),
-                        &opcode::FMUL => panic!("UnImplemented byte-code: FMUL"),
-                        &opcode::DMUL => panic!("UnImplemented byte-code: DMUL"),
-                        &opcode::IDIV => panic!("UnImplemented byte-code: IDIV"),
-                        &opcode::LDIV => panic!("UnImplemented byte-code: LDIV"),
-                        &opcode::FDIV => panic!("UnImplemented byte-code: FDIV"),
-                        &opcode::DDIV => panic!("UnImplemented byte-code: DDIV"),
-                        &opcode::IREM => panic!("UnImplemented byte-code: IREM"),
-                        &opcode::LREM => panic!("UnImplemented byte-code: LREM"),
-                        &opcode::FREM => panic!("UnImplemented byte-code: FREM"),
-                        &opcode::DREM => panic!("UnImplemented byte-code: DREM"),
-                        &opcode::INEG => panic!("UnImplemented byte-code: INEG"),
-                        &opcode::LNEG => panic!("UnImplemented byte-code: LNEG"),
-                        &opcode::FNEG => panic!("UnImplemented byte-code: FNEG"),
-                        &opcode::DNEG => panic!("UnImplemented byte-code: DNEG"),
-                        &opcode::ISHL => panic!("UnImplemented byte-code: ISHL"),
-                        &opcode::LSHL => panic!("UnImplemented byte-code: LSHL"),
-                        &opcode::ISHR => panic!("UnImplemented byte-code: ISHR"),
-                        &opcode::LSHR => panic!("UnImplemented byte-code: LSHR"),
-                        &opcode::IUSHR => panic!("UnImplemented byte-code: IUSHR"),
-                        &opcode::LUSHR => panic!("UnImplemented byte-code: LUSHR"),
-                        &opcode::IAND => panic!("UnImplemented byte-code: IAND"),
-                        &opcode::LAND => panic!("UnImplemented byte-code: LAND"),
-                        &opcode::IOR => panic!("UnImplemented byte-code: IOR"),
-                        &opcode::LOR => panic!("UnImplemented byte-code: LOR"),
-                        &opcode::IXOR => panic!("UnImplemented byte-code: IXOR"),
-                        &opcode::LXOR => panic!("UnImplemented byte-code: LXOR"),
-                        &opcode::IINC => panic!("UnImplemented byte-code: IINC"),
-                        &opcode::I2L => panic!("UnImplemented byte-code: I2L"),
-                        &opcode::I2F => panic!("UnImplemented byte-code: I2F"),
-                        &opcode::I2D => panic!("UnImplemented byte-code: I2D"),
-                        &opcode::L2I => panic!("UnImplemented byte-code: L2I"),
-                        &opcode::L2F => panic!("UnImplemented byte-code: L2F"),
-                        &opcode::L2D => panic!("UnImplemented byte-code: L2D"),
-                        &opcode::F2I => panic!("UnImplemented byte-code: F2I"),
-                        &opcode::F2L => panic!("UnImplemented byte-code: F2L"),
-                        &opcode::F2D => panic!("UnImplemented byte-code: F2D"),
-                        &opcode::D2I => panic!("UnImplemented byte-code: D2I"),
-                        &opcode::D2L => panic!("UnImplemented byte-code: D2L"),
-                        &opcode::D2F => panic!("UnImplemented byte-code: D2F"),
-                        &opcode::I2B => panic!("UnImplemented byte-code: I2B"),
-                        &opcode::I2C => panic!("UnImplemented byte-code: I2C"),
-                        &opcode::I2S => panic!("UnImplemented byte-code: I2S"),
-                        &opcode::LCMP => panic!("UnImplemented byte-code: LCMP"),
-                        &opcode::FCMPL => panic!("UnImplemented byte-code: FCMPL"),
-                        &opcode::FCMPG => panic!("UnImplemented byte-code: FCMPG"),
-                        &opcode::DCMPL => panic!("UnImplemented byte-code: DCMPL"),
-                        &opcode::DCMPG => panic!("UnImplemented byte-code: DCMPG"),
-                        &opcode::IFEQ => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::EQ)?,
-                        &opcode::IFNE => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::NEQ)?,
-                        &opcode::IFLT => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LT)?,
-                        &opcode::IFGE => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GE)?,
-                        &opcode::IFGT => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GT)?,
-                        &opcode::IFLE => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LE)?,
-                        &opcode::IF_ICMPEQ => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::EQ)?,
-                        &opcode::IF_ICMPNE => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::NEQ)?,
-                        &opcode::IF_ICMPLT => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LT)?,
-                        &opcode::IF_ICMPGE => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GE)?,
-                        &opcode::IF_ICMPGT => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GT)?,
-                        &opcode::IF_ICMPLE => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LE)?,
-                        &opcode::IF_ACMPEQ => panic!("UnImplemented byte-code: IF_ACMPEQ"),
-                        &opcode::IF_ACMPNE => panic!("UnImplemented byte-code: IF_ACMPNE"),
-                        &opcode::GOTO => {
-                            let tmp_ip = self.ip;
-                            let offset = read_offset(byte_codes, ip);
-                            self.ip = tmp_ip + offset;
-                        }
-                        &opcode::JSR => panic!("UnImplemented byte-code: JSR"),
-                        &opcode::RET => panic!("UnImplemented byte-code: RET"),
-                        &opcode::TABLESWITCH => panic!("UnImplemented byte-code: TABLESWITCH"),
-                        &opcode::LOOKUPSWITCH => panic!("UnImplemented byte-code: LOOKUPSWITCH"),
-                        &opcode::IRETURN => {
+                        opcode::DUP_X1 => self.eval_stack.duplicate(1, 1)?,
+                        opcode::DUP_X2 => self.eval_stack.duplicate(1, 2)?,
+                        opcode::DUP2 => self.eval_stack.duplicate(2, 0)?,
+                        opcode::DUP2_X1 => self.eval_stack.duplicate(2, 1)?,
+                        opcode::DUP2_X2 => self.eval_stack.duplicate(2, 2)?,
+                        opcode::SWAP => {
+                            let value1 = self.eval_stack.pop_slots(1)?;
+                            let value2 = self.eval_stack.pop_slots(1)?;
+                            self.eval_stack.push_all(value1);
+                            self.eval_stack.push_all(value2);
+                        }
+                        opcode::IADD => self.eval_stack.add()?,
+                        opcode::LADD => self.eval_stack.add()?,
+                        opcode::FADD => self.eval_stack.add()?,
+                        opcode::DADD => self.eval_stack.add()?,
+                        opcode::ISUB => self.eval_stack.sub()?,
+                        opcode::LSUB => self.eval_stack.sub()?,
+                        opcode::FSUB => self.eval_stack.sub()?,
+                        opcode::DSUB => self.eval_stack.sub()?,
+                        opcode::IMUL => self.eval_stack.mul()?,
+                        opcode::LMUL => self.eval_stack.mul()?,
+                        opcode::FMUL => self.eval_stack.mul()?,
+                        opcode::DMUL => self.eval_stack.mul()?,
+                        opcode::IDIV => self.eval_stack.div()?,
+                        opcode::LDIV => self.eval_stack.div()?,
+                        opcode::FDIV => self.eval_stack.div()?,
+                        opcode::DDIV => self.eval_stack.div()?,
+                        opcode::IREM => self.eval_stack.rem()?,
+                        opcode::LREM => self.eval_stack.rem()?,
+                        opcode::FREM => self.eval_stack.rem()?,
+                        opcode::DREM => self.eval_stack.rem()?,
+                        opcode::INEG => self.eval_stack.neg()?,
+                        opcode::LNEG => self.eval_stack.neg()?,
+                        opcode::FNEG => self.eval_stack.neg()?,
+                        opcode::DNEG => self.eval_stack.neg()?,
+                        opcode::ISHL => self.eval_stack.shl()?,
+                        opcode::LSHL => self.eval_stack.shl()?,
+                        opcode::ISHR => self.eval_stack.shr()?,
+                        opcode::LSHR => self.eval_stack.shr()?,
+                        opcode::IUSHR => self.eval_stack.ushr()?,
+                        opcode::LUSHR => self.eval_stack.ushr()?,
+                        opcode::IAND => self.eval_stack.and()?,
+                        opcode::LAND => self.eval_stack.and()?,
+                        opcode::IOR => self.eval_stack.or()?,
+                        opcode::LOR => self.eval_stack.or()?,
+                        opcode::IXOR => self.eval_stack.xor()?,
+                        opcode::LXOR => self.eval_stack.xor()?,
+                        opcode::IINC => {
+                            let wide = self.wide;
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            let constant = if wide {
+                                read_u16(self.byte_codes, &mut self.ip) as i16 as i32
+                            } else {
+                                read_u8(self.byte_codes, &mut self.ip) as i8 as i32
+                            };
+                            match self.local_variables.load(index) {
+                                JvmValue::Int { val } => self.local_variables.store(JvmValue::Int { val: val.wrapping_add(constant) }, index),
+                                other => return Err(JvmException::from(format!("Cannot increment non-int local variable: {:?}", other))),
+                            }
+                        }
+                        opcode::I2L => {
+                            let value = self.eval_stack.pop_int()?;
+                            self.eval_stack.l_constant(value as i64)
+                        }
+                        opcode::I2F => {
+                            let value = self.eval_stack.pop_int()?;
+                            self.eval_stack.f_constant(value as f32)
+                        }
+                        opcode::I2D => {
+                            let value = self.eval_stack.pop_int()?;
+                            self.eval_stack.d_constant(value as f64)
+                        }
+                        opcode::L2I => {
+                            let value = self.eval_stack.pop_long()?;
+                            self.eval_stack.i_constant(value as i32)
+                        }
+                        opcode::L2F => {
+                            let value = self.eval_stack.pop_long()?;
+                            self.eval_stack.f_constant(value as f32)
+                        }
+                        opcode::L2D => {
+                            let value = self.eval_stack.pop_long()?;
+                            self.eval_stack.d_constant(value as f64)
+                        }
+                        opcode::F2I => {
+                            //float to int casts in Rust saturate and convert NaN to 0 exactly as the JLS requires
+                            let value = self.eval_stack.pop_float()?;
+                            self.eval_stack.i_constant(value as i32)
+                        }
+                        opcode::F2L => {
+                            let value = self.eval_stack.pop_float()?;
+                            self.eval_stack.l_constant(value as i64)
+                        }
+                        opcode::F2D => {
+                            let value = self.eval_stack.pop_float()?;
+                            self.eval_stack.d_constant(value as f64)
+                        }
+                        opcode::D2I => {
+                            let value = self.eval_stack.pop_double()?;
+                            self.eval_stack.i_constant(value as i32)
+                        }
+                        opcode::D2L => {
+                            let value = self.eval_stack.pop_double()?;
+                            self.eval_stack.l_constant(value as i64)
+                        }
+                        opcode::D2F => {
+                            let value = self.eval_stack.pop_double()?;
+                            self.eval_stack.f_constant(value as f32)
+                        }
+                        opcode::I2B => {
+                            let value = self.eval_stack.pop_int()?;
+                            self.eval_stack.i_constant(value as i8 as i32)
+                        }
+                        opcode::I2C => {
+                            let value = self.eval_stack.pop_int()?;
+                            self.eval_stack.i_constant(value as u16 as i32)
+                        }
+                        opcode::I2S => {
+                            let value = self.eval_stack.pop_int()?;
+                            self.eval_stack.i_constant(value as i16 as i32)
+                        }
+                        opcode::LCMP => self.eval_stack.l_compare()?,
+                        opcode::FCMPL => self.eval_stack.f_compare(-1)?,
+                        opcode::FCMPG => self.eval_stack.f_compare(1)?,
+                        opcode::DCMPL => self.eval_stack.d_compare(-1)?,
+                        opcode::DCMPG => self.eval_stack.d_compare(1)?,
+                        opcode::IFEQ => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::EQ)?,
+                        opcode::IFNE => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::NEQ)?,
+                        opcode::IFLT => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LT)?,
+                        opcode::IFGE => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GE)?,
+                        opcode::IFGT => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GT)?,
+                        opcode::IFLE => eval_if(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LE)?,
+                        opcode::IF_ICMPEQ => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::EQ)?,
+                        opcode::IF_ICMPNE => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::NEQ)?,
+                        opcode::IF_ICMPLT => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LT)?,
+                        opcode::IF_ICMPGE => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GE)?,
+                        opcode::IF_ICMPGT => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::GT)?,
+                        opcode::IF_ICMPLE => eval_if_cmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, comparators::LE)?,
+                        opcode::IF_ACMPEQ => eval_if_acmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, true)?,
+                        opcode::IF_ACMPNE => eval_if_acmp(self.byte_codes, &mut self.ip, &mut self.eval_stack, false)?,
+                        opcode::GOTO => self.ip = read_branch_target(self.byte_codes, &mut self.ip),
+                        opcode::JSR => {
+                            let target = read_branch_target(self.byte_codes, &mut self.ip);
+                            self.eval_stack.push(JvmValue::ReturnAddr { val: self.ip + 1 });
+                            self.ip = target;
+                        }
+                        opcode::RET => {
+                            let index = read_local_index(self.byte_codes, &mut self.ip, &mut self.wide);
+                            self.ip = match self.local_variables.load(index) {
+                                JvmValue::ReturnAddr { val } => jump_to(val),
+                                other => return Err(JvmException::from(format!("RET expected a returnAddress but got: {:?}", other))),
+                            };
+                        }
+                        opcode::TABLESWITCH => {
+                            let index = self.eval_stack.pop_int()?;
+                            skip_switch_padding(self.pc, &mut self.ip);
+
+                            let default = read_i32(self.byte_codes, &mut self.ip);
+                            let low = read_i32(self.byte_codes, &mut self.ip);
+                            let high = read_i32(self.byte_codes, &mut self.ip);
+
+                            let offset = if index < low || index > high {
+                                default
+                            } else {
+                                self.ip += 4 * (index - low) as usize;
+                                read_i32(self.byte_codes, &mut self.ip)
+                            };
+                            self.ip = jump_to(branch_target(self.pc, offset));
+                        }
+                        opcode::LOOKUPSWITCH => {
+                            let key = self.eval_stack.pop_int()?;
+                            skip_switch_padding(self.pc, &mut self.ip);
+
+                            let mut offset = read_i32(self.byte_codes, &mut self.ip);
+                            let number_of_pairs = read_i32(self.byte_codes, &mut self.ip);
+
+                            for _ in 0..number_of_pairs {
+                                let match_value = read_i32(self.byte_codes, &mut self.ip);
+                                let match_offset = read_i32(self.byte_codes, &mut self.ip);
+                                if match_value == key {
+                                    offset = match_offset;
+                                    break;
+                                }
+                            }
+                            self.ip = jump_to(branch_target(self.pc, offset));
+                        }
+                        opcode::IRETURN => {
                             return match self.eval_stack.pop() {
                                 java_int @ JvmValue::Int { val: _ } => Ok(java_int),
                                 _ => Err(JvmException::from(
@@ -321,13 +799,59 @@ impl<'a> Interpreter<'a> {
                                 )),
                             };
                         }
-                        &opcode::LRETURN => panic!("UnImplemented byte-code: LRETURN"),
-                        &opcode::FRETURN => panic!("UnImplemented byte-code: FRETURN"),
-                        &opcode::DRETURN => panic!("UnImplemented byte-code: DRETURN"),
-                        &opcode::ARETURN => panic!("UnImplemented byte-code: ARETURN"),
-                        &opcode::RETURN => return Ok(JvmValue::Void {}),
-                        &opcode::GETSTATIC => panic!("UnImplemented byte-code: GETSTATIC"),
-                        &opcode::PUTSTATIC => {
+                        opcode::LRETURN => {
+                            return match self.eval_stack.pop() {
+                                java_long @ JvmValue::Long { val: _ } => Ok(java_long),
+                                _ => Err(JvmException::from(
+                                    "Non-long value was found on top of stack when executing LRETURN",
+                                )),
+                            };
+                        }
+                        opcode::FRETURN => {
+                            return match self.eval_stack.pop() {
+                                java_float @ JvmValue::Float { val: _ } => Ok(java_float),
+                                _ => Err(JvmException::from(
+                                    "Non-float value was found on top of stack when executing FRETURN",
+                                )),
+                            };
+                        }
+                        opcode::DRETURN => {
+                            return match self.eval_stack.pop() {
+                                java_double @ JvmValue::Double { val: _ } => Ok(java_double),
+                                _ => Err(JvmException::from(
+                                    "Non-double value was found on top of stack when executing DRETURN",
+                                )),
+                            };
+                        }
+                        opcode::ARETURN => {
+                            return match self.eval_stack.pop() {
+                                java_ref @ JvmValue::ObjRef(_) => Ok(java_ref),
+                                _ => Err(JvmException::from(
+                                    "Non-reference value was found on top of stack when executing ARETURN",
+                                )),
+                            };
+                        }
+                        opcode::RETURN => return Ok(JvmValue::Void {}),
+                        opcode::GETSTATIC => {
+                            let index = read_u16(self.byte_codes, &mut self.ip);
+                            let qualified_name = self.current_frame
+                                .constant_pool()
+                                .get_qualified_name(index);
+
+                            match qualified_name {
+                                Qualifier::FieldRef { class_name, name, type_descriptor } => {
+                                    //accessing a static field triggers the initialization of its class
+                                    let klass = self.call_out(|frame| frame.class_loader().load_and_init_class(&class_name))?;
+
+                                    let field_value = klass.get_static_field_by_name_and_type(&name, &type_descriptor)
+                                        .map(|static_field| static_field.static_value())
+                                        .ok_or(JvmException::no_such_field(&name))?;
+                                    self.eval_stack.push(field_value.widen());
+                                }
+                                invalid => return Err(JvmException::from(format!("GetStatic index should refer to a field not a {:?}", invalid)))
+                            }
+                        }
+                        opcode::PUTSTATIC => {
                             let index = read_u16(self.byte_codes, &mut self.ip);
                             let qualified_name = self.current_frame
                                 .constant_pool()
@@ -337,21 +861,19 @@ impl<'a> Interpreter<'a> {
 
                             match qualified_name {
                                 Qualifier::FieldRef { class_name, name, type_descriptor } => {
-                                    let klass = self.current_frame
-                                        .class_loader()
-                                        .load_and_init_class(&class_name)?;
+                                    let klass = self.call_out(|frame| frame.class_loader().load_and_init_class(&class_name))?;
 
                                     klass.get_static_field_by_name_and_type(&name, &type_descriptor)
-                                        .map(|static_field| static_field.set_static_value(value_to_assign))
-                                        .ok_or(
-                                            JvmException::from(format!("Field not found by {:?}",
-                                                                       Qualifier::FieldRef { class_name, name, type_descriptor }))
-                                        )?;
+                                        .map(|static_field| static_field.set_static_value(value_to_assign.clone()))
+                                        .ok_or(JvmException::no_such_field(&name))?;
+                                    if let JvmValue::ObjRef(Ref(_)) = value_to_assign {
+                                        self.current_frame.heap().static_write_barrier(&klass, &value_to_assign);
+                                    }
                                 }
                                 invalid => Err(JvmException::from(format!("PutField index should refer to a field not a {:?}", invalid)))?
                             };
                         }
-                        &opcode::GETFIELD => {
+                        opcode::GETFIELD => {
                             let index = read_u16(self.byte_codes, &mut self.ip);
                             let qualified_name = self.current_frame
                                 .constant_pool()
@@ -361,9 +883,7 @@ impl<'a> Interpreter<'a> {
 
                             match qualified_name {
                                 Qualifier::FieldRef { class_name, name, type_descriptor } => {
-                                    let klass = self.current_frame
-                                        .class_loader()
-                                        .load_and_init_class(&class_name)?;
+                                    let klass = self.call_out(|frame| frame.class_loader().load_and_init_class(&class_name))?;
 
                                     let field_value = klass.get_instance_field_offset(&name, &type_descriptor)
                                         .map(|field_offset| {
@@ -374,16 +894,14 @@ impl<'a> Interpreter<'a> {
                                                 Err(JvmException::from(format!("Stack should contain a Reference to an Object, but was {:?}", object_to_modify)))
                                             }
                                         })
-                                        .ok_or(
-                                            JvmException::from(format!("Field not found by {:?}",
-                                                                       Qualifier::FieldRef { class_name, name, type_descriptor }))
-                                        )??;
-                                    self.eval_stack.push(field_value);
+                                        .ok_or(JvmException::no_such_field(&name))??;
+                                    //fields keep their declared type, e.g. default booleans, which are ints on the stack
+                                    self.eval_stack.push(field_value.widen());
                                 }
                                 invalid => return Err(JvmException::from(format!("GetField index should refer to a field not a {:?}", invalid)))
                             }
                         }
-                        &opcode::PUTFIELD => {
+                        opcode::PUTFIELD => {
                             let index = read_u16(self.byte_codes, &mut self.ip);
                             let qualified_name = self.current_frame
                                 .constant_pool()
@@ -394,145 +912,150 @@ impl<'a> Interpreter<'a> {
 
                             match qualified_name {
                                 Qualifier::FieldRef { class_name, name, type_descriptor } => {
-                                    let klass = self.current_frame
-                                        .class_loader()
-                                        .load_and_init_class(&class_name)?;
+                                    let klass = self.call_out(|frame| frame.class_loader().load_and_init_class(&class_name))?;
                                     klass.get_instance_field_offset(&name, &type_descriptor)
                                         .map(|field_offset| {
                                             if let JvmValue::ObjRef(object_ref) = object_to_modify {
                                                 //TODO: do a lots of checks here
-                                                object_ref.dereference()?.instance_data().put_field(field_offset, value_to_assign)?;
+                                                let object = object_ref.dereference()?;
+                                                object.instance_data().put_field(field_offset, value_to_assign.clone())?;
+                                                self.write_barrier(object.instance_data(), &value_to_assign);
                                                 Ok(())
                                             } else {
                                                 Err(JvmException::from(format!("Stack should contain a Reference to an Object, but was {:?}", object_to_modify)))
                                             }
                                         })
-                                        .ok_or(
-                                            JvmException::from(format!("Field not found by {:?}",
-                                                                       Qualifier::FieldRef { class_name, name, type_descriptor }))
-                                        )??;
+                                        .ok_or(JvmException::no_such_field(&name))??;
                                 }
                                 invalid => return Err(JvmException::from(format!("PutStatic index should refer to a field not a {:?}", invalid)))
                             }
                         }
-                        &opcode::INVOKEVIRTUAL => panic!("UnImplemented byte-code: INVOKEVIRTUAL"),
-                        &opcode::INVOKESPECIAL => {
+                        opcode::INVOKEVIRTUAL => {
                             let index = read_u16(self.byte_codes, &mut self.ip);
 
                             let qualified_method_name = self.current_frame
                                 .constant_pool()
                                 .get_qualified_name(index);
 
-                            let method_to_call = self.current_frame
-                                .class_loader()
-                                .lookup_instance_method(qualified_method_name)?;
+                            let resolved_method = self.call_out(|frame| frame.class_loader().lookup_instance_method(qualified_method_name))?;
 
-                            let number_of_parameters = method_to_call.number_of_parameters() + 1;
+                            let args = self.pop_arguments_with_receiver(&resolved_method);
+                            let receiver_class = self.receiver_class(&args[0])?;
 
-                            let mut args: Vec<JvmValue> = (0..number_of_parameters).map(|_| self.eval_stack.pop()).collect();
-                            args.reverse();
-
-                            let void_method = method_to_call.is_void();
-                            let method_return_value = self.current_frame.execute_method(method_to_call, args)?;
+                            //private methods are not overridden, so they are invoked without selection
+                            let method_to_call = if resolved_method.is_private() {
+                                resolved_method
+                            } else {
+                                let selected_method = receiver_class.virtual_method(&resolved_method);
+                                Interpreter::implementation_of(selected_method, &resolved_method, &receiver_class)?
+                            };
 
-                            if !void_method {
-                                self.eval_stack.push(method_return_value);
-                            }
+                            self.invoke(method_to_call, args)?;
                         }
-                        &opcode::INVOKESTATIC => {
+                        opcode::INVOKESPECIAL => {
                             let index = read_u16(self.byte_codes, &mut self.ip);
 
                             let qualified_method_name = self.current_frame
                                 .constant_pool()
                                 .get_qualified_name(index);
 
-                            let method_to_call = self.current_frame
-                                .class_loader()
-                                .lookup_static_method(qualified_method_name)?;
+                            let referenced_class_name = match &qualified_method_name {
+                                Qualifier::MethodRef { class_name, .. } => class_name.clone(),
+                                invalid => return Err(JvmException::from(format!("InvokeSpecial index should refer to a method not a {:?}", invalid)))
+                            };
 
-                            let number_of_parameters = method_to_call.number_of_parameters();
-                            let mut args: Vec<JvmValue> = (0..number_of_parameters).map(|_| self.eval_stack.pop()).collect();
-                            args.reverse();
+                            let resolved_method = self.call_out(|frame| frame.class_loader().lookup_instance_method(qualified_method_name))?;
 
-                            let void_method = method_to_call.is_void();
-                            let method_return_value = self.current_frame.execute_method(method_to_call, args)?;
+                            let method_to_call = self.select_special_method(resolved_method, &referenced_class_name)?;
+                            let args = self.pop_arguments_with_receiver(&method_to_call);
 
-                            if !void_method {
-                                self.eval_stack.push(method_return_value);
+                            if args[0] == JvmValue::null_obj() {
+                                return Err(JvmException::null_pointer());
                             }
+                            self.invoke(method_to_call, args)?;
                         }
-                        &opcode::INVOKEINTERFACE => {
+                        opcode::INVOKESTATIC => {
                             let index = read_u16(self.byte_codes, &mut self.ip);
 
                             let qualified_method_name = self.current_frame
                                 .constant_pool()
                                 .get_qualified_name(index);
 
-                            let n_args = read_u8(self.byte_codes, &mut self.ip);
-                            let mut args: Vec<JvmValue> = (0..n_args).map(|_| self.eval_stack.pop()).collect();
-                            args.reverse();
+                            let method_to_call = self.call_out(|frame| frame.class_loader().lookup_static_method(qualified_method_name))?;
 
-                            let this = match &args[0] {
-                                ObjRef(obj_ref) => obj_ref.dereference()?,
-                                _ => panic!("0th argument must be this!")
-                            };
+                            let number_of_parameters = method_to_call.number_of_parameters();
+                            let mut args: Vec<JvmValue> = (0..number_of_parameters).map(|_| self.eval_stack.pop()).collect();
+                            args.reverse();
 
-                            let this_klass = this.java_klass_or_fail();
+                            self.invoke(method_to_call, args)?;
+                        }
+                        opcode::INVOKEINTERFACE => {
+                            let index = read_u16(self.byte_codes, &mut self.ip);
+                            let _count = read_u8(self.byte_codes, &mut self.ip);
+                            let _zero = read_u8(self.byte_codes, &mut self.ip);
 
+                            let qualified_method_name = self.current_frame
+                                .constant_pool()
+                                .get_qualified_name(index);
 
-                            let method_to_call = self.current_frame
-                                .class_loader()
-                                .lookup_interface_method(this_klass, qualified_method_name)?;
+                            let resolved_method = self.call_out(|frame| frame.class_loader().lookup_instance_method(qualified_method_name))?;
 
-                            let _zero = read_u8(self.byte_codes, &mut self.ip);
+                            //count is the number of slots the arguments occupy, longs and doubles are single values on our stack
+                            let args = self.pop_arguments_with_receiver(&resolved_method);
+                            let receiver_class = self.receiver_class(&args[0])?;
 
-                            let void_method = method_to_call.is_void();
-                            let method_return_value = self.current_frame.execute_method(method_to_call, args)?;
+                            let selected_method = receiver_class.find_interface_method(&resolved_method.name_desc());
+                            let method_to_call = Interpreter::implementation_of(selected_method, &resolved_method, &receiver_class)?;
 
-                            if !void_method {
-                                self.eval_stack.push(method_return_value);
-                            }
+                            self.invoke(method_to_call, args)?;
                         }
-                        &opcode::INVOKEDYNAMIC => panic!("UnImplemented byte-code: INVOKEDYNAMIC"),
-                        &opcode::NEW => {
+                        opcode::INVOKEDYNAMIC => panic!("UnImplemented byte-code: INVOKEDYNAMIC"),
+                        opcode::NEW => {
                             let index = read_u16(self.byte_codes, &mut self.ip);
 
                             let qualified_klass_name = self.current_frame
                                 .constant_pool()
                                 .get_qualified_name(index);
 
-                            let klass = self.current_frame
-                                .class_loader()
-                                .load_class(&qualified_klass_name)?;
+                            let klass = match qualified_klass_name {
+                                Qualifier::Class { name } => self.call_out(|frame| frame.class_loader().load_and_init_class(&name))?,
+                                invalid => return Err(JvmException::from(format!("New index should refer to a class not a {:?}", invalid)))
+                            };
 
-                            let obj_ref = self.current_frame.heap().allocate_object(klass)?;
+                            let obj_ref = self.call_out(|frame| frame.heap().allocate_object(klass))?;
 
                             self.eval_stack.push(JvmValue::from(obj_ref));
                         }
-                        &opcode::NEWARRAY => panic!("UnImplemented byte-code: NEWARRAY"),
-                        &opcode::ANEWARRAY => {
+                        opcode::NEWARRAY => {
+                            let array_size = self.eval_stack.pop_int()?;
+                            let array_type = PrimitiveType::from(read_u8(self.byte_codes, &mut self.ip) as i32);
+
+                            if array_size < 0 {
+                                return Err(JvmException::negative_array_size(array_size));
+                            }
+
+                            let array_ref = self.call_out(|frame| frame.heap().allocate_primitive_array(array_type, array_size))?;
+                            self.eval_stack.push(JvmValue::from(array_ref));
+                        }
+                        opcode::ANEWARRAY => {
                             let array_size = self.eval_stack.pop_int()?;
                             let array_type_index = read_u16(self.byte_codes, &mut self.ip);
 
-                            let qualified_klass_name = self.current_frame
-                                .constant_pool()
-                                .get_qualified_name(array_type_index);
+                            if array_size < 0 {
+                                return Err(JvmException::negative_array_size(array_size));
+                            }
 
-                            let klass = self.current_frame
-                                .class_loader()
-                                .load_class(&qualified_klass_name)?;
+                            let klass = match self.current_frame.constant_pool().get_qualified_name(array_type_index) {
+                                Qualifier::Class { name } => self.array_component_class(&name)?,
+                                other => return Err(JvmException::from(format!("Expected Class for ANEWARRAY, but got {:?}", other))),
+                            };
 
-                            let array_ref = self.current_frame.heap().allocate_array(klass, array_size)?;
+                            let array_ref = self.call_out(|frame| frame.heap().allocate_array(klass, array_size))?;
                             self.eval_stack.push(JvmValue::from(array_ref));
                         }
-                        &opcode::ARRAYLENGTH => {
+                        opcode::ARRAYLENGTH => {
                             if let JvmValue::ObjRef(array_ref) = self.eval_stack.pop() {
-                                //do a lots of checks here
-                                let array_length = match array_ref.dereference()? {
-                                    Oop::ArrayOop(desc) => Ok(desc.size),
-                                    Oop::PrimitiveArrayOop(desc) => Ok(desc.size),
-                                    _ => Err(JvmException::from("Expected array reference!"))
-                                }?;
+                                let array_length = array_ref.dereference()?.array_length()?;
                                 self.eval_stack.push(JvmValue::Int {
                                     val: array_length,
                                 })
@@ -540,83 +1063,207 @@ impl<'a> Interpreter<'a> {
                                 return Err(JvmException::from("Stack should contain a Reference."));
                             }
                         }
-                        &opcode::ATHROW => panic!("UnImplemented byte-code: ATHROW"),
-                        &opcode::CHECKCAST => panic!("UnImplemented byte-code: CHECKCAST"),
-                        &opcode::INSTANCEOF => panic!("UnImplemented byte-code: INSTANCEOF"),
-                        &opcode::MONITORENTER => panic!("UnImplemented byte-code: MONITORENTER"),
-                        &opcode::MONITOREXIT => panic!("UnImplemented byte-code: MONITOREXIT"),
-                        &opcode::WIDE => panic!("UnImplemented byte-code: WIDE"),
-                        &opcode::MULTIANEWARRAY => panic!("UnImplemented byte-code: MULTIANEWARRAY"),
-                        &opcode::IFNULL => panic!("UnImplemented byte-code: IFNULL"),
-                        &opcode::IFNONNULL => {
-                            let tmp_ip = self.ip;
-                            let offset = read_offset(byte_codes, ip);
+                        opcode::ATHROW => {
+                            let throwable = self.eval_stack.pop_ref()?.dereference()?;
+                            return Err(JvmException::from_throwable(throwable));
+                        }
+                        opcode::CHECKCAST => {
+                            let type_index = read_u16(self.byte_codes, &mut self.ip);
                             let object_ref = self.eval_stack.pop_ref()?;
-                            let ip_offset = match object_ref {
-                                ObjectRef::Null => offset,
-                                Ref(_) => 1
+
+                            if let Ref(object) = &object_ref {
+                                let type_name = match self.current_frame.constant_pool().get_qualified_name(type_index) {
+                                    Qualifier::Class { name } => name,
+                                    other => return Err(JvmException::from(format!("Expected Class for CHECKCAST, but got {:?}", other))),
+                                };
+
+                                if !self.is_instance_of(object, &type_name)? {
+                                    return Err(JvmException::class_cast(&Interpreter::class_name_of(object), &type_name));
+                                }
+                            }
+                            self.eval_stack.push(JvmValue::ObjRef(object_ref));
+                        }
+                        opcode::INSTANCEOF => {
+                            let type_index = read_u16(self.byte_codes, &mut self.ip);
+                            let is_instance = match self.eval_stack.pop_ref()? {
+                                Ref(object) => {
+                                    let type_name = match self.current_frame.constant_pool().get_qualified_name(type_index) {
+                                        Qualifier::Class { name } => name,
+                                        other => return Err(JvmException::from(format!("Expected Class for INSTANCEOF, but got {:?}", other))),
+                                    };
+                                    self.is_instance_of(&object, &type_name)?
+                                }
+                                ObjectRef::Null => false,
+                            };
+                            self.eval_stack.push(JvmValue::Int { val: is_instance as i32 });
+                        }
+                        opcode::MONITORENTER => {
+                            let object = self.eval_stack.pop_ref()?.dereference()?;
+                            self.call_out(|_| object.monitor().enter());
+                        }
+                        opcode::MONITOREXIT => {
+                            let object = self.eval_stack.pop_ref()?.dereference()?;
+                            object.monitor().exit()?;
+                        }
+                        opcode::WIDE => {
+                            self.wide = true;
+                            //the modified instruction has to be executed together with WIDE
+                            self.ip += 1;
+                            continue;
+                        }
+                        opcode::MULTIANEWARRAY => {
+                            let array_type_index = read_u16(self.byte_codes, &mut self.ip);
+                            let dimensions = read_u8(self.byte_codes, &mut self.ip);
+
+                            let mut counts = Vec::with_capacity(dimensions as usize);
+                            for _ in 0..dimensions {
+                                counts.push(self.eval_stack.pop_int()?);
+                            }
+                            counts.reverse();
+
+                            if let Some(negative_count) = counts.iter().find(|count| **count < 0) {
+                                return Err(JvmException::negative_array_size(*negative_count));
+                            }
+
+                            let array_type = match self.current_frame.constant_pool().get_qualified_name(array_type_index) {
+                                Qualifier::Class { name } => name,
+                                other => return Err(JvmException::from(format!("Expected Class for MULTIANEWARRAY, but got {:?}", other))),
                             };
 
-                            self.ip = tmp_ip + ip_offset;
-                        },
-                        &opcode::GOTO_W => panic!("UnImplemented byte-code: GOTO_W"),
-                        &opcode::JSR_W => panic!("UnImplemented byte-code: JSR_W"),
-                        &opcode::BREAKPOINT => panic!("UnImplemented byte-code: BREAKPOINT"),
-                        &opcode::IMPDEP1 => panic!("UnImplemented byte-code: IMPDEP1"),
-                        &opcode::IMPDEP2 => panic!("UnImplemented byte-code: IMPDEP2"),
+                            let array_ref = self.allocate_multi_array(&array_type, &counts)?;
+                            self.eval_stack.push(array_ref);
+                        }
+                        opcode::IFNULL => eval_if_null(self.byte_codes, &mut self.ip, &mut self.eval_stack, true)?,
+                        opcode::IFNONNULL => eval_if_null(self.byte_codes, &mut self.ip, &mut self.eval_stack, false)?,
+                        opcode::GOTO_W => self.ip = read_wide_branch_target(self.byte_codes, &mut self.ip),
+                        opcode::JSR_W => {
+                            let target = read_wide_branch_target(self.byte_codes, &mut self.ip);
+                            self.eval_stack.push(JvmValue::ReturnAddr { val: self.ip + 1 });
+                            self.ip = target;
+                        }
+                        opcode::BREAKPOINT => panic!("UnImplemented byte-code: BREAKPOINT"),
+                        opcode::IMPDEP1 => panic!("UnImplemented byte-code: IMPDEP1"),
+                        opcode::IMPDEP2 => panic!("UnImplemented byte-code: IMPDEP2"),
                         _ => panic!("Impossible to reach..."),
                     },
                     None => {
                         panic!("Malformed array of byte codes! Should have been terminated with Return")
                     }
                 }
-                self.ip += 1;
+                self.ip = self.ip.wrapping_add(1);
             }
         }
 
-        fn eval_if_cmp(byte_codes: &Vec<u8>,
-                       mut ip: &mut usize,
+        fn eval_if_cmp(byte_codes: &[u8],
+                       ip: &mut usize,
                        eval_stack: &mut EvaluationStack,
                        comparator: fn(i32, i32) -> bool) -> Result<(), JvmException> {
-            let lhs = eval_stack.pop_int()?;
             let rhs = eval_stack.pop_int()?;
+            let lhs = eval_stack.pop_int()?;
+
+            evaluate_conditional(byte_codes, ip, comparator, lhs, rhs)
+        }
+
+        fn eval_if_acmp(byte_codes: &[u8],
+                        ip: &mut usize,
+                        eval_stack: &mut EvaluationStack,
+                        branch_if_same: bool) -> Result<(), JvmException> {
+            let rhs = eval_stack.pop_ref()?;
+            let lhs = eval_stack.pop_ref()?;
+
+            let target = read_branch_target(byte_codes, ip);
+            if lhs.is_same_object(&rhs) == branch_if_same {
+                *ip = target;
+            }
+            Ok(())
+        }
 
-            evaluate_conditional(byte_codes, &mut ip, comparator, lhs, rhs)
+        fn eval_if_null(byte_codes: &[u8],
+                        ip: &mut usize,
+                        eval_stack: &mut EvaluationStack,
+                        branch_if_null: bool) -> Result<(), JvmException> {
+            let object_ref = eval_stack.pop_ref()?;
+
+            let target = read_branch_target(byte_codes, ip);
+            if (object_ref == ObjectRef::Null) == branch_if_null {
+                *ip = target;
+            }
+            Ok(())
         }
 
-        fn eval_if(byte_codes: &Vec<u8>,
-                   mut ip: &mut usize,
+        fn eval_if(byte_codes: &[u8],
+                   ip: &mut usize,
                    eval_stack: &mut EvaluationStack,
                    comparator: fn(i32, i32) -> bool) -> Result<(), JvmException> {
             let value = eval_stack.pop_int()?;
-            evaluate_conditional(byte_codes, &mut ip, comparator, value, 0)
+            evaluate_conditional(byte_codes, ip, comparator, value, 0)
         }
 
-        fn evaluate_conditional(byte_codes: &Vec<u8>,
-                                mut ip: &mut usize,
+        fn evaluate_conditional(byte_codes: &[u8],
+                                ip: &mut usize,
                                 comparator: fn(i32, i32) -> bool,
                                 lhs: i32, rhs: i32) -> Result<(), JvmException> {
-            let tmp_ip = *ip;
-
-            let offset = read_offset(byte_codes, ip);
+            let target = read_branch_target(byte_codes, ip);
             if comparator(lhs, rhs) {
-                *ip = tmp_ip + offset;
+                *ip = target;
             }
 
             Ok(())
         }
 
-        fn read_offset(byte_codes: &Vec<u8>, ip: &mut usize) -> usize {
-            //TODO make this cleaner as we're adding 1 to ip in all iterations so we need to offset by 1 less
-            (read_u16(byte_codes, ip) - 1) as usize
+        /// Reads the signed 16 bit offset of a branch instruction, starting at its opcode, and returns the ip
+        /// which continues the execution at the branch target.
+        fn read_branch_target(byte_codes: &[u8], ip: &mut usize) -> usize {
+            let pc = *ip;
+            let offset = read_u16(byte_codes, ip) as i16;
+            jump_to(branch_target(pc, offset as i32))
+        }
+
+        /// Same as `read_branch_target` for the signed 32 bit offsets of GOTO_W and JSR_W.
+        fn read_wide_branch_target(byte_codes: &[u8], ip: &mut usize) -> usize {
+            let pc = *ip;
+            let offset = read_i32(byte_codes, ip);
+            jump_to(branch_target(pc, offset))
+        }
+
+        fn branch_target(pc: usize, offset: i32) -> usize {
+            (pc as isize + offset as isize) as usize
+        }
+
+        /// As ip is incremented by 1 after every instruction, jumps have to land 1 before their target. Jumping
+        /// to the first instruction wraps around, which is undone by the wrapping increment.
+        fn jump_to(target: usize) -> usize {
+            target.wrapping_sub(1)
+        }
+
+        /// The operands of TABLESWITCH and LOOKUPSWITCH start at the next address which is a multiple of 4
+        /// after the opcode, so ip is moved to the last padding byte.
+        fn skip_switch_padding(pc: usize, ip: &mut usize) {
+            *ip = (pc + 4) / 4 * 4 - 1;
+        }
+
+        fn read_i32(byte_codes: &[u8], ip: &mut usize) -> i32 {
+            *ip += 4;
+            i32::from_be_bytes([byte_codes[*ip - 3], byte_codes[*ip - 2], byte_codes[*ip - 1], byte_codes[*ip]])
+        }
+
+        /// Reads the local variable index operand of the current instruction, which is 16 bit wide if the
+        /// instruction was prefixed by WIDE.
+        fn read_local_index(byte_codes: &[u8], ip: &mut usize, wide: &mut bool) -> u16 {
+            if *wide {
+                *wide = false;
+                read_u16(byte_codes, ip)
+            } else {
+                read_u8(byte_codes, ip) as u16
+            }
         }
 
-        fn read_u8(byte_codes: &Vec<u8>, ip: &mut usize) -> u8 {
+        fn read_u8(byte_codes: &[u8], ip: &mut usize) -> u8 {
             *ip += 1;
             byte_codes[*ip]
         }
 
-        fn read_u16(byte_codes: &Vec<u8>, ip: &mut usize) -> u16 {
+        fn read_u16(byte_codes: &[u8], ip: &mut usize) -> u16 {
             *ip += 2;
             ((byte_codes[*ip - 1] as u16) << 8) + byte_codes[*ip] as u16
         }
@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use mockall::*;
 use mockall::predicate::*;
 use mockall_double::double;
 
@@ -12,21 +11,26 @@ use crate::share::memory::heap::Heap;
 #[double]
 use crate::share::runtime::stack_frame::JvmStackFrame;
 use crate::share::utilities::jvm_exception::JvmException;
-use crate::share::utilities::jvm_value::{JvmValue, ObjectRef};
+use crate::share::utilities::jvm_value::{JvmValue, ObjectRef, PrimitiveType};
 use crate::share::utilities::jvm_value::JvmValue::ObjRef;
 use crate::share::memory::oop::Oop::ObjectOop;
+use crate::share::memory::oop::Oop;
 use crate::share::utilities::testing;
-use crate::share::memory::oop::oops::ObjectOopDesc;
+use crate::share::memory::oop::oops::{ObjectOopDesc, ArrayOopDesc, PrimitiveArrayOopDesc};
 use crate::share::utilities::testing::test_class;
 use crate::share::memory::heap::HeapWord;
 use crate::share::interpreter::interpreter::Interpreter;
+use crate::share::native::native_helper_classes::{java_lang_String, java_lang_Throwable};
+use crate::share::classfile::constant_pool::{ConstantPool, CpInfo, Qualifier};
+use crate::share::classfile::attribute::ExceptionHandler;
+#[double]
+use crate::share::classfile::class_loader::ClassLoader;
 
 fn run_interpreter(code: Vec<u8>) -> Result<JvmValue, JvmException> {
     let mut store = JvmLocalVariableStore::new();
     let frame = JvmStackFrame::new();
 
-    let result = Interpreter::interpret(&frame, &code, &mut store);
-    result
+    Interpreter::interpret(&frame, &code, &mut store)
 }
 
 fn assert_stack_empty(interpreter: &Interpreter) {
@@ -111,7 +115,7 @@ pub fn iload_with_incorrect_index() {
         .times(1)
         .returning(|_| panic!("Test Exception"));
 
-    Interpreter::interpret(&frame, &code, &mut store);
+    let _ = Interpreter::interpret(&frame, &code, &mut store);
 }
 
 #[test]
@@ -234,6 +238,110 @@ fn goto() {
     assert_eq!(actual_return, Ok(JvmValue::Int { val: 1 }))
 }
 
+#[test]
+pub fn if_icmplt_compares_operands_in_order() {
+    test_conditional_compare_to_value(opcode::ICONST_0, opcode::ICONST_1, opcode::IF_ICMPLT, 1);
+    test_conditional_compare_to_value(opcode::ICONST_1, opcode::ICONST_0, opcode::IF_ICMPLT, 0);
+}
+
+#[test]
+pub fn if_acmp() {
+    let code = |cond_opcode| vec![cond_opcode, 0x0, 0x5,
+                                  opcode::ICONST_0, opcode::IRETURN,
+                                  opcode::ICONST_1, opcode::IRETURN];
+    let object = testing::test_object_ref();
+    let same = vec![object.clone(), object.clone()];
+    let different = vec![object, testing::new_test_object_ref()];
+
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), run_interpreter_with_stack(code(opcode::IF_ACMPEQ), same.clone()));
+    assert_eq!(Ok(JvmValue::Int { val: 0 }), run_interpreter_with_stack(code(opcode::IF_ACMPEQ), different.clone()));
+    assert_eq!(Ok(JvmValue::Int { val: 0 }), run_interpreter_with_stack(code(opcode::IF_ACMPNE), same));
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), run_interpreter_with_stack(code(opcode::IF_ACMPNE), different));
+}
+
+#[test]
+fn goto_backwards_to_first_instruction() {
+    //counts down the value on the stack to 0
+    let code = vec![opcode::DUP,
+                    opcode::IFEQ, 0x0, 0x8,
+                    opcode::ICONST_M1,
+                    opcode::IADD,
+                    opcode::GOTO, 0xff, 0xfa,
+                    opcode::IRETURN];
+
+    let actual_return = run_interpreter_with_stack(code, vec![JvmValue::Int { val: 3 }]);
+    assert_eq!(actual_return, Ok(JvmValue::Int { val: 0 }))
+}
+
+#[test]
+fn goto_w() {
+    let code = vec![opcode::GOTO_W, 0x0, 0x0, 0x0, 0x7,
+                    opcode::ICONST_0, opcode::IRETURN,
+                    opcode::ICONST_1, opcode::IRETURN];
+
+    let actual_return = run_interpreter(code);
+    assert_eq!(actual_return, Ok(JvmValue::Int { val: 1 }))
+}
+
+#[test]
+fn tableswitch() {
+    let code = vec![opcode::NOP,
+                    opcode::TABLESWITCH, 0x0, 0x0,
+                    0x0, 0x0, 0x0, 23,
+                    0x0, 0x0, 0x0, 0x1,
+                    0x0, 0x0, 0x0, 0x2,
+                    0x0, 0x0, 0x0, 25,
+                    0x0, 0x0, 0x0, 27,
+                    opcode::ICONST_0, opcode::IRETURN,
+                    opcode::ICONST_1, opcode::IRETURN,
+                    opcode::ICONST_2, opcode::IRETURN];
+
+    for (index, expected) in [(0, 0), (1, 1), (2, 2), (3, 0), (-1, 0)] {
+        let actual_return = run_interpreter_with_stack(code.clone(), vec![JvmValue::Int { val: index }]);
+        assert_eq!(actual_return, Ok(JvmValue::Int { val: expected }))
+    }
+}
+
+#[test]
+fn lookupswitch() {
+    let code = vec![opcode::LOOKUPSWITCH, 0x0, 0x0, 0x0,
+                    0x0, 0x0, 0x0, 28,
+                    0x0, 0x0, 0x0, 0x2,
+                    0xff, 0xff, 0xff, 0xfb, 0x0, 0x0, 0x0, 30,
+                    0x0, 0x0, 0x0, 10, 0x0, 0x0, 0x0, 32,
+                    opcode::ICONST_0, opcode::IRETURN,
+                    opcode::ICONST_1, opcode::IRETURN,
+                    opcode::ICONST_2, opcode::IRETURN];
+
+    for (key, expected) in [(-5, 1), (10, 2), (0, 0)] {
+        let actual_return = run_interpreter_with_stack(code.clone(), vec![JvmValue::Int { val: key }]);
+        assert_eq!(actual_return, Ok(JvmValue::Int { val: expected }))
+    }
+}
+
+#[test]
+fn jsr_and_ret() {
+    let code = vec![opcode::JSR, 0x0, 0x6,
+                    opcode::ICONST_2, opcode::IRETURN,
+                    opcode::NOP,
+                    opcode::ASTORE_0,
+                    opcode::RET, 0x0];
+
+    let mut store = JvmLocalVariableStore::new();
+    store.expect_store()
+        .with(eq(JvmValue::ReturnAddr { val: 3 }), eq(0))
+        .times(1)
+        .returning(|_, _| ());
+    store.expect_load()
+        .with(eq(0))
+        .times(1)
+        .returning(|_| JvmValue::ReturnAddr { val: 3 });
+
+    let frame = JvmStackFrame::new();
+    let actual_return = Interpreter::interpret(&frame, &code, &mut store);
+    assert_eq!(actual_return, Ok(JvmValue::Int { val: 2 }))
+}
+
 #[test]
 fn i_return() {
     let code = vec![opcode::ICONST_5, opcode::IRETURN];
@@ -257,25 +365,36 @@ fn aa_store() {
 
     let test_data = HeapWord::test_object(vec![JvmValue::null_obj(); 6]);
     let test_data_clone = test_data.clone();
+    let barrier_holder = test_data.clone();
+    frame.expect_heap()
+        .returning(move || {
+            let mut heap = Heap::new();
+            let holder = barrier_holder.clone();
+            heap.expect_write_barrier()
+                .withf(move |barrier_holder, value| barrier_holder.is_same(&holder) && *value == testing::test_object_ref())
+                .times(1)
+                .return_const(());
+            Arc::new(heap)
+        });
     store.expect_load()
         .with(eq(3))
         .times(1)
-        .returning(move |_| JvmValue::from(ObjectOop(ObjectOopDesc::new(test_class(), test_data_clone.clone()))));
+        .returning(move |_| JvmValue::from(ArrayOopDesc { klass: test_class(), size: 6, instance_data: test_data_clone.clone() }));
 
     store.expect_load()
         .with(eq(2))
         .times(1)
-        .returning(|_| JvmValue::from(5 as i32));
+        .returning(|_| JvmValue::from(5_i32));
 
     store.expect_load()
         .with(eq(1))
         .times(1)
-        .returning(|_| JvmValue::from(8 as i32));
+        .returning(|_| testing::test_object_ref());
 
     let result = Interpreter::interpret(&frame, &code, &mut store);
 
     assert_eq!(Ok(JvmValue::Void {}), result);
-    assert_eq!(Ok(JvmValue::from(8 as i32)), test_data.get_field(5))
+    assert_eq!(Ok(testing::test_object_ref()), test_data.get_field(5))
 }
 
 #[test]
@@ -346,45 +465,1129 @@ pub fn astore_n() {
 #[test]
 pub fn if_nonnull() {
     let code = vec![
-        opcode::ALOAD_0,
-        opcode::IFNONNULL,
+        opcode::IFNONNULL, 0x0, 0x5,
+        opcode::ICONST_0, opcode::IRETURN,
+        opcode::ICONST_1, opcode::IRETURN,
+    ];
 
-        opcode::IRETURN,
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), run_interpreter_with_stack(code.clone(), vec![testing::test_object_ref()]));
+    assert_eq!(Ok(JvmValue::Int { val: 0 }), run_interpreter_with_stack(code, vec![JvmValue::null_obj()]));
+}
+
+#[test]
+pub fn if_null() {
+    let code = vec![
+        opcode::IFNULL, 0x0, 0x5,
+        opcode::ICONST_0, opcode::IRETURN,
+        opcode::ICONST_1, opcode::IRETURN,
     ];
-    let test_object_ref = testing::test_object_ref();
 
+    assert_eq!(Ok(JvmValue::Int { val: 0 }), run_interpreter_with_stack(code.clone(), vec![testing::test_object_ref()]));
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), run_interpreter_with_stack(code, vec![JvmValue::null_obj()]));
+}
+
+fn run_interpreter_with_stack(code: Vec<u8>, stack: Vec<JvmValue>) -> Result<JvmValue, JvmException> {
     let mut store = JvmLocalVariableStore::new();
-    store.expect_store()
-        .with(eq(test_object_ref.clone()), eq(0))
+    let frame = JvmStackFrame::new();
+
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(stack);
+    interpreter.do_interpret()
+}
+
+fn test_load(code: Vec<u8>, expected_index: u16, value: JvmValue) {
+    let mut store = JvmLocalVariableStore::new();
+    let frame = JvmStackFrame::new();
+
+    let returned_value = value.clone();
+    store
+        .expect_load()
+        .with(eq(expected_index))
         .times(1)
-        .returning(|_, _| ());
-    store.expect_store()
-        .with(eq(test_object_ref.clone()), eq(1))
+        .returning(move |_| returned_value.clone());
+
+    let result = Interpreter::interpret(&frame, &code, &mut store);
+
+    assert_eq!(Ok(value), result)
+}
+
+fn test_store(code: Vec<u8>, expected_index: u16, value: JvmValue) {
+    let mut store = JvmLocalVariableStore::new();
+    let frame = JvmStackFrame::new();
+
+    store
+        .expect_store()
+        .with(eq(value.clone()), eq(expected_index))
         .times(1)
         .returning(|_, _| ());
+
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![value]);
+
+    assert_eq!(Ok(JvmValue::Void {}), interpreter.do_interpret());
+    assert_stack_empty(&interpreter);
+}
+
+#[test]
+pub fn lconst_then_lreturn() {
+    assert_eq!(run_interpreter(vec![opcode::LCONST_0, opcode::LRETURN]), Ok(JvmValue::Long { val: 0 }));
+    assert_eq!(run_interpreter(vec![opcode::LCONST_1, opcode::LRETURN]), Ok(JvmValue::Long { val: 1 }));
+}
+
+#[test]
+pub fn dconst_then_dreturn() {
+    assert_eq!(run_interpreter(vec![opcode::DCONST_0, opcode::DRETURN]), Ok(JvmValue::Double { val: 0.0 }));
+    assert_eq!(run_interpreter(vec![opcode::DCONST_1, opcode::DRETURN]), Ok(JvmValue::Double { val: 1.0 }));
+}
+
+#[test]
+pub fn lreturn_with_non_long_on_stack() {
+    let result = run_interpreter(vec![opcode::ICONST_1, opcode::LRETURN]);
+    assert_eq!(result, Err(JvmException::from("Non-long value was found on top of stack when executing LRETURN")))
+}
+
+#[test]
+pub fn dreturn_with_non_double_on_stack() {
+    let result = run_interpreter(vec![opcode::LCONST_1, opcode::DRETURN]);
+    assert_eq!(result, Err(JvmException::from("Non-double value was found on top of stack when executing DRETURN")))
+}
+
+#[test]
+pub fn lload_with_correct_index() {
+    test_load(vec![opcode::LLOAD, 0x10, opcode::LRETURN], 16, JvmValue::Long { val: 12 });
+    test_load(vec![opcode::LLOAD_0, opcode::LRETURN], 0, JvmValue::Long { val: 12 });
+    test_load(vec![opcode::LLOAD_1, opcode::LRETURN], 1, JvmValue::Long { val: 12 });
+    test_load(vec![opcode::LLOAD_2, opcode::LRETURN], 2, JvmValue::Long { val: 12 });
+    test_load(vec![opcode::LLOAD_3, opcode::LRETURN], 3, JvmValue::Long { val: 12 });
+}
+
+#[test]
+pub fn dload_with_correct_index() {
+    test_load(vec![opcode::DLOAD, 0x10, opcode::DRETURN], 16, JvmValue::Double { val: 1.5 });
+    test_load(vec![opcode::DLOAD_0, opcode::DRETURN], 0, JvmValue::Double { val: 1.5 });
+    test_load(vec![opcode::DLOAD_1, opcode::DRETURN], 1, JvmValue::Double { val: 1.5 });
+    test_load(vec![opcode::DLOAD_2, opcode::DRETURN], 2, JvmValue::Double { val: 1.5 });
+    test_load(vec![opcode::DLOAD_3, opcode::DRETURN], 3, JvmValue::Double { val: 1.5 });
+}
+
+#[test]
+pub fn lstore_with_correct_index() {
+    test_store(vec![opcode::LSTORE, 0x10, opcode::RETURN], 16, JvmValue::Long { val: 12 });
+    test_store(vec![opcode::LSTORE_0, opcode::RETURN], 0, JvmValue::Long { val: 12 });
+    test_store(vec![opcode::LSTORE_1, opcode::RETURN], 1, JvmValue::Long { val: 12 });
+    test_store(vec![opcode::LSTORE_2, opcode::RETURN], 2, JvmValue::Long { val: 12 });
+    test_store(vec![opcode::LSTORE_3, opcode::RETURN], 3, JvmValue::Long { val: 12 });
+}
+
+#[test]
+pub fn dstore_with_correct_index() {
+    test_store(vec![opcode::DSTORE, 0x10, opcode::RETURN], 16, JvmValue::Double { val: 1.5 });
+    test_store(vec![opcode::DSTORE_0, opcode::RETURN], 0, JvmValue::Double { val: 1.5 });
+    test_store(vec![opcode::DSTORE_1, opcode::RETURN], 1, JvmValue::Double { val: 1.5 });
+    test_store(vec![opcode::DSTORE_2, opcode::RETURN], 2, JvmValue::Double { val: 1.5 });
+    test_store(vec![opcode::DSTORE_3, opcode::RETURN], 3, JvmValue::Double { val: 1.5 });
+}
+
+#[test]
+pub fn ldc_w_int_and_float() {
+    //the constants follow 256 other entries, so they can't be referenced by LDC
+    let mut constants = vec![CpInfo::Utf8 { string: String::from("padding") }; 256];
+    constants.push(CpInfo::Integer { bytes: 0xffff_fffe });
+    constants.push(CpInfo::Float { bytes: 1.5f32.to_bits() });
+
+    let mut store = JvmLocalVariableStore::new();
+    let mut frame = JvmStackFrame::new();
+    frame.expect_constant_pool()
+        .return_const(ConstantPool::from(constants));
+    frame.expect_heap()
+        .returning(forgetting_heap);
+
+    let code = vec![opcode::LDC_W, 0x1, 0x1, opcode::IRETURN];
+    assert_eq!(Ok(JvmValue::Int { val: -2 }), Interpreter::interpret(&frame, &code, &mut store));
+
+    let code = vec![opcode::LDC_W, 0x1, 0x2, opcode::FRETURN];
+    assert_eq!(Ok(JvmValue::Float { val: 1.5 }), Interpreter::interpret(&frame, &code, &mut store));
+}
+
+#[test]
+pub fn ldc_and_ldc_w_string() {
+    let mut store = JvmLocalVariableStore::new();
+    let mut frame = exception_materializing_frame();
+    frame.expect_constant_pool()
+        .return_const(ConstantPool::from(vec![
+            CpInfo::String { string_index: 2 },
+            CpInfo::Utf8 { string: String::from("constant") },
+        ]));
+
+    for code in [vec![opcode::LDC, 0x1, opcode::ARETURN], vec![opcode::LDC_W, 0x0, 0x1, opcode::ARETURN]] {
+        let string = match Interpreter::interpret(&frame, &code, &mut store) {
+            Ok(ObjRef(string_ref)) => string_ref.dereference().unwrap(),
+            other => panic!("Expected a String, but got {:?}", other),
+        };
+        let string = match string {
+            ObjectOop(string) => string,
+            other => panic!("Expected a String, but got {:?}", other),
+        };
+        assert_eq!(Ok(String::from("constant")), java_lang_String::to_rust_string(&string));
+    }
+}
+
+#[test]
+pub fn ldc2_w_long_and_double() {
+    let mut store = JvmLocalVariableStore::new();
+    let mut frame = JvmStackFrame::new();
+    frame.expect_constant_pool()
+        .return_const(ConstantPool::from(vec![
+            CpInfo::Long { high_bytes: 0x1, low_bytes: 0x2 },
+            CpInfo::Long { high_bytes: 0x1, low_bytes: 0x2 },
+            CpInfo::Double { high_bytes: 0x4004_0000, low_bytes: 0x0 },
+            CpInfo::Double { high_bytes: 0x4004_0000, low_bytes: 0x0 },
+        ]));
+
+    let code = vec![opcode::LDC2_W, 0x0, 0x1, opcode::LRETURN];
+    assert_eq!(Ok(JvmValue::Long { val: 0x1_0000_0002 }), Interpreter::interpret(&frame, &code, &mut store));
+
+    let code = vec![opcode::LDC2_W, 0x0, 0x3, opcode::DRETURN];
+    assert_eq!(Ok(JvmValue::Double { val: 2.5 }), Interpreter::interpret(&frame, &code, &mut store));
+}
+
+#[test]
+pub fn ladd_lsub_lmul() {
+    let operands = || vec![JvmValue::Long { val: 7 }, JvmValue::Long { val: 3 }];
+    assert_eq!(run_interpreter_with_stack(vec![opcode::LADD, opcode::LRETURN], operands()), Ok(JvmValue::Long { val: 10 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::LSUB, opcode::LRETURN], operands()), Ok(JvmValue::Long { val: 4 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::LMUL, opcode::LRETURN], operands()), Ok(JvmValue::Long { val: 21 }));
+}
+
+#[test]
+pub fn long_arithmetic_wraps_on_overflow() {
+    let result = run_interpreter_with_stack(vec![opcode::LADD, opcode::LRETURN],
+                                            vec![JvmValue::Long { val: i64::MAX }, JvmValue::Long { val: 1 }]);
+    assert_eq!(result, Ok(JvmValue::Long { val: i64::MIN }));
+
+    let result = run_interpreter_with_stack(vec![opcode::LSUB, opcode::LRETURN],
+                                            vec![JvmValue::Long { val: i64::MIN }, JvmValue::Long { val: 1 }]);
+    assert_eq!(result, Ok(JvmValue::Long { val: i64::MAX }));
+
+    let result = run_interpreter_with_stack(vec![opcode::LMUL, opcode::LRETURN],
+                                            vec![JvmValue::Long { val: i64::MAX }, JvmValue::Long { val: 2 }]);
+    assert_eq!(result, Ok(JvmValue::Long { val: -2 }));
+}
+
+#[test]
+pub fn lneg() {
+    let result = run_interpreter_with_stack(vec![opcode::LNEG, opcode::LRETURN], vec![JvmValue::Long { val: 5 }]);
+    assert_eq!(result, Ok(JvmValue::Long { val: -5 }));
+
+    let result = run_interpreter_with_stack(vec![opcode::LNEG, opcode::LRETURN], vec![JvmValue::Long { val: i64::MIN }]);
+    assert_eq!(result, Ok(JvmValue::Long { val: i64::MIN }));
+}
+
+#[test]
+pub fn dadd_dsub_dmul_ddiv_drem() {
+    let operands = || vec![JvmValue::Double { val: 7.5 }, JvmValue::Double { val: 2.0 }];
+    assert_eq!(run_interpreter_with_stack(vec![opcode::DADD, opcode::DRETURN], operands()), Ok(JvmValue::Double { val: 9.5 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::DSUB, opcode::DRETURN], operands()), Ok(JvmValue::Double { val: 5.5 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::DMUL, opcode::DRETURN], operands()), Ok(JvmValue::Double { val: 15.0 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::DDIV, opcode::DRETURN], operands()), Ok(JvmValue::Double { val: 3.75 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::DREM, opcode::DRETURN], operands()), Ok(JvmValue::Double { val: 1.5 }));
+}
+
+#[test]
+pub fn ddiv_and_drem_by_zero() {
+    let result = run_interpreter_with_stack(vec![opcode::DDIV, opcode::DRETURN],
+                                            vec![JvmValue::Double { val: -1.0 }, JvmValue::Double { val: 0.0 }]);
+    assert_eq!(result, Ok(JvmValue::Double { val: f64::NEG_INFINITY }));
+
+    let result = run_interpreter_with_stack(vec![opcode::DREM, opcode::DRETURN],
+                                            vec![JvmValue::Double { val: 1.0 }, JvmValue::Double { val: 0.0 }]);
+    match result {
+        Ok(JvmValue::Double { val }) => assert!(val.is_nan()),
+        other => panic!("Expected NaN but got {:?}", other)
+    }
+}
+
+#[test]
+pub fn drem_keeps_sign_of_dividend() {
+    let result = run_interpreter_with_stack(vec![opcode::DREM, opcode::DRETURN],
+                                            vec![JvmValue::Double { val: -7.5 }, JvmValue::Double { val: 2.0 }]);
+    assert_eq!(result, Ok(JvmValue::Double { val: -1.5 }));
+}
+
+#[test]
+pub fn dneg() {
+    let result = run_interpreter_with_stack(vec![opcode::DNEG, opcode::DRETURN], vec![JvmValue::Double { val: 5.0 }]);
+    assert_eq!(result, Ok(JvmValue::Double { val: -5.0 }));
+
+    let result = run_interpreter_with_stack(vec![opcode::DNEG, opcode::DRETURN], vec![JvmValue::Double { val: 0.0 }]);
+    match result {
+        Ok(JvmValue::Double { val }) => assert!(val == 0.0 && val.is_sign_negative()),
+        other => panic!("Expected -0.0 but got {:?}", other)
+    }
+}
+
+#[test]
+pub fn lcmp() {
+    let compare = |lhs: i64, rhs: i64| run_interpreter_with_stack(vec![opcode::LCMP, opcode::IRETURN],
+                                                                  vec![JvmValue::Long { val: lhs }, JvmValue::Long { val: rhs }]);
+    assert_eq!(compare(5, 3), Ok(JvmValue::Int { val: 1 }));
+    assert_eq!(compare(3, 3), Ok(JvmValue::Int { val: 0 }));
+    assert_eq!(compare(i64::MIN, 3), Ok(JvmValue::Int { val: -1 }));
+}
+
+#[test]
+pub fn dcmpl_and_dcmpg() {
+    let compare = |cmp_opcode: u8, lhs: f64, rhs: f64| run_interpreter_with_stack(vec![cmp_opcode, opcode::IRETURN],
+                                                                                  vec![JvmValue::Double { val: lhs }, JvmValue::Double { val: rhs }]);
+    for cmp_opcode in [opcode::DCMPL, opcode::DCMPG] {
+        assert_eq!(compare(cmp_opcode, 5.0, 3.0), Ok(JvmValue::Int { val: 1 }));
+        assert_eq!(compare(cmp_opcode, 3.0, 3.0), Ok(JvmValue::Int { val: 0 }));
+        assert_eq!(compare(cmp_opcode, 0.0, -0.0), Ok(JvmValue::Int { val: 0 }));
+        assert_eq!(compare(cmp_opcode, 1.0, 3.0), Ok(JvmValue::Int { val: -1 }));
+    }
+
+    assert_eq!(compare(opcode::DCMPL, f64::NAN, 3.0), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(compare(opcode::DCMPG, f64::NAN, 3.0), Ok(JvmValue::Int { val: 1 }));
+    assert_eq!(compare(opcode::DCMPL, 3.0, f64::NAN), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(compare(opcode::DCMPG, 3.0, f64::NAN), Ok(JvmValue::Int { val: 1 }));
+}
+
+#[test]
+pub fn lcmp_with_non_long_on_stack() {
+    let result = run_interpreter_with_stack(vec![opcode::LCMP, opcode::IRETURN],
+                                            vec![JvmValue::Long { val: 1 }, JvmValue::Int { val: 1 }]);
+    assert_eq!(result, Err(JvmException::from("JvmValue::Long expected but got: Int { val: 1 }")));
+}
+
+#[test]
+pub fn fconst_then_freturn() {
+    assert_eq!(run_interpreter(vec![opcode::FCONST_0, opcode::FRETURN]), Ok(JvmValue::Float { val: 0.0 }));
+    assert_eq!(run_interpreter(vec![opcode::FCONST_1, opcode::FRETURN]), Ok(JvmValue::Float { val: 1.0 }));
+    assert_eq!(run_interpreter(vec![opcode::FCONST_2, opcode::FRETURN]), Ok(JvmValue::Float { val: 2.0 }));
+}
+
+#[test]
+pub fn freturn_with_non_float_on_stack() {
+    let result = run_interpreter(vec![opcode::DCONST_1, opcode::FRETURN]);
+    assert_eq!(result, Err(JvmException::from("Non-float value was found on top of stack when executing FRETURN")))
+}
+
+#[test]
+pub fn fload_with_correct_index() {
+    test_load(vec![opcode::FLOAD, 0x10, opcode::FRETURN], 16, JvmValue::Float { val: 1.5 });
+    test_load(vec![opcode::FLOAD_0, opcode::FRETURN], 0, JvmValue::Float { val: 1.5 });
+    test_load(vec![opcode::FLOAD_1, opcode::FRETURN], 1, JvmValue::Float { val: 1.5 });
+    test_load(vec![opcode::FLOAD_2, opcode::FRETURN], 2, JvmValue::Float { val: 1.5 });
+    test_load(vec![opcode::FLOAD_3, opcode::FRETURN], 3, JvmValue::Float { val: 1.5 });
+}
+
+#[test]
+pub fn fstore_with_correct_index() {
+    test_store(vec![opcode::FSTORE, 0x10, opcode::RETURN], 16, JvmValue::Float { val: 1.5 });
+    test_store(vec![opcode::FSTORE_0, opcode::RETURN], 0, JvmValue::Float { val: 1.5 });
+    test_store(vec![opcode::FSTORE_1, opcode::RETURN], 1, JvmValue::Float { val: 1.5 });
+    test_store(vec![opcode::FSTORE_2, opcode::RETURN], 2, JvmValue::Float { val: 1.5 });
+    test_store(vec![opcode::FSTORE_3, opcode::RETURN], 3, JvmValue::Float { val: 1.5 });
+}
+
+#[test]
+pub fn fadd_fsub_fmul_fdiv_frem() {
+    let operands = || vec![JvmValue::Float { val: 7.5 }, JvmValue::Float { val: 2.0 }];
+    assert_eq!(run_interpreter_with_stack(vec![opcode::FADD, opcode::FRETURN], operands()), Ok(JvmValue::Float { val: 9.5 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::FSUB, opcode::FRETURN], operands()), Ok(JvmValue::Float { val: 5.5 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::FMUL, opcode::FRETURN], operands()), Ok(JvmValue::Float { val: 15.0 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::FDIV, opcode::FRETURN], operands()), Ok(JvmValue::Float { val: 3.75 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::FREM, opcode::FRETURN], operands()), Ok(JvmValue::Float { val: 1.5 }));
+}
+
+#[test]
+pub fn float_arithmetic_with_nan_and_infinity() {
+    let result = run_interpreter_with_stack(vec![opcode::FDIV, opcode::FRETURN],
+                                            vec![JvmValue::Float { val: 1.0 }, JvmValue::Float { val: 0.0 }]);
+    assert_eq!(result, Ok(JvmValue::Float { val: f32::INFINITY }));
+
+    let result = run_interpreter_with_stack(vec![opcode::FADD, opcode::FRETURN],
+                                            vec![JvmValue::Float { val: f32::NAN }, JvmValue::Float { val: 1.0 }]);
+    match result {
+        Ok(JvmValue::Float { val }) => assert!(val.is_nan()),
+        other => panic!("Expected NaN but got {:?}", other)
+    }
+
+    let result = run_interpreter_with_stack(vec![opcode::FREM, opcode::FRETURN],
+                                            vec![JvmValue::Float { val: f32::INFINITY }, JvmValue::Float { val: 2.0 }]);
+    match result {
+        Ok(JvmValue::Float { val }) => assert!(val.is_nan()),
+        other => panic!("Expected NaN but got {:?}", other)
+    }
+}
+
+#[test]
+pub fn fneg() {
+    let result = run_interpreter_with_stack(vec![opcode::FNEG, opcode::FRETURN], vec![JvmValue::Float { val: 5.0 }]);
+    assert_eq!(result, Ok(JvmValue::Float { val: -5.0 }));
+}
+
+#[test]
+pub fn fcmpl_and_fcmpg() {
+    let compare = |cmp_opcode: u8, lhs: f32, rhs: f32| run_interpreter_with_stack(vec![cmp_opcode, opcode::IRETURN],
+                                                                                  vec![JvmValue::Float { val: lhs }, JvmValue::Float { val: rhs }]);
+    for cmp_opcode in [opcode::FCMPL, opcode::FCMPG] {
+        assert_eq!(compare(cmp_opcode, 5.0, 3.0), Ok(JvmValue::Int { val: 1 }));
+        assert_eq!(compare(cmp_opcode, 3.0, 3.0), Ok(JvmValue::Int { val: 0 }));
+        assert_eq!(compare(cmp_opcode, -0.0, 0.0), Ok(JvmValue::Int { val: 0 }));
+        assert_eq!(compare(cmp_opcode, f32::NEG_INFINITY, 3.0), Ok(JvmValue::Int { val: -1 }));
+    }
+
+    assert_eq!(compare(opcode::FCMPL, f32::NAN, 3.0), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(compare(opcode::FCMPG, f32::NAN, 3.0), Ok(JvmValue::Int { val: 1 }));
+    assert_eq!(compare(opcode::FCMPL, f32::NAN, f32::NAN), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(compare(opcode::FCMPG, f32::NAN, f32::NAN), Ok(JvmValue::Int { val: 1 }));
+}
+
+fn convert(conversion_opcode: u8, return_opcode: u8, value: JvmValue) -> Result<JvmValue, JvmException> {
+    run_interpreter_with_stack(vec![conversion_opcode, return_opcode], vec![value])
+}
+
+#[test]
+pub fn int_conversions() {
+    assert_eq!(convert(opcode::I2L, opcode::LRETURN, JvmValue::Int { val: -5 }), Ok(JvmValue::Long { val: -5 }));
+    assert_eq!(convert(opcode::I2F, opcode::FRETURN, JvmValue::Int { val: -5 }), Ok(JvmValue::Float { val: -5.0 }));
+    assert_eq!(convert(opcode::I2D, opcode::DRETURN, JvmValue::Int { val: i32::MAX }), Ok(JvmValue::Double { val: 2147483647.0 }));
+    // I2F rounds to the nearest representable float
+    assert_eq!(convert(opcode::I2F, opcode::FRETURN, JvmValue::Int { val: 16777217 }), Ok(JvmValue::Float { val: 16777216.0 }));
+}
+
+#[test]
+pub fn long_conversions() {
+    assert_eq!(convert(opcode::L2I, opcode::IRETURN, JvmValue::Long { val: 0x1_0000_0005 }), Ok(JvmValue::Int { val: 5 }));
+    assert_eq!(convert(opcode::L2I, opcode::IRETURN, JvmValue::Long { val: 0xFFFF_FFFF }), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(convert(opcode::L2F, opcode::FRETURN, JvmValue::Long { val: -3 }), Ok(JvmValue::Float { val: -3.0 }));
+    assert_eq!(convert(opcode::L2D, opcode::DRETURN, JvmValue::Long { val: 1 << 40 }), Ok(JvmValue::Double { val: 1099511627776.0 }));
+}
+
+#[test]
+pub fn float_conversions_round_towards_zero() {
+    assert_eq!(convert(opcode::F2I, opcode::IRETURN, JvmValue::Float { val: 2.9 }), Ok(JvmValue::Int { val: 2 }));
+    assert_eq!(convert(opcode::F2I, opcode::IRETURN, JvmValue::Float { val: -2.9 }), Ok(JvmValue::Int { val: -2 }));
+    assert_eq!(convert(opcode::F2L, opcode::LRETURN, JvmValue::Float { val: -2.9 }), Ok(JvmValue::Long { val: -2 }));
+    assert_eq!(convert(opcode::F2D, opcode::DRETURN, JvmValue::Float { val: 0.5 }), Ok(JvmValue::Double { val: 0.5 }));
+    assert_eq!(convert(opcode::D2I, opcode::IRETURN, JvmValue::Double { val: 7.99 }), Ok(JvmValue::Int { val: 7 }));
+    assert_eq!(convert(opcode::D2L, opcode::LRETURN, JvmValue::Double { val: -7.99 }), Ok(JvmValue::Long { val: -7 }));
+    assert_eq!(convert(opcode::D2F, opcode::FRETURN, JvmValue::Double { val: 0.25 }), Ok(JvmValue::Float { val: 0.25 }));
+}
+
+#[test]
+pub fn float_conversions_handle_nan_and_saturate() {
+    assert_eq!(convert(opcode::F2I, opcode::IRETURN, JvmValue::Float { val: f32::NAN }), Ok(JvmValue::Int { val: 0 }));
+    assert_eq!(convert(opcode::F2L, opcode::LRETURN, JvmValue::Float { val: f32::NAN }), Ok(JvmValue::Long { val: 0 }));
+    assert_eq!(convert(opcode::D2I, opcode::IRETURN, JvmValue::Double { val: f64::NAN }), Ok(JvmValue::Int { val: 0 }));
+    assert_eq!(convert(opcode::D2L, opcode::LRETURN, JvmValue::Double { val: f64::NAN }), Ok(JvmValue::Long { val: 0 }));
+
+    assert_eq!(convert(opcode::F2I, opcode::IRETURN, JvmValue::Float { val: 1e20 }), Ok(JvmValue::Int { val: i32::MAX }));
+    assert_eq!(convert(opcode::F2I, opcode::IRETURN, JvmValue::Float { val: f32::NEG_INFINITY }), Ok(JvmValue::Int { val: i32::MIN }));
+    assert_eq!(convert(opcode::F2L, opcode::LRETURN, JvmValue::Float { val: f32::INFINITY }), Ok(JvmValue::Long { val: i64::MAX }));
+    assert_eq!(convert(opcode::D2I, opcode::IRETURN, JvmValue::Double { val: -1e20 }), Ok(JvmValue::Int { val: i32::MIN }));
+    assert_eq!(convert(opcode::D2L, opcode::LRETURN, JvmValue::Double { val: 1e300 }), Ok(JvmValue::Long { val: i64::MAX }));
+
+    assert_eq!(convert(opcode::D2F, opcode::FRETURN, JvmValue::Double { val: 1e300 }), Ok(JvmValue::Float { val: f32::INFINITY }));
+}
+
+#[test]
+pub fn int_narrowing_conversions() {
+    assert_eq!(convert(opcode::I2B, opcode::IRETURN, JvmValue::Int { val: 0x1FF }), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(convert(opcode::I2B, opcode::IRETURN, JvmValue::Int { val: 0x17F }), Ok(JvmValue::Int { val: 127 }));
+    assert_eq!(convert(opcode::I2C, opcode::IRETURN, JvmValue::Int { val: -1 }), Ok(JvmValue::Int { val: 0xFFFF }));
+    assert_eq!(convert(opcode::I2C, opcode::IRETURN, JvmValue::Int { val: 0x1_0041 }), Ok(JvmValue::Int { val: 0x41 }));
+    assert_eq!(convert(opcode::I2S, opcode::IRETURN, JvmValue::Int { val: 0x1_8000 }), Ok(JvmValue::Int { val: -32768 }));
+    assert_eq!(convert(opcode::I2S, opcode::IRETURN, JvmValue::Int { val: 0x7FFF }), Ok(JvmValue::Int { val: 32767 }));
+}
+
+#[test]
+pub fn conversion_with_wrong_type_on_stack() {
+    assert_eq!(convert(opcode::L2I, opcode::IRETURN, JvmValue::Int { val: 1 }),
+               Err(JvmException::from("JvmValue::Long expected but got: Int { val: 1 }")));
+}
+
+#[test]
+pub fn isub_idiv_irem_ineg() {
+    let operands = || vec![JvmValue::Int { val: -7 }, JvmValue::Int { val: 2 }];
+    assert_eq!(run_interpreter_with_stack(vec![opcode::ISUB, opcode::IRETURN], operands()), Ok(JvmValue::Int { val: -9 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::IDIV, opcode::IRETURN], operands()), Ok(JvmValue::Int { val: -3 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::IREM, opcode::IRETURN], operands()), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::INEG, opcode::IRETURN], vec![JvmValue::Int { val: 7 }]), Ok(JvmValue::Int { val: -7 }));
+}
+
+#[test]
+pub fn int_arithmetic_wraps_on_overflow() {
+    let result = run_interpreter_with_stack(vec![opcode::IADD, opcode::IRETURN],
+                                            vec![JvmValue::Int { val: i32::MAX }, JvmValue::Int { val: 1 }]);
+    assert_eq!(result, Ok(JvmValue::Int { val: i32::MIN }));
+
+    let result = run_interpreter_with_stack(vec![opcode::IMUL, opcode::IRETURN],
+                                            vec![JvmValue::Int { val: i32::MAX }, JvmValue::Int { val: 2 }]);
+    assert_eq!(result, Ok(JvmValue::Int { val: -2 }));
+
+    let result = run_interpreter_with_stack(vec![opcode::IDIV, opcode::IRETURN],
+                                            vec![JvmValue::Int { val: i32::MIN }, JvmValue::Int { val: -1 }]);
+    assert_eq!(result, Ok(JvmValue::Int { val: i32::MIN }));
+
+    let result = run_interpreter_with_stack(vec![opcode::IREM, opcode::IRETURN],
+                                            vec![JvmValue::Int { val: i32::MIN }, JvmValue::Int { val: -1 }]);
+    assert_eq!(result, Ok(JvmValue::Int { val: 0 }));
+
+    let result = run_interpreter_with_stack(vec![opcode::INEG, opcode::IRETURN], vec![JvmValue::Int { val: i32::MIN }]);
+    assert_eq!(result, Ok(JvmValue::Int { val: i32::MIN }));
+
+    let result = run_interpreter_with_stack(vec![opcode::LDIV, opcode::LRETURN],
+                                            vec![JvmValue::Long { val: i64::MIN }, JvmValue::Long { val: -1 }]);
+    assert_eq!(result, Ok(JvmValue::Long { val: i64::MIN }));
+}
+
+#[test]
+pub fn integer_division_by_zero_throws_arithmetic_exception() {
+    for (code, operands) in [
+        (opcode::IDIV, vec![JvmValue::Int { val: 1 }, JvmValue::Int { val: 0 }]),
+        (opcode::IREM, vec![JvmValue::Int { val: 1 }, JvmValue::Int { val: 0 }]),
+        (opcode::LDIV, vec![JvmValue::Long { val: 1 }, JvmValue::Long { val: 0 }]),
+        (opcode::LREM, vec![JvmValue::Long { val: 1 }, JvmValue::Long { val: 0 }]),
+    ] {
+        let exception = run_throwing_interpreter(vec![code, opcode::IRETURN], operands);
+
+        assert_eq!(Some(&String::from("java/lang/ArithmeticException")), exception.exception_class());
+        assert_eq!(Some(&String::from("/ by zero")), exception.message());
+        assert!(exception.throwable().is_some());
+    }
+}
+
+#[test]
+pub fn int_shifts_mask_distance() {
+    let shift = |code, value, distance| run_interpreter_with_stack(vec![code, opcode::IRETURN],
+                                                                   vec![JvmValue::Int { val: value }, JvmValue::Int { val: distance }]);
+    assert_eq!(shift(opcode::ISHL, 1, 4), Ok(JvmValue::Int { val: 16 }));
+    assert_eq!(shift(opcode::ISHL, 1, 33), Ok(JvmValue::Int { val: 2 }));
+    assert_eq!(shift(opcode::ISHR, -16, 2), Ok(JvmValue::Int { val: -4 }));
+    assert_eq!(shift(opcode::ISHR, -16, 34), Ok(JvmValue::Int { val: -4 }));
+    assert_eq!(shift(opcode::IUSHR, -1, 28), Ok(JvmValue::Int { val: 0xf }));
+    assert_eq!(shift(opcode::IUSHR, -1, -4), Ok(JvmValue::Int { val: 0xf }));
+}
+
+#[test]
+pub fn long_shifts_take_int_distance() {
+    let shift = |code, value, distance| run_interpreter_with_stack(vec![code, opcode::LRETURN],
+                                                                   vec![JvmValue::Long { val: value }, JvmValue::Int { val: distance }]);
+    assert_eq!(shift(opcode::LSHL, 1, 40), Ok(JvmValue::Long { val: 1 << 40 }));
+    assert_eq!(shift(opcode::LSHL, 1, 65), Ok(JvmValue::Long { val: 2 }));
+    assert_eq!(shift(opcode::LSHR, -256, 4), Ok(JvmValue::Long { val: -16 }));
+    assert_eq!(shift(opcode::LUSHR, -1, 60), Ok(JvmValue::Long { val: 0xf }));
+}
+
+#[test]
+pub fn bitwise_operations() {
+    let ints = || vec![JvmValue::Int { val: 0b1100 }, JvmValue::Int { val: 0b1010 }];
+    assert_eq!(run_interpreter_with_stack(vec![opcode::IAND, opcode::IRETURN], ints()), Ok(JvmValue::Int { val: 0b1000 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::IOR, opcode::IRETURN], ints()), Ok(JvmValue::Int { val: 0b1110 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::IXOR, opcode::IRETURN], ints()), Ok(JvmValue::Int { val: 0b0110 }));
+
+    let longs = || vec![JvmValue::Long { val: 0b1100 << 32 }, JvmValue::Long { val: 0b1010 << 32 }];
+    assert_eq!(run_interpreter_with_stack(vec![opcode::LAND, opcode::LRETURN], longs()), Ok(JvmValue::Long { val: 0b1000 << 32 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::LOR, opcode::LRETURN], longs()), Ok(JvmValue::Long { val: 0b1110 << 32 }));
+    assert_eq!(run_interpreter_with_stack(vec![opcode::LXOR, opcode::LRETURN], longs()), Ok(JvmValue::Long { val: 0b0110 << 32 }));
+}
+
+#[test]
+pub fn bitwise_operation_on_mismatching_types() {
+    let result = run_interpreter_with_stack(vec![opcode::IAND, opcode::IRETURN],
+                                            vec![JvmValue::Int { val: 1 }, JvmValue::Long { val: 1 }]);
+    assert_eq!(result, Err(JvmException::from("Cannot apply bitwise operation to values of type: Int { val: 1 } Long { val: 1 }")));
+}
+
+#[test]
+pub fn iinc() {
+    let mut store = JvmLocalVariableStore::new();
+    let frame = JvmStackFrame::new();
+
+    store.expect_load().with(eq(3)).times(1).returning(|_| JvmValue::Int { val: 10 });
     store.expect_store()
-        .with(eq(test_object_ref.clone()), eq(2))
+        .with(eq(JvmValue::Int { val: 8 }), eq(3))
         .times(1)
         .returning(|_, _| ());
+
+    let code = vec![opcode::IINC, 0x3, 0xfe, opcode::RETURN];
+    assert_eq!(Ok(JvmValue::Void {}), Interpreter::interpret(&frame, &code, &mut store));
+}
+
+#[test]
+pub fn aload_and_astore_with_index() {
+    let mut store = JvmLocalVariableStore::new();
+    let frame = JvmStackFrame::new();
+    store.expect_load().with(eq(7)).times(1).returning(|_| testing::test_object_ref());
+
+    let code = vec![opcode::ALOAD, 0x7, opcode::RETURN];
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    assert_eq!(Ok(JvmValue::Void {}), interpreter.do_interpret());
+    assert_eq!(vec![testing::test_object_ref()], interpreter.stack_contents());
+
+    test_store(vec![opcode::ASTORE, 0x7, opcode::RETURN], 7, testing::test_object_ref());
+}
+
+#[test]
+pub fn wide_load_and_store() {
+    test_load(vec![opcode::WIDE, opcode::ILOAD, 0x01, 0x02, opcode::IRETURN], 258, JvmValue::Int { val: 3 });
+    test_load(vec![opcode::WIDE, opcode::DLOAD, 0x01, 0x00, opcode::DRETURN], 256, JvmValue::Double { val: 1.5 });
+    test_store(vec![opcode::WIDE, opcode::ASTORE, 0x01, 0x00, opcode::RETURN], 256, testing::test_object_ref());
+    test_store(vec![opcode::WIDE, opcode::LSTORE, 0x00, 0x05, opcode::RETURN], 5, JvmValue::Long { val: 2 });
+}
+
+#[test]
+pub fn wide_iinc() {
+    let mut store = JvmLocalVariableStore::new();
+    let frame = JvmStackFrame::new();
+
+    store.expect_load().with(eq(300)).times(1).returning(|_| JvmValue::Int { val: 10 });
     store.expect_store()
-        .with(eq(test_object_ref.clone()), eq(3))
+        .with(eq(JvmValue::Int { val: -990 }), eq(300))
         .times(1)
         .returning(|_, _| ());
 
-    let frame = JvmStackFrame::new();
+    let code = vec![opcode::WIDE, opcode::IINC, 0x01, 0x2c, 0xfc, 0x18, opcode::RETURN];
+    assert_eq!(Ok(JvmValue::Void {}), Interpreter::interpret(&frame, &code, &mut store));
+}
+
+#[test]
+pub fn pop_and_swap() {
+    let long = JvmValue::Long { val: 1 };
+    let int = JvmValue::Int { val: 2 };
+    let reference = testing::test_object_ref();
+
+    let run = |code: Vec<u8>, stack: Vec<JvmValue>| {
+        let mut store = JvmLocalVariableStore::new();
+        let frame = JvmStackFrame::new();
+        let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+        interpreter.set_stack_contents(stack);
+        assert_eq!(Ok(JvmValue::Void {}), interpreter.do_interpret());
+        interpreter.stack_contents()
+    };
+
+    assert_eq!(vec![int.clone()], run(vec![opcode::POP, opcode::RETURN], vec![int.clone(), reference.clone()]));
+    assert_eq!(vec![int.clone()], run(vec![opcode::POP2, opcode::RETURN], vec![int.clone(), long.clone()]));
+    assert_eq!(Vec::<JvmValue>::new(), run(vec![opcode::POP2, opcode::RETURN], vec![int.clone(), reference.clone()]));
+    assert_eq!(vec![reference.clone(), int.clone()], run(vec![opcode::SWAP, opcode::RETURN], vec![int, reference]));
+}
+
+#[test]
+pub fn negative_bipush_and_sipush() {
+    assert_eq!(run_interpreter(vec![opcode::BIPUSH, 0xff, opcode::IRETURN]), Ok(JvmValue::Int { val: -1 }));
+    assert_eq!(run_interpreter(vec![opcode::SIPUSH, 0x01, 0x00, opcode::IRETURN]), Ok(JvmValue::Int { val: 256 }));
+    assert_eq!(run_interpreter(vec![opcode::SIPUSH, 0x80, 0x00, opcode::IRETURN]), Ok(JvmValue::Int { val: -32768 }));
+}
+
+fn run_with_exception_table(code: Vec<u8>, exception_table: Vec<ExceptionHandler>, frame: JvmStackFrame) -> (Result<JvmValue, JvmException>, Vec<JvmValue>) {
+    let mut store = JvmLocalVariableStore::new();
+
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store).with_exception_table(&exception_table);
+    interpreter.set_stack_contents(vec![JvmValue::Int { val: 7 }, testing::test_object_ref()]);
+
+    let result = interpreter.do_interpret();
+    (result, interpreter.stack_contents())
+}
+
+fn catch_type_constant_pool(catch_type: &str) -> ConstantPool {
+    ConstantPool::from(vec![
+        CpInfo::Class { name_index: 2 },
+        CpInfo::Utf8 { string: String::from(catch_type) },
+    ])
+}
+
+#[test]
+pub fn athrow_without_handler() {
+    let (result, _) = run_with_exception_table(vec![opcode::ATHROW], vec![], JvmStackFrame::new());
+
+    assert_eq!(Err(JvmException::from_throwable(testing::test_object_oop())), result);
+}
+
+#[test]
+pub fn athrow_caught_by_catch_all_handler() {
+    let code = vec![opcode::NOP, opcode::ATHROW, opcode::RETURN, opcode::ICONST_1, opcode::IRETURN];
+    let exception_table = vec![ExceptionHandler { start_pc: 0, end_pc: 2, handler_pc: 3, catch_type: 0 }];
+
+    let (result, stack) = run_with_exception_table(code, exception_table, JvmStackFrame::new());
+
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), result);
+    assert_eq!(vec![testing::test_object_ref()], stack);
+}
+
+#[test]
+pub fn athrow_outside_of_handler_range() {
+    let code = vec![opcode::NOP, opcode::ATHROW, opcode::ICONST_1, opcode::IRETURN];
+    let exception_table = vec![ExceptionHandler { start_pc: 0, end_pc: 1, handler_pc: 2, catch_type: 0 }];
+
+    let (result, _) = run_with_exception_table(code, exception_table, JvmStackFrame::new());
+
+    assert_eq!(Err(JvmException::from_throwable(testing::test_object_oop())), result);
+}
+
+#[test]
+pub fn athrow_caught_by_matching_catch_type() {
+    let code = vec![opcode::ATHROW, opcode::ICONST_1, opcode::IRETURN];
+    let exception_table = vec![ExceptionHandler { start_pc: 0, end_pc: 1, handler_pc: 1, catch_type: 1 }];
+
+    let mut frame = JvmStackFrame::new();
+    frame.expect_constant_pool()
+        .return_const(catch_type_constant_pool("java/lang/Object"));
+    frame.expect_class_loader()
+        .returning(|| {
+            let mut class_loader = ClassLoader::new();
+            class_loader.expect_load_class()
+                .with(eq(Qualifier::Class { name: String::from("java/lang/Object") }))
+                .returning(|_| Ok(testing::java_lang_object_class()));
+            Arc::new(class_loader)
+        });
+    frame.expect_heap().returning(forgetting_heap);
+
+    let (result, _) = run_with_exception_table(code, exception_table, frame);
+
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), result);
+}
+
+#[test]
+pub fn athrow_skips_handler_with_other_catch_type() {
+    let code = vec![opcode::ATHROW, opcode::ICONST_1, opcode::IRETURN];
+    let exception_table = vec![ExceptionHandler { start_pc: 0, end_pc: 1, handler_pc: 1, catch_type: 1 }];
+
+    let mut frame = JvmStackFrame::new();
+    frame.expect_constant_pool()
+        .return_const(catch_type_constant_pool("java/lang/String"));
+    frame.expect_class_loader()
+        .returning(|| {
+            let mut class_loader = ClassLoader::new();
+            class_loader.expect_load_class()
+                .with(eq(Qualifier::Class { name: String::from("java/lang/String") }))
+                .returning(|_| Ok(testing::java_lang_string_class()));
+            Arc::new(class_loader)
+        });
+    frame.expect_heap().returning(forgetting_heap);
+
+    let (result, _) = run_with_exception_table(code, exception_table, frame);
+
+    assert_eq!(Err(JvmException::from_throwable(testing::test_object_oop())), result);
+}
+
+
+#[test]
+pub fn exception_thrown_by_invoked_method_is_caught() {
+    let code = vec![opcode::INVOKESTATIC, 0x0, 0x1, opcode::RETURN, opcode::ICONST_1, opcode::IRETURN];
+    let exception_table = vec![ExceptionHandler { start_pc: 0, end_pc: 3, handler_pc: 4, catch_type: 0 }];
+
+    let method_ref = Qualifier::MethodRef {
+        class_name: String::from("tests/unit/UnitTestClass"),
+        name: String::from("<init>"),
+        descriptor: String::from("()V"),
+    };
+
+    let mut frame = JvmStackFrame::new();
+    frame.expect_constant_pool()
+        .return_const(ConstantPool::from(vec![
+            CpInfo::MethodRef { class_index: 2, name_and_type_index: 4 },
+            CpInfo::Class { name_index: 3 },
+            CpInfo::Utf8 { string: String::from("tests/unit/UnitTestClass") },
+            CpInfo::NameAndType { name_index: 5, descriptor_index: 6 },
+            CpInfo::Utf8 { string: String::from("<init>") },
+            CpInfo::Utf8 { string: String::from("()V") },
+        ]));
+    let class_loader_method_ref = method_ref.clone();
+    frame.expect_class_loader()
+        .returning(move || {
+            let method_ref = class_loader_method_ref.clone();
+            let mut class_loader = ClassLoader::new();
+            class_loader.expect_lookup_static_method()
+                .with(eq(method_ref.clone()))
+                .returning(move |_| Ok(test_class().get_method_by_qualified_name(&method_ref).unwrap()));
+            Arc::new(class_loader)
+        });
+    frame.expect_execute_method()
+        .times(1)
+        .returning(|_, _| Err(JvmException::from_throwable(testing::test_object_oop())));
+    frame.expect_heap()
+        .returning(forgetting_heap);
+
+    let (result, stack) = run_with_exception_table(code, exception_table, frame);
+
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), result);
+    assert_eq!(vec![testing::test_object_ref()], stack);
+}
+
+/// Heap which only expects the interpreter to forget the objects it allocated, before it calls into other code.
+fn forgetting_heap() -> Arc<dyn crate::share::memory::heap::Heap> {
+    let mut heap = Heap::new();
+    heap.expect_forget_recent_allocations()
+        .return_const(());
+    Arc::new(heap)
+}
+
+/// Frame which can allocate the instances of exceptions raised by the VM, every exception class is substituted by
+/// java/lang/Throwable and other classes by the test class.
+fn exception_materializing_frame() -> JvmStackFrame {
+    let mut frame = JvmStackFrame::new();
+    frame.expect_class_loader()
+        .returning(|| {
+            let mut class_loader = ClassLoader::new();
+            class_loader.expect_load_and_init_class()
+                .returning(|class_name| match class_name.as_str() {
+                    "java/lang/String" => Ok(testing::java_lang_string_class()),
+                    name if name.ends_with("Exception") || name.ends_with("Error") => Ok(testing::java_lang_throwable_class()),
+                    _ => Ok(test_class()),
+                });
+            Arc::new(class_loader)
+        });
+    frame.expect_heap()
+        .returning(|| {
+            let mut heap = Heap::new();
+            heap.expect_allocate_object()
+                .returning(|klass| {
+                    let field_count = klass.instance_field_count();
+                    Ok(ObjectOopDesc::new(klass, HeapWord::test_object(vec![JvmValue::null_obj(); field_count])))
+                });
+            heap.expect_allocate_primitive_array()
+                .returning(|inner_type, size| Ok(PrimitiveArrayOopDesc {
+                    inner_type,
+                    size,
+                    instance_data: HeapWord::test_object(vec![JvmValue::from(inner_type); size as usize]),
+                }));
+            heap.expect_write_barrier()
+                .return_const(());
+            heap.expect_forget_recent_allocations()
+                .return_const(());
+            Arc::new(heap)
+        });
+    frame
+}
+
+fn run_throwing_interpreter(code: Vec<u8>, stack: Vec<JvmValue>) -> JvmException {
+    let mut store = JvmLocalVariableStore::new();
+    let frame = exception_materializing_frame();
+
     let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(stack);
+    interpreter.do_interpret().unwrap_err()
+}
 
-    interpreter.set_stack_contents(
-        vec![
-            test_object_ref.clone(),
-            test_object_ref.clone(),
-            test_object_ref.clone(),
-            test_object_ref.clone()
-        ]
-    );
+#[test]
+pub fn athrow_null_throws_null_pointer_exception() {
+    let exception = run_throwing_interpreter(vec![opcode::ATHROW], vec![JvmValue::null_obj()]);
+
+    assert_eq!(Some(&String::from("java/lang/NullPointerException")), exception.exception_class());
+    assert_eq!(None, exception.message());
+    match exception.throwable() {
+        Some(ObjectOop(throwable)) => assert_eq!(Ok(JvmValue::null_obj()), java_lang_Throwable::get_detail_message(throwable)),
+        other => panic!("Throwable should have been allocated, but was {:?}", other)
+    }
+}
+
+#[test]
+pub fn vm_exception_has_detail_message() {
+    let array = JvmValue::from(ArrayOopDesc { klass: test_class(), size: 2, instance_data: HeapWord::test_object(vec![JvmValue::null_obj(); 2]) });
+    let exception = run_throwing_interpreter(vec![opcode::AALOAD, opcode::ARETURN], vec![array, JvmValue::Int { val: 2 }]);
+
+    assert_eq!(Some(&String::from("java/lang/ArrayIndexOutOfBoundsException")), exception.exception_class());
+    assert_eq!(Some(&String::from("Index 2 out of bounds for length 2")), exception.message());
+    match exception.throwable() {
+        Some(ObjectOop(throwable)) => assert_ne!(Ok(JvmValue::null_obj()), java_lang_Throwable::get_detail_message(throwable)),
+        other => panic!("Throwable should have been allocated, but was {:?}", other)
+    }
+}
+
+#[test]
+pub fn vm_exception_caught_by_handler() {
+    let code = vec![opcode::ACONST_NULL, opcode::ARRAYLENGTH, opcode::IRETURN, opcode::ICONST_1, opcode::IRETURN];
+    let exception_table = vec![ExceptionHandler { start_pc: 1, end_pc: 2, handler_pc: 3, catch_type: 0 }];
+
+    let (result, stack) = run_with_exception_table(code, exception_table, exception_materializing_frame());
+
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), result);
+    match stack.as_slice() {
+        [JvmValue::ObjRef(ObjectRef::Ref(ObjectOop(_)))] => {}
+        other => panic!("Only the exception should be on the stack, but was {:?}", other)
+    }
+}
+
+#[test]
+pub fn anewarray_with_negative_size() {
+    let mut frame = exception_materializing_frame();
+    frame.expect_constant_pool()
+        .return_const(catch_type_constant_pool("java/lang/Object"));
+
+    let mut store = JvmLocalVariableStore::new();
+    let code = vec![opcode::ANEWARRAY, 0x0, 0x1, opcode::ARETURN];
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![JvmValue::Int { val: -1 }]);
+    let exception = interpreter.do_interpret().unwrap_err();
+
+    assert_eq!(Some(&String::from("java/lang/NegativeArraySizeException")), exception.exception_class());
+    assert_eq!(Some(&String::from("-1")), exception.message());
+}
+
+fn run_type_check(code: Vec<u8>, type_name: &str, object: JvmValue) -> (Result<JvmValue, JvmException>, Vec<JvmValue>) {
+    let mut frame = exception_materializing_frame();
+    frame.expect_constant_pool()
+        .return_const(catch_type_constant_pool(type_name));
 
+    let mut store = JvmLocalVariableStore::new();
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![object]);
     let result = interpreter.do_interpret();
+    (result, interpreter.stack_contents())
+}
+
+fn int_array() -> JvmValue {
+    JvmValue::from(PrimitiveArrayOopDesc { inner_type: PrimitiveType::Int, size: 0, instance_data: HeapWord::test_object(vec![]) })
+}
+
+#[test]
+pub fn instanceof_superclass() {
+    let mut frame = JvmStackFrame::new();
+    frame.expect_constant_pool()
+        .return_const(catch_type_constant_pool("java/lang/Object"));
+    frame.expect_class_loader()
+        .returning(|| {
+            let mut class_loader = ClassLoader::new();
+            class_loader.expect_load_class()
+                .with(eq(Qualifier::Class { name: String::from("java/lang/Object") }))
+                .returning(|_| Ok(testing::java_lang_object_class()));
+            Arc::new(class_loader)
+        });
+    frame.expect_heap().returning(forgetting_heap);
+
+    let mut store = JvmLocalVariableStore::new();
+    let code = vec![opcode::INSTANCEOF, 0x0, 0x1, opcode::IRETURN];
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![testing::test_object_ref()]);
+
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), interpreter.do_interpret());
+}
+
+#[test]
+pub fn instanceof_null() {
+    let (result, _) = run_type_check(vec![opcode::INSTANCEOF, 0x0, 0x1, opcode::IRETURN], "java/lang/Object", JvmValue::null_obj());
+
+    assert_eq!(Ok(JvmValue::Int { val: 0 }), result);
+}
+
+#[test]
+pub fn instanceof_primitive_array() {
+    let code = vec![opcode::INSTANCEOF, 0x0, 0x1, opcode::IRETURN];
+
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), run_type_check(code.clone(), "[I", int_array()).0);
+    assert_eq!(Ok(JvmValue::Int { val: 0 }), run_type_check(code.clone(), "[J", int_array()).0);
+    assert_eq!(Ok(JvmValue::Int { val: 1 }), run_type_check(code.clone(), "java/lang/Cloneable", int_array()).0);
+    assert_eq!(Ok(JvmValue::Int { val: 0 }), run_type_check(code, "java/lang/String", int_array()).0);
+}
+
+#[test]
+pub fn checkcast_null_passes() {
+    let (result, stack) = run_type_check(vec![opcode::CHECKCAST, 0x0, 0x1, opcode::RETURN], "java/lang/String", JvmValue::null_obj());
 
     assert_eq!(Ok(JvmValue::Void {}), result);
-    assert_stack_empty(&interpreter);
+    assert_eq!(vec![JvmValue::null_obj()], stack);
+}
+
+#[test]
+pub fn checkcast_to_incompatible_type() {
+    let (result, _) = run_type_check(vec![opcode::CHECKCAST, 0x0, 0x1, opcode::RETURN], "java/lang/String", int_array());
+    let exception = result.unwrap_err();
+
+    assert_eq!(Some(&String::from("java/lang/ClassCastException")), exception.exception_class());
+    assert_eq!(Some(&String::from("class [I cannot be cast to class java.lang.String")), exception.message());
+}
+
+fn primitive_array(inner_type: PrimitiveType, size: usize) -> PrimitiveArrayOopDesc {
+    PrimitiveArrayOopDesc {
+        inner_type,
+        size: size as i32,
+        instance_data: HeapWord::test_object(vec![JvmValue::from(inner_type); size]),
+    }
+}
+
+#[test]
+pub fn primitive_array_store_then_load() {
+    let cases = vec![
+        (PrimitiveType::Int, opcode::IASTORE, opcode::IALOAD, JvmValue::Int { val: -7 }, JvmValue::Int { val: -7 }),
+        (PrimitiveType::Long, opcode::LASTORE, opcode::LALOAD, JvmValue::Long { val: 1 << 40 }, JvmValue::Long { val: 1 << 40 }),
+        (PrimitiveType::Float, opcode::FASTORE, opcode::FALOAD, JvmValue::Float { val: 1.5 }, JvmValue::Float { val: 1.5 }),
+        (PrimitiveType::Double, opcode::DASTORE, opcode::DALOAD, JvmValue::Double { val: 2.5 }, JvmValue::Double { val: 2.5 }),
+        (PrimitiveType::Byte, opcode::BASTORE, opcode::BALOAD, JvmValue::Int { val: 0x1ff }, JvmValue::Int { val: -1 }),
+        (PrimitiveType::Boolean, opcode::BASTORE, opcode::BALOAD, JvmValue::Int { val: 3 }, JvmValue::Int { val: 1 }),
+        (PrimitiveType::Short, opcode::SASTORE, opcode::SALOAD, JvmValue::Int { val: 0x18000 }, JvmValue::Int { val: -32768 }),
+        (PrimitiveType::Char, opcode::CASTORE, opcode::CALOAD, JvmValue::Int { val: -1 }, JvmValue::Int { val: 0xffff }),
+    ];
+
+    for (inner_type, store_opcode, load_opcode, stored, expected) in cases {
+        let array = JvmValue::from(primitive_array(inner_type, 2));
+        let code = vec![store_opcode, load_opcode, opcode::RETURN];
+
+        let mut store = JvmLocalVariableStore::new();
+        let frame = JvmStackFrame::new();
+        let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+        interpreter.set_stack_contents(vec![array.clone(), JvmValue::Int { val: 1 }, array, JvmValue::Int { val: 1 }, stored]);
+
+        assert_eq!(Ok(JvmValue::Void {}), interpreter.do_interpret());
+        assert_eq!(vec![expected], interpreter.stack_contents());
+    }
+}
+
+#[test]
+pub fn primitive_array_load_out_of_bounds() {
+    let array = JvmValue::from(primitive_array(PrimitiveType::Int, 2));
+    let exception = run_throwing_interpreter(vec![opcode::IALOAD, opcode::IRETURN], vec![array, JvmValue::Int { val: -1 }]);
+
+    assert_eq!(Some(&String::from("java/lang/ArrayIndexOutOfBoundsException")), exception.exception_class());
+    assert_eq!(Some(&String::from("Index -1 out of bounds for length 2")), exception.message());
+}
+
+#[test]
+pub fn newarray() {
+    let mut frame = JvmStackFrame::new();
+    frame.expect_heap()
+        .returning(|| {
+            let mut heap = Heap::new();
+            heap.expect_allocate_primitive_array()
+                .with(eq(PrimitiveType::Char), eq(3))
+                .returning(|inner_type, size| Ok(primitive_array(inner_type, size as usize)));
+            heap.expect_forget_recent_allocations()
+                .return_const(());
+            Arc::new(heap)
+        });
+
+    let mut store = JvmLocalVariableStore::new();
+    let code = vec![opcode::NEWARRAY, 5, opcode::RETURN];
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![JvmValue::Int { val: 3 }]);
+
+    assert_eq!(Ok(JvmValue::Void {}), interpreter.do_interpret());
+    match interpreter.stack_contents().as_slice() {
+        [JvmValue::ObjRef(ObjectRef::Ref(Oop::PrimitiveArrayOop(array)))] => {
+            assert_eq!(PrimitiveType::Char, array.inner_type);
+            assert_eq!(3, array.size);
+        }
+        other => panic!("Expected a char array, but got {:?}", other)
+    }
+}
+
+#[test]
+pub fn multianewarray() {
+    let mut frame = JvmStackFrame::new();
+    frame.expect_constant_pool()
+        .return_const(catch_type_constant_pool("[[[I"));
+    frame.expect_class_loader()
+        .returning(|| {
+            let mut class_loader = ClassLoader::new();
+            class_loader.expect_load_class()
+                .with(eq(Qualifier::Class { name: String::from("java/lang/Object") }))
+                .returning(|_| Ok(testing::java_lang_object_class()));
+            Arc::new(class_loader)
+        });
+    frame.expect_heap()
+        .returning(|| {
+            let mut heap = Heap::new();
+            heap.expect_allocate_array()
+                .returning(|klass, size| Ok(ArrayOopDesc { klass, size, instance_data: HeapWord::test_object(vec![JvmValue::null_obj(); size as usize]) }));
+            heap.expect_write_barrier()
+                .return_const(());
+            heap.expect_forget_recent_allocations()
+                .return_const(());
+            Arc::new(heap)
+        });
+
+    let mut store = JvmLocalVariableStore::new();
+    let code = vec![opcode::MULTIANEWARRAY, 0x0, 0x1, 2, opcode::RETURN];
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![JvmValue::Int { val: 2 }, JvmValue::Int { val: 3 }]);
+
+    assert_eq!(Ok(JvmValue::Void {}), interpreter.do_interpret());
+    let outer = match interpreter.stack_contents().pop() {
+        Some(JvmValue::ObjRef(ObjectRef::Ref(outer @ Oop::ArrayOop(_)))) => outer,
+        other => panic!("Expected an array of arrays, but got {:?}", other)
+    };
+    assert_eq!(Ok(2), outer.array_length());
+    match outer.instance_data().get_field(1) {
+        Ok(JvmValue::ObjRef(ObjectRef::Ref(inner))) => {
+            assert_eq!(Ok(3), inner.array_length());
+            assert_eq!(Ok(JvmValue::null_obj()), inner.instance_data().get_field(2));
+        }
+        other => panic!("Expected an array, but got {:?}", other)
+    }
+}
+
+#[test]
+pub fn multianewarray_with_negative_count() {
+    let mut frame = exception_materializing_frame();
+    frame.expect_constant_pool()
+        .return_const(catch_type_constant_pool("[[I"));
+
+    let mut store = JvmLocalVariableStore::new();
+    let code = vec![opcode::MULTIANEWARRAY, 0x0, 0x1, 2, opcode::RETURN];
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![JvmValue::Int { val: 2 }, JvmValue::Int { val: -3 }]);
+    let exception = interpreter.do_interpret().unwrap_err();
+
+    assert_eq!(Some(&String::from("java/lang/NegativeArraySizeException")), exception.exception_class());
+    assert_eq!(Some(&String::from("-3")), exception.message());
+}
+
+#[test]
+pub fn aastore_incompatible_value() {
+    let array = JvmValue::from(ArrayOopDesc { klass: testing::java_lang_string_class(), size: 1, instance_data: HeapWord::test_object(vec![JvmValue::null_obj()]) });
+    let exception = run_throwing_interpreter(vec![opcode::AASTORE, opcode::RETURN],
+                                             vec![array, JvmValue::Int { val: 0 }, testing::test_object_ref()]);
+
+    assert_eq!(Some(&String::from("java/lang/ArrayStoreException")), exception.exception_class());
+    assert_eq!(Some(&String::from("tests.unit.UnitTestClass")), exception.message());
+}
+
+#[test]
+pub fn monitorenter_and_monitorexit() {
+    let object = testing::test_object_ref();
+    let code = vec![opcode::MONITORENTER, opcode::MONITORENTER, opcode::MONITOREXIT, opcode::RETURN];
+
+    let mut store = JvmLocalVariableStore::new();
+    let mut frame = JvmStackFrame::new();
+    frame.expect_heap()
+        .returning(forgetting_heap);
+    let mut interpreter = Interpreter::new(&frame, &code, &mut store);
+    interpreter.set_stack_contents(vec![object.clone(), object.clone(), object.clone()]);
+
+    assert_eq!(Ok(JvmValue::Void {}), interpreter.do_interpret());
+    match object {
+        JvmValue::ObjRef(ObjectRef::Ref(oop)) => {
+            assert!(oop.monitor().is_owned_by_current_thread());
+            assert_eq!(Ok(()), oop.monitor().exit());
+            assert!(!oop.monitor().is_owned_by_current_thread());
+        }
+        other => panic!("Expected object, but got {:?}", other)
+    }
+}
+
+#[test]
+pub fn monitorexit_without_owning_monitor() {
+    let exception = run_throwing_interpreter(vec![opcode::MONITOREXIT, opcode::RETURN], vec![testing::test_object_ref()]);
+
+    assert_eq!(Some(&String::from("java/lang/IllegalMonitorStateException")), exception.exception_class());
+}
+
+#[test]
+pub fn monitorenter_on_null() {
+    let exception = run_throwing_interpreter(vec![opcode::MONITORENTER, opcode::RETURN], vec![JvmValue::null_obj()]);
+
+    assert_eq!(Some(&String::from("java/lang/NullPointerException")), exception.exception_class());
+}
+
+#[test]
+pub fn aconst_null_then_areturn() {
+    let result = run_interpreter(vec![opcode::ACONST_NULL, opcode::ARETURN]);
+    assert_eq!(result, Ok(JvmValue::null_obj()))
+}
+
+#[test]
+pub fn areturn_of_non_reference_fails() {
+    let result = run_interpreter(vec![opcode::ICONST_1, opcode::ARETURN]);
+    assert!(result.is_err())
 }
@@ -6,8 +6,10 @@ mod local_variables_test;
 
 #[cfg_attr(test, mockall::automock)]
 pub trait JvmLocalVariableStore {
-    fn store(&mut self, var: JvmValue, ind: u8);
-    fn load(&self, ind: u8) -> JvmValue;
+    fn store(&mut self, var: JvmValue, ind: u16);
+    fn load(&self, ind: u16) -> JvmValue;
+    /// All the values stored in the local variables, the roots of the garbage collector.
+    fn values(&self) -> Vec<JvmValue>;
 }
 
 pub struct LocalVariableStore {
@@ -24,11 +26,20 @@ impl LocalVariableStore {
 }
 
 impl JvmLocalVariableStore for LocalVariableStore {
-    fn store(&mut self, var: JvmValue, ind: u8) {
+    /// Stores the value at the given index. Longs and doubles occupy two consecutive slots,
+    /// so the slot after a category 2 value is invalidated.
+    fn store(&mut self, var: JvmValue, ind: u16) {
+        if var.category() == 2 {
+            self.store[ind as usize + 1] = JvmValue::Void {};
+        }
         self.store[ind as usize] = var;
     }
 
-    fn load(&self, ind: u8) -> JvmValue {
+    fn load(&self, ind: u16) -> JvmValue {
         self.store[ind as usize].clone()
     }
+
+    fn values(&self) -> Vec<JvmValue> {
+        self.store.clone()
+    }
 }
@@ -9,4 +9,17 @@ pub fn store_and_then_load() {
 
     assert_eq!(JvmValue::Int {val: 1}, local_variables.load(3));
     assert_eq!(JvmValue::Int {val: 4}, local_variables.load(1));
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn store_category_2_values_in_two_slots() {
+    let mut local_variables = LocalVariableStore::new(4);
+    local_variables.store(JvmValue::Int { val: 1 }, 1);
+    local_variables.store(JvmValue::Long { val: 7 }, 0);
+    local_variables.store(JvmValue::Double { val: 2.5 }, 2);
+
+    assert_eq!(JvmValue::Long { val: 7 }, local_variables.load(0));
+    assert_eq!(JvmValue::Void {}, local_variables.load(1));
+    assert_eq!(JvmValue::Double { val: 2.5 }, local_variables.load(2));
+    assert_eq!(JvmValue::Void {}, local_variables.load(3));
+}
@@ -0,0 +1,49 @@
+use std::time::Instant;
+
+use crate::share::memory::heap::Collection;
+use crate::share::utilities::context::GlobalContext;
+use crate::share::utilities::jvm_value::JvmValue;
+
+/// Stops every thread executing Java code, then frees the objects of the heap which are not reachable from the
+/// roots. Returns how many objects were freed. The calling thread has to publish its frames before the call.
+pub fn collect_garbage(context: &GlobalContext) -> usize {
+    run_collection(context, Collection::Full)
+}
+
+/// Runs the kind of collection requested by the heap.
+pub fn run_collection(context: &GlobalContext, collection: Collection) -> usize {
+    let started = Instant::now();
+    let freed_objects = context.safepoint().stop_the_world(|| {
+        match collection {
+            Collection::Young => context.heap().collect_young_garbage(enumerate_thread_roots(context)),
+            Collection::Full => context.heap().collect_garbage(enumerate_roots(context)),
+        }
+    });
+    log::debug!("{:?} garbage collection freed {} objects, paused for {:?}", collection, freed_objects, started.elapsed());
+    freed_objects
+}
+
+/// The roots of the garbage collector: static fields and java/lang/Class instances of the loaded classes,
+/// java/lang/Thread instances of the live threads and the values of their frames.
+fn enumerate_roots(context: &GlobalContext) -> Vec<JvmValue> {
+    let mut roots: Vec<JvmValue> = context.class_loader()
+        .loaded_classes()
+        .into_iter()
+        .flat_map(|class| class.gc_roots())
+        .collect();
+    roots.extend(enumerate_thread_roots(context));
+    roots
+}
+
+/// The roots of young collections, static fields are only scanned when the write barrier recorded them.
+fn enumerate_thread_roots(context: &GlobalContext) -> Vec<JvmValue> {
+    context.threads()
+        .live_threads()
+        .into_iter()
+        .flat_map(|thread| {
+            let mut roots = thread.frame_roots();
+            roots.push(JvmValue::from(thread.thread_object().clone()));
+            roots
+        })
+        .collect()
+}
@@ -0,0 +1,451 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::share::classfile::klass::Klass;
+use crate::share::memory::heap::{Collection, GarbageCollectorHook, Heap, HeapWord, HeapWordKey, JvmHeap, RecentAllocations, REFERENCE_SIZE};
+use crate::share::memory::heap_dump;
+use crate::share::memory::heap_dump::HeapDumpRoots;
+use crate::share::memory::oop::Oop;
+use crate::share::memory::oop::oops::{ArrayOopDesc, ObjectOopDesc, PrimitiveArrayOopDesc};
+use crate::share::utilities::jvm_exception::JvmException;
+use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType};
+
+#[cfg(test)]
+#[path = "./generational_heap_test.rs"]
+mod generational_heap_test;
+
+//the default sizing of HotSpot: the old generation is twice as large as the young one, whose eden is eight
+//times as large as either of its survivor spaces
+const NEW_RATIO: usize = 2;
+const SURVIVOR_RATIO: usize = 8;
+//how many young collections an object has to survive before it's promoted to the old generation
+const MAX_TENURING_THRESHOLD: u8 = 15;
+
+/// A contiguous region of the young generation, objects are allocated in it by bumping its top.
+struct Space {
+    capacity: usize,
+    top: usize,
+}
+
+impl Space {
+    fn new(capacity: usize) -> Space {
+        Space { capacity, top: 0 }
+    }
+
+    fn try_allocate(&mut self, size: usize) -> bool {
+        if self.capacity - self.top < size {
+            return false;
+        }
+        self.top += size;
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Location {
+    Eden,
+    Survivor,
+}
+
+struct YoungObject {
+    oop: Oop,
+    size: usize,
+    age: u8,
+    location: Location,
+}
+
+/// New objects are allocated in eden. The objects surviving a young collection are copied into the empty
+/// survivor space, the to space, then the survivor spaces are swapped, so eden and the to space are empty
+/// again. Objects are reference counted, so copying moves their allocation rather than their storage.
+struct YoungGeneration {
+    eden: Space,
+    from_space: Space,
+    to_space: Space,
+    objects: HashMap<HeapWordKey, YoungObject>,
+}
+
+impl YoungGeneration {
+    fn new(young_size: usize) -> YoungGeneration {
+        let survivor_size = young_size / (SURVIVOR_RATIO + 2);
+        YoungGeneration {
+            eden: Space::new(young_size - 2 * survivor_size),
+            from_space: Space::new(survivor_size),
+            to_space: Space::new(survivor_size),
+            objects: HashMap::new(),
+        }
+    }
+
+    fn contains(&self, heap_word: &HeapWord) -> bool {
+        self.objects.contains_key(&heap_word.key())
+    }
+
+    fn try_allocate(&mut self, oop: &Oop, size: usize) -> bool {
+        if !self.eden.try_allocate(size) {
+            return false;
+        }
+        let object = YoungObject { oop: oop.clone(), size, age: 0, location: Location::Eden };
+        self.objects.insert(oop.instance_data().key(), object);
+        true
+    }
+
+    /// Returns the keys of the young objects reachable from the roots. References to old objects aren't
+    /// followed, the young objects they reference are roots themselves.
+    fn trace(&self, roots: Vec<HeapWord>) -> HashSet<HeapWordKey> {
+        let mut live = HashSet::new();
+        let mut to_visit = roots;
+
+        while let Some(heap_word) = to_visit.pop() {
+            let key = heap_word.key();
+            if !self.objects.contains_key(&key) || !live.insert(key) {
+                continue;
+            }
+            to_visit.extend(heap_word.references());
+        }
+        live
+    }
+
+    /// Copies the live objects into the to space, or promotes them to the old generation once they are old
+    /// enough or the to space is full. The dead objects are freed. Returns the promoted objects.
+    fn evacuate(&mut self, live: &HashSet<HeapWordKey>, old: &mut OldGeneration) -> Vec<HeapWord> {
+        let mut promoted = Vec::new();
+        for (key, mut object) in std::mem::take(&mut self.objects) {
+            if !live.contains(&key) {
+                JvmHeap::free(object.oop.instance_data());
+                continue;
+            }
+
+            object.age += 1;
+            if object.age < MAX_TENURING_THRESHOLD && self.to_space.try_allocate(object.size) {
+                object.location = Location::Survivor;
+                self.objects.insert(key, object);
+            } else {
+                promoted.push(object.oop.instance_data().clone());
+                old.promote(object.oop, object.size);
+            }
+        }
+
+        self.eden.top = 0;
+        self.from_space.top = 0;
+        std::mem::swap(&mut self.from_space, &mut self.to_space);
+        promoted
+    }
+
+    /// Frees the objects which aren't marked, then compacts the remaining ones in their spaces.
+    fn sweep(&mut self, marked: &HashSet<HeapWordKey>) -> usize {
+        let object_count = self.objects.len();
+        self.objects.retain(|key, object| {
+            let reachable = marked.contains(key);
+            if !reachable {
+                JvmHeap::free(object.oop.instance_data());
+            }
+            reachable
+        });
+
+        self.eden.top = self.used_bytes_in(Location::Eden);
+        self.from_space.top = self.used_bytes_in(Location::Survivor);
+        object_count - self.objects.len()
+    }
+
+    fn used_bytes_in(&self, location: Location) -> usize {
+        self.objects.values()
+            .filter(|object| object.location == location)
+            .map(|object| object.size)
+            .sum()
+    }
+
+    fn used_bytes(&self) -> usize {
+        self.eden.top + self.from_space.top
+    }
+}
+
+/// Holds the objects promoted from the young generation and the ones too large to be allocated in eden. It's
+/// collected by marking and sweeping the whole heap. Promotion may exceed its capacity, in which case a full
+/// collection has to follow the young one.
+struct OldGeneration {
+    capacity: usize,
+    used_bytes: usize,
+    objects: HashMap<HeapWordKey, (Oop, usize)>,
+}
+
+impl OldGeneration {
+    fn new(capacity: usize) -> OldGeneration {
+        OldGeneration { capacity, used_bytes: 0, objects: HashMap::new() }
+    }
+
+    fn contains(&self, heap_word: &HeapWord) -> bool {
+        self.objects.contains_key(&heap_word.key())
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.used_bytes > self.capacity
+    }
+
+    fn try_allocate(&mut self, oop: &Oop, size: usize) -> bool {
+        if self.capacity.saturating_sub(self.used_bytes) < size {
+            return false;
+        }
+        self.promote(oop.clone(), size);
+        true
+    }
+
+    fn promote(&mut self, oop: Oop, size: usize) {
+        self.used_bytes += size;
+        self.objects.insert(oop.instance_data().key(), (oop, size));
+    }
+
+    fn sweep(&mut self, marked: &HashSet<HeapWordKey>) -> usize {
+        let object_count = self.objects.len();
+        let mut freed_bytes = 0;
+        self.objects.retain(|key, (oop, size)| {
+            let reachable = marked.contains(key);
+            if !reachable {
+                JvmHeap::free(oop.instance_data());
+                freed_bytes += *size;
+            }
+            reachable
+        });
+        self.used_bytes -= freed_bytes;
+        object_count - self.objects.len()
+    }
+}
+
+/// The old objects and classes which may reference young objects, recorded by the write barrier. The young
+/// objects they reference are roots of young collections.
+#[derive(Default)]
+struct RememberedSet {
+    objects: HashMap<HeapWordKey, HeapWord>,
+    classes: HashMap<usize, Arc<Klass>>,
+}
+
+impl RememberedSet {
+    fn young_roots(&self) -> Vec<HeapWord> {
+        let class_roots = self.classes.values()
+            .flat_map(|klass| klass.gc_roots())
+            .filter_map(|value| HeapWord::referenced_by(&value));
+
+        self.objects.values()
+            .flat_map(|heap_word| heap_word.references())
+            .chain(class_roots)
+            .collect()
+    }
+
+    /// Adds the promoted objects, then keeps only the old objects and classes which still reference young ones.
+    fn refresh(&mut self, young: &YoungGeneration, old: &OldGeneration, promoted: Vec<HeapWord>) {
+        self.objects.extend(promoted.into_iter().map(|heap_word| (heap_word.key(), heap_word)));
+        self.objects.retain(|_, heap_word| {
+            old.contains(heap_word) && heap_word.references().iter().any(|referenced| young.contains(referenced))
+        });
+        self.classes.retain(|_, klass| {
+            klass.gc_roots()
+                .iter()
+                .filter_map(HeapWord::referenced_by)
+                .any(|referenced| young.contains(&referenced))
+        });
+    }
+}
+
+/// A heap split into a young and an old generation. Objects are allocated in the eden of the young generation
+/// by bumping a pointer. When eden is full a young collection copies the live young objects into a survivor
+/// space, or promotes them to the old generation. The write barrier records the old objects referencing young
+/// ones, so young collections don't have to trace the old generation. Once the old generation is full the
+/// whole heap is collected by marking and sweeping.
+pub struct GenerationalHeap {
+    young: Mutex<YoungGeneration>,
+    old: Mutex<OldGeneration>,
+    remembered_set: Mutex<RememberedSet>,
+    garbage_collector: RwLock<Option<GarbageCollectorHook>>,
+    out_of_memory_error: RwLock<Option<ObjectOopDesc>>,
+    recent_allocations: RecentAllocations,
+}
+
+impl GenerationalHeap {
+    /// Creates a heap whose generations may occupy the given number of bytes.
+    pub fn new(young_size: usize, old_size: usize) -> GenerationalHeap {
+        GenerationalHeap {
+            young: Mutex::new(YoungGeneration::new(young_size)),
+            old: Mutex::new(OldGeneration::new(old_size)),
+            remembered_set: Mutex::new(RememberedSet::default()),
+            garbage_collector: RwLock::new(None),
+            out_of_memory_error: RwLock::new(None),
+            recent_allocations: RecentAllocations::default(),
+        }
+    }
+
+    /// Creates a heap whose objects may occupy at most the given number of bytes, split between the generations
+    /// like by HotSpot.
+    pub fn with_max_heap_size(max_heap_size: usize) -> GenerationalHeap {
+        let young_size = max_heap_size / (NEW_RATIO + 1);
+        GenerationalHeap::new(young_size, max_heap_size - young_size)
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.young.lock().unwrap().used_bytes() + self.old.lock().unwrap().used_bytes
+    }
+
+    pub fn is_young(&self, heap_word: &HeapWord) -> bool {
+        self.young.lock().unwrap().contains(heap_word)
+    }
+
+    pub fn is_old(&self, heap_word: &HeapWord) -> bool {
+        self.old.lock().unwrap().contains(heap_word)
+    }
+
+    /// Allocates the object in eden, running a young collection if it's full. Objects larger than eden are
+    /// allocated in the old generation straight away.
+    fn allocate(&self, oop: Oop, size: usize) -> Result<(), JvmException> {
+        let fits_eden = size <= self.young.lock().unwrap().eden.capacity;
+        if fits_eden {
+            if self.young.lock().unwrap().try_allocate(&oop, size) {
+                self.recent_allocations.remember(oop.instance_data());
+                return Ok(());
+            }
+
+            self.collect(Collection::Young);
+            if self.old.lock().unwrap().is_exhausted() {
+                self.collect(Collection::Full);
+                if self.old.lock().unwrap().is_exhausted() {
+                    return Err(self.out_of_memory());
+                }
+            }
+            if self.young.lock().unwrap().try_allocate(&oop, size) {
+                self.recent_allocations.remember(oop.instance_data());
+                return Ok(());
+            }
+        }
+        self.allocate_old(oop, size)
+    }
+
+    fn allocate_old(&self, oop: Oop, size: usize) -> Result<(), JvmException> {
+        if !self.old.lock().unwrap().try_allocate(&oop, size) {
+            self.collect(Collection::Full);
+            if !self.old.lock().unwrap().try_allocate(&oop, size) {
+                return Err(self.out_of_memory());
+            }
+        }
+        self.recent_allocations.remember(oop.instance_data());
+        Ok(())
+    }
+
+    fn collect(&self, collection: Collection) {
+        if let Some(garbage_collector) = self.garbage_collector.read().unwrap().as_ref() {
+            garbage_collector(collection);
+        }
+    }
+
+    fn out_of_memory(&self) -> JvmException {
+        JvmHeap::out_of_memory(self.out_of_memory_error.read().unwrap().as_ref())
+    }
+
+    /// The roots every collection has besides the ones it's given: the objects the VM allocated recently and
+    /// the preallocated OutOfMemoryError.
+    fn vm_roots(&self, roots: Vec<JvmValue>) -> Vec<HeapWord> {
+        let mut roots: Vec<HeapWord> = roots.iter().filter_map(HeapWord::referenced_by).collect();
+        roots.extend(self.recent_allocations.all());
+        roots.extend(self.out_of_memory_error.read().unwrap().iter().map(|error| error.instance_data().clone()));
+        roots
+    }
+}
+
+impl Heap for GenerationalHeap {
+    fn allocate_object(&self, klass: Arc<Klass>) -> Result<ObjectOopDesc, JvmException> {
+        let object_size = JvmHeap::object_size(&klass);
+        let new_obj = ObjectOopDesc::new(klass.clone(), JvmHeap::build_default_object(klass));
+        self.allocate(Oop::ObjectOop(new_obj.clone()), object_size)?;
+        Ok(new_obj)
+    }
+
+    fn allocate_array(&self, klass: Arc<Klass>, size: i32) -> Result<ArrayOopDesc, JvmException> {
+        let array_size = JvmHeap::array_size(REFERENCE_SIZE, size);
+        let new_obj = ArrayOopDesc { klass: klass.clone(), size, instance_data: JvmHeap::allocate_obj_array(klass, size) };
+        self.allocate(Oop::ArrayOop(new_obj.clone()), array_size)?;
+        Ok(new_obj)
+    }
+
+    fn allocate_primitive_array(&self, primitive_type: PrimitiveType, size: i32) -> Result<PrimitiveArrayOopDesc, JvmException> {
+        let array_size = JvmHeap::array_size(primitive_type.size_in_bytes(), size);
+        let new_obj = PrimitiveArrayOopDesc {
+            inner_type: primitive_type,
+            size,
+            instance_data: JvmHeap::allocate_primitive_array(primitive_type, size),
+        };
+        self.allocate(Oop::PrimitiveArrayOop(new_obj.clone()), array_size)?;
+        Ok(new_obj)
+    }
+
+    /// Class instances are referenced by their classes, which aren't traced by young collections, so they are
+    /// allocated in the old generation.
+    fn allocate_class(&self, klass: Arc<Klass>) -> Result<ObjectOopDesc, JvmException> {
+        let object_size = JvmHeap::object_size(&klass);
+        let new_obj = ObjectOopDesc::new(klass.clone(), JvmHeap::build_default_object(klass));
+        self.allocate_old(Oop::ObjectOop(new_obj.clone()), object_size)?;
+        Ok(new_obj)
+    }
+
+    fn collect_garbage(&self, roots: Vec<JvmValue>) -> usize {
+        let mut young = self.young.lock().unwrap();
+        let mut old = self.old.lock().unwrap();
+        let mut remembered_set = self.remembered_set.lock().unwrap();
+
+        let marked = JvmHeap::mark(self.vm_roots(roots));
+        let freed_objects = young.sweep(&marked) + old.sweep(&marked);
+        remembered_set.refresh(&young, &old, Vec::new());
+        freed_objects
+    }
+
+    fn collect_young_garbage(&self, roots: Vec<JvmValue>) -> usize {
+        let mut young = self.young.lock().unwrap();
+        let mut old = self.old.lock().unwrap();
+        let mut remembered_set = self.remembered_set.lock().unwrap();
+
+        let mut roots = self.vm_roots(roots);
+        roots.extend(remembered_set.young_roots());
+        let live = young.trace(roots);
+
+        let object_count = young.objects.len();
+        let promoted = young.evacuate(&live, &mut old);
+        let freed_objects = object_count - young.objects.len() - promoted.len();
+        remembered_set.refresh(&young, &old, promoted);
+        freed_objects
+    }
+
+    /// Remembers the holder if it's an old object which now references a young one.
+    fn write_barrier(&self, holder: &HeapWord, value: &JvmValue) {
+        if let Some(referenced) = HeapWord::referenced_by(value) {
+            let young = self.young.lock().unwrap();
+            if young.contains(&referenced) && !young.contains(holder) {
+                self.remembered_set.lock().unwrap().objects.insert(holder.key(), holder.clone());
+            }
+        }
+    }
+
+    fn static_write_barrier(&self, klass: &Arc<Klass>, value: &JvmValue) {
+        if let Some(referenced) = HeapWord::referenced_by(value) {
+            if self.young.lock().unwrap().contains(&referenced) {
+                self.remembered_set.lock().unwrap().classes.insert(Arc::as_ptr(klass) as usize, klass.clone());
+            }
+        }
+    }
+
+    fn object_count(&self) -> usize {
+        self.young.lock().unwrap().objects.len() + self.old.lock().unwrap().objects.len()
+    }
+
+    fn set_garbage_collector(&self, garbage_collector: GarbageCollectorHook) {
+        self.garbage_collector.write().unwrap().replace(garbage_collector);
+    }
+
+    fn set_out_of_memory_error(&self, out_of_memory_error: ObjectOopDesc) {
+        self.out_of_memory_error.write().unwrap().replace(out_of_memory_error);
+    }
+
+    fn dump_heap(&self, path: &Path, roots: &HeapDumpRoots) -> Result<(), JvmException> {
+        let mut objects: Vec<Oop> = self.young.lock().unwrap().objects.values().map(|object| object.oop.clone()).collect();
+        objects.extend(self.old.lock().unwrap().objects.values().map(|(oop, _)| oop.clone()));
+        heap_dump::write_heap_dump(path, &objects, roots, &self.vm_roots(Vec::new()))
+    }
+
+    fn forget_recent_allocations(&self) {
+        self.recent_allocations.forget();
+    }
+}
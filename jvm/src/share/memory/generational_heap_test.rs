@@ -0,0 +1,187 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::share::memory::generational_heap::{GenerationalHeap, MAX_TENURING_THRESHOLD};
+use crate::share::memory::heap::{Collection, Heap};
+use crate::share::memory::oop::oops::ArrayOopDesc;
+use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType};
+use crate::share::utilities::testing::java_lang_object_class;
+
+fn allocate_node(heap: &GenerationalHeap) -> ArrayOopDesc {
+    heap.allocate_array(java_lang_object_class(), 1).unwrap()
+}
+
+fn link(heap: &GenerationalHeap, from: &ArrayOopDesc, to: &ArrayOopDesc) {
+    let reference = JvmValue::from(to.clone());
+    from.instance_data.put_field(0, reference.clone()).unwrap();
+    heap.write_barrier(&from.instance_data, &reference);
+}
+
+fn promote(heap: &GenerationalHeap, node: &ArrayOopDesc) {
+    for _ in 0..MAX_TENURING_THRESHOLD {
+        heap.collect_young_garbage(vec![JvmValue::from(node.clone())]);
+    }
+}
+
+#[test]
+pub fn objects_are_allocated_in_eden() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let node = allocate_node(&heap);
+
+    assert!(heap.is_young(&node.instance_data));
+    assert!(!heap.is_old(&node.instance_data));
+}
+
+#[test]
+pub fn objects_larger_than_eden_are_allocated_in_old_generation() {
+    let heap = GenerationalHeap::new(1024, 64 * 1024);
+    let array = heap.allocate_primitive_array(PrimitiveType::Long, 1024).unwrap();
+
+    assert!(heap.is_old(&array.instance_data));
+}
+
+#[test]
+pub fn class_instances_are_allocated_in_old_generation() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let mirror = heap.allocate_class(java_lang_object_class()).unwrap();
+
+    assert!(heap.is_old(mirror.instance_data()));
+}
+
+#[test]
+pub fn young_collection_frees_unreachable_young_objects() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let first = allocate_node(&heap);
+    let second = allocate_node(&heap);
+    link(&heap, &first, &second);
+    allocate_node(&heap);
+    heap.forget_recent_allocations();
+
+    assert_eq!(1, heap.collect_young_garbage(vec![JvmValue::from(first.clone())]));
+    assert!(heap.is_young(&first.instance_data));
+    assert!(heap.is_young(&second.instance_data));
+}
+
+#[test]
+pub fn survivors_are_promoted_after_tenuring_threshold() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let node = allocate_node(&heap);
+    heap.forget_recent_allocations();
+
+    for _ in 1..MAX_TENURING_THRESHOLD {
+        heap.collect_young_garbage(vec![JvmValue::from(node.clone())]);
+    }
+    assert!(heap.is_young(&node.instance_data));
+
+    heap.collect_young_garbage(vec![JvmValue::from(node.clone())]);
+    assert!(heap.is_old(&node.instance_data));
+}
+
+#[test]
+pub fn survivors_overflowing_survivor_space_are_promoted() {
+    let heap = GenerationalHeap::new(1024, 64 * 1024);
+    let large = heap.allocate_primitive_array(PrimitiveType::Long, 48).unwrap();
+    heap.forget_recent_allocations();
+
+    heap.collect_young_garbage(vec![JvmValue::from(large.clone())]);
+    assert!(heap.is_old(&large.instance_data));
+}
+
+#[test]
+pub fn old_objects_remembered_by_write_barrier_keep_young_objects_alive() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let old = allocate_node(&heap);
+    heap.forget_recent_allocations();
+    promote(&heap, &old);
+
+    let young = allocate_node(&heap);
+    let unreferenced = allocate_node(&heap);
+    link(&heap, &old, &young);
+    heap.forget_recent_allocations();
+
+    assert_eq!(1, heap.collect_young_garbage(Vec::new()));
+    assert!(heap.is_young(&young.instance_data));
+    assert!(!heap.is_young(&unreferenced.instance_data));
+}
+
+#[test]
+pub fn promoted_objects_referencing_young_ones_are_remembered() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let parent = allocate_node(&heap);
+    heap.forget_recent_allocations();
+    for _ in 1..MAX_TENURING_THRESHOLD {
+        heap.collect_young_garbage(vec![JvmValue::from(parent.clone())]);
+    }
+
+    let child = allocate_node(&heap);
+    let grandchild = allocate_node(&heap);
+    link(&heap, &parent, &child);
+    link(&heap, &child, &grandchild);
+    heap.forget_recent_allocations();
+    heap.collect_young_garbage(vec![JvmValue::from(parent.clone())]);
+    assert!(heap.is_old(&parent.instance_data));
+
+    assert_eq!(0, heap.collect_young_garbage(Vec::new()));
+    assert!(heap.is_young(&child.instance_data));
+    assert_eq!(Ok(JvmValue::from(grandchild.clone())), child.instance_data.get_field(0));
+}
+
+#[test]
+pub fn full_collection_frees_unreachable_old_objects() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let kept = allocate_node(&heap);
+    let dropped = allocate_node(&heap);
+    heap.forget_recent_allocations();
+    for _ in 0..MAX_TENURING_THRESHOLD {
+        heap.collect_young_garbage(vec![JvmValue::from(kept.clone()), JvmValue::from(dropped.clone())]);
+    }
+    assert!(heap.is_old(&dropped.instance_data));
+
+    assert_eq!(1, heap.collect_garbage(vec![JvmValue::from(kept.clone())]));
+    assert_eq!(1, heap.object_count());
+    assert!(heap.is_old(&kept.instance_data));
+}
+
+#[test]
+pub fn exhausted_eden_requests_young_collection() {
+    let heap = Arc::new(GenerationalHeap::new(4 * 1024, 64 * 1024));
+    let young_collections = Arc::new(AtomicUsize::new(0));
+    let (gc_heap, gc_collections) = (Arc::downgrade(&heap), young_collections.clone());
+    heap.set_garbage_collector(Box::new(move |collection| {
+        assert_eq!(Collection::Young, collection);
+        gc_collections.fetch_add(1, Ordering::SeqCst);
+        gc_heap.upgrade().map_or(0, |heap| heap.collect_young_garbage(Vec::new()))
+    }));
+
+    for _ in 0..100 {
+        heap.allocate_primitive_array(PrimitiveType::Int, 64).unwrap();
+        heap.forget_recent_allocations();
+    }
+
+    assert!(young_collections.load(Ordering::SeqCst) > 0);
+    assert!(heap.used_bytes() <= 4 * 1024);
+}
+
+#[test]
+pub fn exhausted_old_generation_throws_out_of_memory_error() {
+    let heap = Arc::new(GenerationalHeap::new(1024, 4 * 1024));
+    let gc_heap = Arc::downgrade(&heap);
+    heap.set_garbage_collector(Box::new(move |_| {
+        gc_heap.upgrade().map_or(0, |heap| heap.collect_garbage(Vec::new()))
+    }));
+
+    let exception = heap.allocate_primitive_array(PrimitiveType::Long, 1024).unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/OutOfMemoryError")), exception.exception_class());
+}
+
+#[test]
+pub fn identity_hash_survives_promotion() {
+    let heap = GenerationalHeap::new(64 * 1024, 64 * 1024);
+    let node = allocate_node(&heap);
+    let identity_hash = node.instance_data.identity_hash();
+    heap.forget_recent_allocations();
+
+    promote(&heap, &node);
+    assert!(heap.is_old(&node.instance_data));
+    assert_eq!(identity_hash, node.instance_data.identity_hash());
+}
@@ -1,17 +1,51 @@
 use core::sync::atomic::Ordering;
-use std::collections::HashMap;
-use std::iter::Map;
-use std::ops::Deref;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::path::Path;
 use std::sync::{Arc, Mutex, RwLock};
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicI32, AtomicUsize};
+use std::thread::ThreadId;
 
 use crate::share::classfile::klass::Klass;
+use crate::share::memory::heap_dump;
+use crate::share::memory::heap_dump::HeapDumpRoots;
 use crate::share::memory::oop::Oop;
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::utilities::jvm_value::{JvmValue, ObjectRef, PrimitiveType};
 use crate::share::utilities::jvm_value::JvmValue::ObjRef;
 use crate::share::memory::oop::oops::{PrimitiveArrayOopDesc, ObjectOopDesc, ArrayOopDesc};
 use crate::share::memory::oop::Oop::{ArrayOop, PrimitiveArrayOop, ObjectOop};
+use crate::share::runtime::monitor::ObjectMonitor;
+
+#[cfg(test)]
+#[path = "./heap_test.rs"]
+mod heap_test;
+
+//sizes the heap usage is accounted with, similar to the object layout of HotSpot on 64 bit platforms
+const OBJECT_HEADER_SIZE: usize = 16;
+const ARRAY_HEADER_SIZE: usize = 24;
+const FIELD_SIZE: usize = 8;
+pub(super) const REFERENCE_SIZE: usize = 8;
+
+thread_local! {
+    //the state of the identity hash code generator of the current thread, seeded differently for every thread
+    static IDENTITY_HASH_STATE: Cell<u32> = Cell::new(identity_hash_seed());
+}
+
+/// The kinds of garbage collection a heap may request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Collection {
+    /// Collects only the young generation, tracing from the frames of the threads and the old objects which
+    /// were recorded by the write barrier.
+    Young,
+    /// Collects the whole heap, tracing from every root.
+    Full,
+}
+
+/// Runs a garbage collection when the heap is exhausted and returns how many objects were freed.
+pub type GarbageCollectorHook = Box<dyn Fn(Collection) -> usize + Send + Sync>;
 
 #[cfg_attr(test, mockall::automock)]
 pub trait Heap: Send + Sync {
@@ -19,29 +53,155 @@ pub trait Heap: Send + Sync {
     fn allocate_array(&self, klass: Arc<Klass>, size: i32) -> Result<ArrayOopDesc, JvmException>;
     fn allocate_primitive_array(&self, primitive_type: PrimitiveType, size: i32) -> Result<PrimitiveArrayOopDesc, JvmException>;
     fn allocate_class(&self, klass: Arc<Klass>) -> Result<ObjectOopDesc, JvmException>;
+    /// Frees every object which is not reachable from the given roots and returns how many were freed.
+    /// The caller has to make sure no other thread uses the heap meanwhile.
+    fn collect_garbage(&self, roots: Vec<JvmValue>) -> usize;
+    /// Frees the unreachable objects of the young generation and returns how many were freed. The roots are the
+    /// values of the frames and the thread objects, references of old objects are recorded by the write barrier.
+    /// Heaps without generations never request young collections.
+    fn collect_young_garbage(&self, _roots: Vec<JvmValue>) -> usize {
+        0
+    }
+    /// Called after a reference has been stored into a field or array element of the given object, e.g. by
+    /// PUTFIELD or AASTORE.
+    fn write_barrier(&self, _holder: &HeapWord, _value: &JvmValue) {}
+    /// Called after a reference has been stored into a static field of the given class by PUTSTATIC.
+    fn static_write_barrier(&self, _klass: &Arc<Klass>, _value: &JvmValue) {}
+    fn object_count(&self) -> usize;
+    /// Sets the garbage collector run when the heap is exhausted.
+    fn set_garbage_collector(&self, garbage_collector: GarbageCollectorHook);
+    /// Sets the instance thrown when the heap is exhausted, it's allocated in advance as there may be no space
+    /// left for it by then.
+    fn set_out_of_memory_error(&self, out_of_memory_error: ObjectOopDesc);
+    /// Writes every object of the heap to the given file in HPROF format, together with the given roots.
+    /// The caller has to make sure no other thread uses the heap meanwhile.
+    fn dump_heap(&self, path: &Path, roots: &HeapDumpRoots) -> Result<(), JvmException>;
+    /// Forgets the objects allocated by the current thread so far, called once they are referenced by the values
+    /// of its frame, and when the thread terminates.
+    fn forget_recent_allocations(&self);
+}
+
+/// The objects every thread allocated since its frame was last published. They may be referenced only by the VM
+/// itself yet, e.g. the outer array of MULTIANEWARRAY while its inner arrays are allocated, so a collection keeps
+/// the ones of all threads alive, including the threads which wait for the collection to finish.
+#[derive(Default)]
+pub(super) struct RecentAllocations {
+    threads: Mutex<HashMap<ThreadId, Vec<HeapWord>>>,
+}
+
+impl RecentAllocations {
+    pub(super) fn remember(&self, heap_word: &HeapWord) {
+        self.threads.lock().unwrap()
+            .entry(std::thread::current().id())
+            .or_default()
+            .push(heap_word.clone());
+    }
+
+    pub(super) fn forget(&self) {
+        self.threads.lock().unwrap().remove(&std::thread::current().id());
+    }
+
+    pub(super) fn all(&self) -> Vec<HeapWord> {
+        self.threads.lock().unwrap().values().flatten().cloned().collect()
+    }
+}
+
+struct Allocation {
+    oop: Oop,
+    size: usize,
 }
 
 pub struct JvmHeap {
-    object_count: AtomicUsize,
-    heap: Mutex<HashMap<HeapWordKey, HeapWord>>,
+    used_bytes: AtomicUsize,
+    max_heap_size: Option<usize>,
+    heap: Mutex<HashMap<HeapWordKey, Allocation>>,
+    garbage_collector: RwLock<Option<GarbageCollectorHook>>,
+    out_of_memory_error: RwLock<Option<ObjectOopDesc>>,
+    recent_allocations: RecentAllocations,
+}
+
+impl Default for JvmHeap {
+    fn default() -> Self {
+        JvmHeap::new()
+    }
 }
 
 impl JvmHeap {
     pub fn new() -> JvmHeap {
         JvmHeap {
-            object_count: AtomicUsize::new(0),
+            used_bytes: AtomicUsize::new(0),
+            max_heap_size: None,
             heap: Mutex::new(HashMap::new()),
+            garbage_collector: RwLock::new(None),
+            out_of_memory_error: RwLock::new(None),
+            recent_allocations: RecentAllocations::default(),
         }
     }
 
-    fn store(&self, heap_word: HeapWord) -> Result<(), JvmException> {
-        self.heap.lock().unwrap().insert(heap_word.key(), heap_word);
+    /// Creates a heap whose objects may occupy at most the given number of bytes, similar to -Xmx of HotSpot.
+    pub fn with_max_heap_size(max_heap_size: usize) -> JvmHeap {
+        JvmHeap {
+            max_heap_size: Some(max_heap_size),
+            ..JvmHeap::new()
+        }
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes.load(Ordering::SeqCst)
+    }
+
+    /// Accounts the size of a new object. If the heap is exhausted a garbage collection is run, and
+    /// OutOfMemoryError is thrown if it couldn't free enough space either.
+    fn reserve(&self, size: usize) -> Result<(), JvmException> {
+        if self.try_reserve(size) {
+            return Ok(());
+        }
+
+        if let Some(garbage_collector) = self.garbage_collector.read().unwrap().as_ref() {
+            garbage_collector(Collection::Full);
+        }
+        if self.try_reserve(size) {
+            return Ok(());
+        }
+
+        Err(JvmHeap::out_of_memory(self.out_of_memory_error.read().unwrap().as_ref()))
+    }
+
+    /// The exception thrown when the heap is exhausted, carrying the preallocated instance if there is one.
+    pub(super) fn out_of_memory(out_of_memory_error: Option<&ObjectOopDesc>) -> JvmException {
+        let exception = JvmException::out_of_memory();
+        match out_of_memory_error {
+            Some(out_of_memory_error) => exception.with_throwable(ObjectOop(out_of_memory_error.clone())),
+            None => exception,
+        }
+    }
+
+    fn try_reserve(&self, size: usize) -> bool {
+        let max_heap_size = self.max_heap_size.unwrap_or(usize::MAX);
+        self.used_bytes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used_bytes| {
+                used_bytes.checked_add(size).filter(|used_bytes| *used_bytes <= max_heap_size)
+            })
+            .is_ok()
+    }
+
+    fn store(&self, oop: Oop, size: usize) -> Result<(), JvmException> {
+        self.recent_allocations.remember(oop.instance_data());
+        self.heap.lock().unwrap().insert(oop.instance_data().key(), Allocation { oop, size });
         Ok(())
     }
 
-    fn build_default_object(klass: Arc<Klass>) -> HeapWord {
+    pub(super) fn object_size(klass: &Klass) -> usize {
+        OBJECT_HEADER_SIZE + klass.instance_field_count() * FIELD_SIZE
+    }
+
+    pub(super) fn array_size(element_size: usize, length: i32) -> usize {
+        ARRAY_HEADER_SIZE + element_size * length.max(0) as usize
+    }
+
+    pub(super) fn build_default_object(klass: Arc<Klass>) -> HeapWord {
         let instance_data: Vec<JvmValue> = klass
-            .instance_fields()
+            .instance_field_layout()
             .iter()
             .map(|f| f.default())
             .collect();
@@ -49,7 +209,7 @@ impl JvmHeap {
         HeapWord::new(instance_data)
     }
 
-    fn allocate_obj_array(klass: Arc<Klass>, size: i32) -> HeapWord {
+    pub(super) fn allocate_obj_array(_klass: Arc<Klass>, size: i32) -> HeapWord {
         assert!(
             size >= 0,
             "Cannot build array OOP with negative size! {}",
@@ -60,7 +220,7 @@ impl JvmHeap {
         HeapWord::new(instance_data)
     }
 
-    fn allocate_primitive_array(primitive_type: PrimitiveType, size: i32) -> HeapWord {
+    pub(super) fn allocate_primitive_array(primitive_type: PrimitiveType, size: i32) -> HeapWord {
         assert!(
             size >= 0,
             "Cannot build array OOP with negative size! {}",
@@ -72,77 +232,157 @@ impl JvmHeap {
         HeapWord::new(instance_data)
     }
 
-    fn set_field(oop: &HeapWord, field_offset: usize, value: JvmValue) -> Result<(), JvmException> {
-        oop.data.write().unwrap()[field_offset] = value;
-        Ok(())
+    /// Returns the keys of the objects reachable from the roots, following the references stored in their
+    /// fields and array elements.
+    pub(super) fn mark(roots: Vec<HeapWord>) -> HashSet<HeapWordKey> {
+        let mut marked = HashSet::new();
+        let mut to_visit = roots;
+
+        while let Some(heap_word) = to_visit.pop() {
+            if !marked.insert(heap_word.key()) {
+                continue;
+            }
+            to_visit.extend(heap_word.references());
+        }
+        marked
     }
 
-    fn get_field(oop: &HeapWord, field_offset: usize) -> Result<JvmValue, JvmException> {
-        Ok(oop.data.read().unwrap()[field_offset].clone())
+    /// Clears the references held by an unreachable object. Objects are reference counted, so otherwise the
+    /// objects of a reference cycle would keep each other alive.
+    pub(super) fn free(heap_word: &HeapWord) {
+        heap_word.data.write().unwrap()
+            .iter_mut()
+            .filter(|value| matches!(value, ObjRef(_)))
+            .for_each(|value| *value = JvmValue::null_obj());
     }
 }
 
 impl Heap for JvmHeap {
     fn allocate_object(&self, klass: Arc<Klass>) -> Result<ObjectOopDesc, JvmException> {
-        let new_obj = JvmHeap::build_default_object(klass.clone());
-        self.store(new_obj.clone())?;
-        Ok(ObjectOopDesc::new(klass, new_obj))
+        let object_size = JvmHeap::object_size(&klass);
+        self.reserve(object_size)?;
+        let new_obj = ObjectOopDesc::new(klass.clone(), JvmHeap::build_default_object(klass));
+        self.store(ObjectOop(new_obj.clone()), object_size)?;
+        Ok(new_obj)
     }
 
     fn allocate_array(&self, klass: Arc<Klass>, size: i32) -> Result<ArrayOopDesc, JvmException> {
-        let new_obj = JvmHeap::allocate_obj_array(klass.clone(), size.clone());
-        self.store(new_obj.clone())?;
-        Ok(
-            ArrayOopDesc {
-                klass,
-                size,
-                instance_data: new_obj,
-            }
-        )
+        let array_size = JvmHeap::array_size(REFERENCE_SIZE, size);
+        self.reserve(array_size)?;
+        let new_obj = ArrayOopDesc {
+            klass: klass.clone(),
+            size,
+            instance_data: JvmHeap::allocate_obj_array(klass, size),
+        };
+        self.store(ArrayOop(new_obj.clone()), array_size)?;
+        Ok(new_obj)
     }
 
     fn allocate_primitive_array(&self, primitive_type: PrimitiveType, size: i32) -> Result<PrimitiveArrayOopDesc, JvmException> {
-        let new_obj = JvmHeap::allocate_primitive_array(primitive_type.clone(), size.clone());
-        self.store(new_obj.clone())?;
-        Ok(
-            PrimitiveArrayOopDesc {
-                inner_type: primitive_type,
-                size,
-                instance_data: new_obj,
-            }
-        )
+        let array_size = JvmHeap::array_size(primitive_type.size_in_bytes(), size);
+        self.reserve(array_size)?;
+        let new_obj = PrimitiveArrayOopDesc {
+            inner_type: primitive_type,
+            size,
+            instance_data: JvmHeap::allocate_primitive_array(primitive_type, size),
+        };
+        self.store(PrimitiveArrayOop(new_obj.clone()), array_size)?;
+        Ok(new_obj)
     }
 
     fn allocate_class(&self, klass: Arc<Klass>) -> Result<ObjectOopDesc, JvmException> {
         //TODO implement this properly, should return a more specialized OOPDesc for class instances
-        let new_obj = JvmHeap::build_default_object(klass.clone());
-        self.store(new_obj.clone())?;
-        Ok(ObjectOopDesc::new(klass, new_obj))
+        let object_size = JvmHeap::object_size(&klass);
+        self.reserve(object_size)?;
+        let new_obj = ObjectOopDesc::new(klass.clone(), JvmHeap::build_default_object(klass));
+        self.store(ObjectOop(new_obj.clone()), object_size)?;
+        Ok(new_obj)
+    }
+
+    fn collect_garbage(&self, roots: Vec<JvmValue>) -> usize {
+        let mut roots: Vec<HeapWord> = roots.iter().filter_map(HeapWord::referenced_by).collect();
+        roots.extend(self.recent_allocations.all());
+        roots.extend(self.out_of_memory_error.read().unwrap().iter().map(|error| error.instance_data().clone()));
+        let marked = JvmHeap::mark(roots);
+
+        let mut heap = self.heap.lock().unwrap();
+        let object_count = heap.len();
+        let mut freed_bytes = 0;
+        heap.retain(|key, allocation| {
+            let reachable = marked.contains(key);
+            if !reachable {
+                JvmHeap::free(allocation.oop.instance_data());
+                freed_bytes += allocation.size;
+            }
+            reachable
+        });
+        self.used_bytes.fetch_sub(freed_bytes, Ordering::SeqCst);
+        object_count - heap.len()
+    }
+
+    fn object_count(&self) -> usize {
+        self.heap.lock().unwrap().len()
+    }
+
+    fn set_garbage_collector(&self, garbage_collector: GarbageCollectorHook) {
+        self.garbage_collector.write().unwrap().replace(garbage_collector);
+    }
+
+    fn set_out_of_memory_error(&self, out_of_memory_error: ObjectOopDesc) {
+        self.out_of_memory_error.write().unwrap().replace(out_of_memory_error);
+    }
+
+    fn dump_heap(&self, path: &Path, roots: &HeapDumpRoots) -> Result<(), JvmException> {
+        let objects: Vec<Oop> = self.heap.lock().unwrap().values().map(|allocation| allocation.oop.clone()).collect();
+        let mut vm_roots = self.recent_allocations.all();
+        vm_roots.extend(self.out_of_memory_error.read().unwrap().iter().map(|error| error.instance_data().clone()));
+        heap_dump::write_heap_dump(path, &objects, roots, &vm_roots)
+    }
+
+    fn forget_recent_allocations(&self) {
+        self.recent_allocations.forget();
     }
 }
 
-type HeapWordKey = usize;
+pub(super) type HeapWordKey = usize;
+
+/// The header of an object: its identity hash code, 0 until it's first requested, and its monitor.
+#[derive(Debug, Default)]
+struct ObjectHeader {
+    identity_hash: AtomicI32,
+    monitor: ObjectMonitor,
+}
 
+/// The storage of a heap allocated object, shared by all the references pointing to it. Besides the fields or
+/// array elements it holds the object header.
 #[derive(Debug, Clone)]
 pub struct HeapWord {
     data: Arc<RwLock<Vec<JvmValue>>>,
+    header: Arc<ObjectHeader>,
 }
 
 impl PartialEq for HeapWord {
     fn eq(&self, other: &Self) -> bool {
-        self.key() == other.key()
+        self.is_same(other)
     }
 }
 
 impl HeapWord {
     fn new(data: Vec<JvmValue>) -> HeapWord {
         HeapWord {
-            data: Arc::new(RwLock::new(data))
+            data: Arc::new(RwLock::new(data)),
+            header: Arc::new(ObjectHeader::default()),
         }
     }
 
+    /// The address of the allocation, unique among the live objects, even the ones without any fields.
     pub fn key(&self) -> HeapWordKey {
-        ((self.data.read().unwrap().as_ptr()) as usize)
+        Arc::as_ptr(&self.data) as usize
+    }
+
+    /// Returns true if both words refer to the same allocation.
+    pub fn is_same(&self, other: &HeapWord) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
     }
 
     pub fn put_field(&self, field_offset: usize, value: JvmValue) -> Result<(), JvmException> {
@@ -151,18 +391,70 @@ impl HeapWord {
     }
 
     pub fn get_field(&self, field_offset: usize) -> Result<JvmValue, JvmException> {
-        Ok(self.data.read().unwrap()[field_offset].clone())
+        self.data.read().unwrap().get(field_offset).cloned()
+            .ok_or_else(|| JvmException::from(format!("Object has no field at offset {}", field_offset)))
     }
     pub fn data(&self) -> Arc<RwLock<Vec<JvmValue>>> {
         self.data.clone()
     }
+
+    pub fn monitor(&self) -> &ObjectMonitor {
+        &self.header.monitor
+    }
+
+    /// Returns the identity hash code of the object. It's generated when first requested and kept in the header,
+    /// so it doesn't change when a garbage collection moves the object.
+    pub fn identity_hash(&self) -> i32 {
+        let identity_hash = self.header.identity_hash.load(Ordering::SeqCst);
+        if identity_hash != 0 {
+            return identity_hash;
+        }
+
+        //threads racing to generate the hash code agree on the one stored first
+        let generated = next_identity_hash();
+        match self.header.identity_hash.compare_exchange(0, generated, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => generated,
+            Err(stored) => stored,
+        }
+    }
+
+    /// The objects referenced by the fields or array elements of this one.
+    pub(super) fn references(&self) -> Vec<HeapWord> {
+        self.data.read().unwrap().iter().filter_map(HeapWord::referenced_by).collect()
+    }
+
+    pub(super) fn referenced_by(value: &JvmValue) -> Option<HeapWord> {
+        match value {
+            ObjRef(ObjectRef::Ref(oop)) => Some(oop.instance_data().clone()),
+            _ => None,
+        }
+    }
+}
+
+fn identity_hash_seed() -> u32 {
+    //the generator would only produce zeros from a zero state
+    RandomState::new().hash_one(std::thread::current().id()) as u32 | 1
+}
+
+//Marsaglia's xor-shift generator, like the default identity hash codes of HotSpot, which are positive 31 bit numbers
+fn next_identity_hash() -> i32 {
+    IDENTITY_HASH_STATE.with(|state| loop {
+        let mut next = state.get();
+        next ^= next << 13;
+        next ^= next >> 17;
+        next ^= next << 5;
+        state.set(next);
+
+        let identity_hash = (next & 0x7fff_ffff) as i32;
+        if identity_hash != 0 {
+            return identity_hash;
+        }
+    })
 }
 
 #[cfg(test)]
 impl HeapWord {
     pub fn test_object(data: Vec<JvmValue>) -> HeapWord {
-        HeapWord {
-            data: Arc::new(RwLock::new(data))
-        }
+        HeapWord::new(data)
     }
 }
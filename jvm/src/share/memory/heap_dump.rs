@@ -0,0 +1,440 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::share::classfile::klass::Klass;
+use crate::share::memory::heap::{HeapWord, JvmHeap};
+use crate::share::memory::oop::Oop;
+use crate::share::memory::oop::oops::ObjectOopDesc;
+use crate::share::utilities::context::GlobalContext;
+use crate::share::utilities::global_symbols::Symbols;
+use crate::share::utilities::jvm_exception::JvmException;
+use crate::share::utilities::jvm_value::{JvmValue, ObjectRef, PrimitiveType};
+
+#[cfg(test)]
+#[path = "./heap_dump_test.rs"]
+mod heap_dump_test;
+
+const HPROF_HEADER: &[u8] = b"JAVA PROFILE 1.0.2\0";
+const IDENTIFIER_SIZE: u32 = 8;
+//heap dumps are split into segments, as the length of a record has to fit into 4 bytes
+const MAX_SEGMENT_SIZE: usize = 1 << 30;
+
+//top level records
+const STRING_IN_UTF8: u8 = 0x01;
+const LOAD_CLASS: u8 = 0x02;
+const STACK_TRACE: u8 = 0x05;
+const HEAP_DUMP_SEGMENT: u8 = 0x1C;
+const HEAP_DUMP_END: u8 = 0x2C;
+
+//sub-records of heap dump segments
+const ROOT_UNKNOWN: u8 = 0xFF;
+const ROOT_JAVA_FRAME: u8 = 0x03;
+const ROOT_STICKY_CLASS: u8 = 0x05;
+const ROOT_THREAD_OBJECT: u8 = 0x08;
+const CLASS_DUMP: u8 = 0x20;
+const INSTANCE_DUMP: u8 = 0x21;
+const OBJECT_ARRAY_DUMP: u8 = 0x22;
+const PRIMITIVE_ARRAY_DUMP: u8 = 0x23;
+
+const OBJECT_TYPE: u8 = 2;
+//objects are recorded with an empty stack trace, as allocation sites aren't tracked
+const EMPTY_STACK_TRACE_SERIAL: u32 = 1;
+const UNKNOWN_FRAME: u32 = u32::MAX;
+
+/// The roots of the garbage collector, recorded in heap dumps with their kind.
+pub struct HeapDumpRoots {
+    pub classes: Vec<Arc<Klass>>,
+    pub threads: Vec<ThreadRoots>,
+}
+
+pub struct ThreadRoots {
+    pub thread_object: ObjectOopDesc,
+    pub frame_roots: Vec<JvmValue>,
+}
+
+/// Stops every thread executing Java code, then writes the objects of the heap to the given file in HPROF format.
+pub fn dump_heap(context: &GlobalContext, path: &Path) -> Result<(), JvmException> {
+    context.safepoint().stop_the_world(|| {
+        let roots = HeapDumpRoots {
+            classes: context.class_loader().loaded_classes(),
+            threads: context.threads()
+                .live_threads()
+                .into_iter()
+                .map(|thread| ThreadRoots { thread_object: thread.thread_object().clone(), frame_roots: thread.frame_roots() })
+                .collect(),
+        };
+        context.heap().dump_heap(path, &roots)
+    })?;
+    log::debug!("Heap dumped to {}", path.display());
+    Ok(())
+}
+
+/// Writes the objects in the binary HPROF format of HotSpot, see the description of the format in the
+/// demo/jvmti/hprof directory of the JDK 6 sources. The ids of objects are the addresses of their storage, the
+/// ids of classes the addresses of their `Klass`. `vm_roots` are the objects referenced by the VM itself.
+pub fn write_heap_dump(path: &Path, objects: &[Oop], roots: &HeapDumpRoots, vm_roots: &[HeapWord]) -> Result<(), JvmException> {
+    let mut writer = HprofWriter::new();
+    writer.write(objects, roots, vm_roots);
+    std::fs::write(path, writer.finish())
+        .map_err(|err| JvmException::from(format!("Failed to write heap dump to {}: {}", path.display(), err)))
+}
+
+struct HprofWriter {
+    out: Vec<u8>,
+    segment: Vec<u8>,
+    string_ids: HashMap<String, u64>,
+    class_serial: u32,
+}
+
+impl HprofWriter {
+    fn new() -> HprofWriter {
+        let mut out = Vec::new();
+        out.extend_from_slice(HPROF_HEADER);
+        put_u4(&mut out, IDENTIFIER_SIZE);
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_millis() as u64);
+        put_u8(&mut out, timestamp);
+        HprofWriter { out, segment: Vec::new(), string_ids: HashMap::new(), class_serial: 0 }
+    }
+
+    fn write(&mut self, objects: &[Oop], roots: &HeapDumpRoots, vm_roots: &[HeapWord]) {
+        self.write_stack_traces(roots.threads.len());
+
+        let classes = HprofWriter::dumped_classes(objects, roots);
+        let object_class_id = classes.iter()
+            .find(|klass| klass.qualified_name() == *Symbols::java_lang_Object)
+            .map_or(0, class_id);
+        let mut array_classes: Vec<&Arc<Klass>> = Vec::new();
+        for object in objects {
+            if let Oop::ArrayOop(array) = object {
+                if !array_classes.iter().any(|klass| Arc::ptr_eq(klass, &array.klass)) {
+                    array_classes.push(&array.klass);
+                }
+            }
+        }
+
+        for klass in &classes {
+            self.load_class(class_id(klass), &klass.qualified_name());
+        }
+        for component_class in &array_classes {
+            self.load_class(array_class_id(component_class), &array_class_name(component_class));
+        }
+
+        self.write_roots(roots, vm_roots);
+        for klass in &classes {
+            self.class_dump(klass);
+        }
+        for component_class in &array_classes {
+            self.array_class_dump(component_class, object_class_id);
+        }
+        for object in objects {
+            match object {
+                Oop::ObjectOop(object) => self.instance_dump(object),
+                Oop::ArrayOop(_) => self.object_array_dump(object),
+                Oop::PrimitiveArrayOop(_) => self.primitive_array_dump(object),
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.flush_segment();
+        HprofWriter::record(&mut self.out, HEAP_DUMP_END, &[]);
+        self.out
+    }
+
+    /// The loaded classes, together with the classes of the objects and their superclasses.
+    fn dumped_classes(objects: &[Oop], roots: &HeapDumpRoots) -> Vec<Arc<Klass>> {
+        let object_classes = objects.iter().filter_map(|object| match object {
+            Oop::ObjectOop(object) => Some(object.klass()),
+            Oop::ArrayOop(array) => Some(array.klass()),
+            Oop::PrimitiveArrayOop(_) => None,
+        });
+
+        let mut classes: Vec<Arc<Klass>> = Vec::new();
+        let mut seen = HashSet::new();
+        for klass in roots.classes.iter().cloned().chain(object_classes) {
+            let mut current = Some(klass);
+            while let Some(klass) = current {
+                if !seen.insert(class_id(&klass)) {
+                    break;
+                }
+                current = klass.super_class();
+                classes.push(klass);
+            }
+        }
+        classes
+    }
+
+    fn record(out: &mut Vec<u8>, tag: u8, body: &[u8]) {
+        out.push(tag);
+        put_u4(out, 0);
+        put_u4(out, body.len() as u32);
+        out.extend_from_slice(body);
+    }
+
+    fn string_id(&mut self, string: &str) -> u64 {
+        if let Some(id) = self.string_ids.get(string) {
+            return *id;
+        }
+
+        let id = self.string_ids.len() as u64 + 1;
+        self.string_ids.insert(String::from(string), id);
+        let mut body = Vec::new();
+        put_u8(&mut body, id);
+        body.extend_from_slice(string.as_bytes());
+        HprofWriter::record(&mut self.out, STRING_IN_UTF8, &body);
+        id
+    }
+
+    /// Writes an empty stack trace for the objects and one for every thread, the stack trace of the nth thread
+    /// has serial n + 1.
+    fn write_stack_traces(&mut self, thread_count: usize) {
+        for serial in EMPTY_STACK_TRACE_SERIAL..=thread_count as u32 + EMPTY_STACK_TRACE_SERIAL {
+            let mut body = Vec::new();
+            put_u4(&mut body, serial);
+            put_u4(&mut body, serial - EMPTY_STACK_TRACE_SERIAL);
+            put_u4(&mut body, 0);
+            HprofWriter::record(&mut self.out, STACK_TRACE, &body);
+        }
+    }
+
+    fn load_class(&mut self, class_id: u64, class_name: &str) {
+        self.class_serial += 1;
+        let name_id = self.string_id(class_name);
+        let mut body = Vec::new();
+        put_u4(&mut body, self.class_serial);
+        put_u8(&mut body, class_id);
+        put_u4(&mut body, EMPTY_STACK_TRACE_SERIAL);
+        put_u8(&mut body, name_id);
+        HprofWriter::record(&mut self.out, LOAD_CLASS, &body);
+    }
+
+    fn write_roots(&mut self, roots: &HeapDumpRoots, vm_roots: &[HeapWord]) {
+        for klass in &roots.classes {
+            self.segment.push(ROOT_STICKY_CLASS);
+            put_u8(&mut self.segment, class_id(klass));
+        }
+
+        for (index, thread) in roots.threads.iter().enumerate() {
+            let thread_serial = index as u32 + 1;
+            self.segment.push(ROOT_THREAD_OBJECT);
+            put_u8(&mut self.segment, object_id(thread.thread_object.instance_data()));
+            put_u4(&mut self.segment, thread_serial);
+            put_u4(&mut self.segment, thread_serial + EMPTY_STACK_TRACE_SERIAL);
+
+            for frame_root in thread.frame_roots.iter().filter(|value| is_reference(value)) {
+                self.segment.push(ROOT_JAVA_FRAME);
+                put_u8(&mut self.segment, value_id(frame_root));
+                put_u4(&mut self.segment, thread_serial);
+                put_u4(&mut self.segment, UNKNOWN_FRAME);
+            }
+        }
+
+        for vm_root in vm_roots {
+            self.segment.push(ROOT_UNKNOWN);
+            put_u8(&mut self.segment, object_id(vm_root));
+        }
+        self.sub_record_written();
+    }
+
+    fn class_dump(&mut self, klass: &Arc<Klass>) {
+        let static_fields: Vec<(u64, Option<PrimitiveType>, JvmValue)> = klass.static_fields()
+            .iter()
+            .map(|field| {
+                let value = field.initialized_static_value().unwrap_or_else(|| field.default());
+                (self.string_id(field.name()), PrimitiveType::from_descriptor(field.descriptor()), value)
+            })
+            .collect();
+        let instance_fields: Vec<(u64, Option<PrimitiveType>)> = klass.instance_fields()
+            .iter()
+            .map(|field| (self.string_id(field.name()), PrimitiveType::from_descriptor(field.descriptor())))
+            .collect();
+
+        let segment = &mut self.segment;
+        segment.push(CLASS_DUMP);
+        put_u8(segment, class_id(klass));
+        put_u4(segment, EMPTY_STACK_TRACE_SERIAL);
+        put_u8(segment, klass.super_class().as_ref().map_or(0, class_id));
+        //class loader, signers, protection domain and two reserved ids
+        (0..5).for_each(|_| put_u8(segment, 0));
+        put_u4(segment, JvmHeap::object_size(klass) as u32);
+        //constant pool entries
+        put_u2(segment, 0);
+
+        put_u2(segment, static_fields.len() as u16);
+        for (name_id, field_type, value) in &static_fields {
+            put_u8(segment, *name_id);
+            segment.push(basic_type(field_type));
+            put_value(segment, field_type, value);
+        }
+        put_u2(segment, instance_fields.len() as u16);
+        for (name_id, field_type) in &instance_fields {
+            put_u8(segment, *name_id);
+            segment.push(basic_type(field_type));
+        }
+        self.sub_record_written();
+    }
+
+    fn array_class_dump(&mut self, component_class: &Arc<Klass>, object_class_id: u64) {
+        let segment = &mut self.segment;
+        segment.push(CLASS_DUMP);
+        put_u8(segment, array_class_id(component_class));
+        put_u4(segment, EMPTY_STACK_TRACE_SERIAL);
+        put_u8(segment, object_class_id);
+        (0..5).for_each(|_| put_u8(segment, 0));
+        //instance size, constant pool entries, static and instance fields
+        put_u4(segment, 0);
+        (0..3).for_each(|_| put_u2(segment, 0));
+        self.sub_record_written();
+    }
+
+    /// The values of the fields are recorded starting with the ones declared by the class of the object, then
+    /// the ones declared by its superclasses.
+    fn instance_dump(&mut self, object: &ObjectOopDesc) {
+        let data = object.instance_data().data();
+        let data = data.read().unwrap();
+        let mut values = Vec::new();
+        let mut current = Some(object.klass());
+        while let Some(klass) = current {
+            current = klass.super_class();
+            let first_offset = current.as_ref().map_or(0, |super_class| super_class.instance_field_count());
+            for (index, field) in klass.instance_fields().iter().enumerate() {
+                let field_type = PrimitiveType::from_descriptor(field.descriptor());
+                put_value(&mut values, &field_type, data.get(first_offset + index).unwrap_or(&JvmValue::null_obj()));
+            }
+        }
+
+        let segment = &mut self.segment;
+        segment.push(INSTANCE_DUMP);
+        put_u8(segment, object_id(object.instance_data()));
+        put_u4(segment, EMPTY_STACK_TRACE_SERIAL);
+        put_u8(segment, class_id(&object.klass()));
+        put_u4(segment, values.len() as u32);
+        segment.extend_from_slice(&values);
+        self.sub_record_written();
+    }
+
+    fn object_array_dump(&mut self, array: &Oop) {
+        let component_class = array.java_klass_or_fail();
+        let data = array.instance_data().data();
+        let elements = data.read().unwrap();
+
+        let segment = &mut self.segment;
+        segment.push(OBJECT_ARRAY_DUMP);
+        put_u8(segment, object_id(array.instance_data()));
+        put_u4(segment, EMPTY_STACK_TRACE_SERIAL);
+        put_u4(segment, elements.len() as u32);
+        put_u8(segment, array_class_id(&component_class));
+        elements.iter().for_each(|element| put_u8(segment, value_id(element)));
+        self.sub_record_written();
+    }
+
+    fn primitive_array_dump(&mut self, array: &Oop) {
+        let element_type = match array {
+            Oop::PrimitiveArrayOop(array) => Some(array.inner_type),
+            _ => None,
+        };
+        let data = array.instance_data().data();
+        let elements = data.read().unwrap();
+
+        let segment = &mut self.segment;
+        segment.push(PRIMITIVE_ARRAY_DUMP);
+        put_u8(segment, object_id(array.instance_data()));
+        put_u4(segment, EMPTY_STACK_TRACE_SERIAL);
+        put_u4(segment, elements.len() as u32);
+        segment.push(basic_type(&element_type));
+        elements.iter().for_each(|element| put_value(segment, &element_type, element));
+        self.sub_record_written();
+    }
+
+    fn sub_record_written(&mut self) {
+        if self.segment.len() >= MAX_SEGMENT_SIZE {
+            self.flush_segment();
+        }
+    }
+
+    fn flush_segment(&mut self) {
+        if !self.segment.is_empty() {
+            HprofWriter::record(&mut self.out, HEAP_DUMP_SEGMENT, &self.segment);
+            self.segment.clear();
+        }
+    }
+}
+
+fn class_id(klass: &Arc<Klass>) -> u64 {
+    Arc::as_ptr(klass) as u64
+}
+
+//arrays don't have classes of their own yet, the id of an array class is derived from the id of its component
+//class, which is aligned, so it can't be the id of any other class
+fn array_class_id(component_class: &Arc<Klass>) -> u64 {
+    class_id(component_class) | 1
+}
+
+fn array_class_name(component_class: &Arc<Klass>) -> String {
+    format!("[L{};", component_class.qualified_name())
+}
+
+fn object_id(heap_word: &HeapWord) -> u64 {
+    heap_word.key() as u64
+}
+
+fn is_reference(value: &JvmValue) -> bool {
+    matches!(value, JvmValue::ObjRef(ObjectRef::Ref(_)))
+}
+
+fn value_id(value: &JvmValue) -> u64 {
+    match value {
+        JvmValue::ObjRef(ObjectRef::Ref(oop)) => object_id(oop.instance_data()),
+        _ => 0,
+    }
+}
+
+/// The type code of a field or array element, None stands for references.
+fn basic_type(field_type: &Option<PrimitiveType>) -> u8 {
+    match field_type {
+        None => OBJECT_TYPE,
+        Some(PrimitiveType::Boolean) => 4,
+        Some(PrimitiveType::Char) => 5,
+        Some(PrimitiveType::Float) => 6,
+        Some(PrimitiveType::Double) => 7,
+        Some(PrimitiveType::Byte) => 8,
+        Some(PrimitiveType::Short) => 9,
+        Some(PrimitiveType::Int) => 10,
+        Some(PrimitiveType::Long) => 11,
+    }
+}
+
+/// Writes the value with the size of the given type. Fields may hold ints stored by byte-code instead of their
+/// declared type, so those are narrowed first.
+fn put_value(out: &mut Vec<u8>, field_type: &Option<PrimitiveType>, value: &JvmValue) {
+    let field_type = match field_type {
+        None => return put_u8(out, value_id(value)),
+        Some(field_type) => field_type,
+    };
+
+    match value.clone().widen().narrow_to(field_type) {
+        JvmValue::Boolean { val } if *field_type == PrimitiveType::Boolean => out.push(val as u8),
+        JvmValue::Byte { val } if *field_type == PrimitiveType::Byte => out.push(val as u8),
+        JvmValue::Short { val } if *field_type == PrimitiveType::Short => put_u2(out, val as u16),
+        JvmValue::Char { val } if *field_type == PrimitiveType::Char => put_u2(out, val),
+        JvmValue::Int { val } if *field_type == PrimitiveType::Int => put_u4(out, val as u32),
+        JvmValue::Long { val } if *field_type == PrimitiveType::Long => put_u8(out, val as u64),
+        JvmValue::Float { val } if *field_type == PrimitiveType::Float => put_u4(out, val.to_bits()),
+        JvmValue::Double { val } if *field_type == PrimitiveType::Double => put_u8(out, val.to_bits()),
+        _ => out.resize(out.len() + field_type.size_in_bytes(), 0),
+    }
+}
+
+fn put_u2(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_u4(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_u8(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
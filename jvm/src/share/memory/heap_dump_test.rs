@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::path::PathBuf;
+
+use crate::share::memory::heap::{Heap, JvmHeap};
+use crate::share::memory::heap_dump::{HeapDumpRoots, ThreadRoots};
+use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType};
+use crate::share::utilities::testing::{field_layout_class, java_lang_object_class};
+
+//the offset of FieldLayout.intField in the dumped instances, following byteField, longField and referenceField
+const INT_FIELD_OFFSET: usize = 1 + 8 + 8;
+
+/// The contents of a heap dump relevant to the tests.
+#[derive(Default)]
+struct ParsedDump {
+    strings: HashMap<u64, String>,
+    class_names: HashMap<u64, String>,
+    roots: Vec<(u8, u64)>,
+    instances: HashMap<u64, (u64, Vec<u8>)>,
+    object_arrays: HashMap<u64, Vec<u64>>,
+    primitive_arrays: HashMap<u64, (u8, u32)>,
+    ended: bool,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> &'a [u8] {
+        let bytes = &self.bytes[self.position..self.position + length];
+        self.position += length;
+        bytes
+    }
+
+    fn u1(&mut self) -> u8 {
+        self.take(1)[0]
+    }
+
+    fn u2(&mut self) -> u16 {
+        u16::from_be_bytes(self.take(2).try_into().unwrap())
+    }
+
+    fn u4(&mut self) -> u32 {
+        u32::from_be_bytes(self.take(4).try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> u64 {
+        u64::from_be_bytes(self.take(8).try_into().unwrap())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+}
+
+fn value_size(basic_type: u8) -> usize {
+    match basic_type {
+        2 | 7 | 11 => 8,
+        4 | 8 => 1,
+        5 | 9 => 2,
+        6 | 10 => 4,
+        _ => panic!("Unknown basic type {}", basic_type),
+    }
+}
+
+fn parse_segment(segment: &[u8], dump: &mut ParsedDump) {
+    let mut reader = Reader { bytes: segment, position: 0 };
+    while !reader.is_empty() {
+        match reader.u1() {
+            tag @ 0xFF | tag @ 0x05 => dump.roots.push((tag, reader.u8())),
+            tag @ 0x03 | tag @ 0x08 => {
+                dump.roots.push((tag, reader.u8()));
+                reader.take(8);
+            }
+            0x20 => {
+                reader.take(8 + 4 + 6 * 8 + 4);
+                assert_eq!(0, reader.u2());
+                for _ in 0..reader.u2() {
+                    reader.u8();
+                    let basic_type = reader.u1();
+                    reader.take(value_size(basic_type));
+                }
+                for _ in 0..reader.u2() {
+                    reader.take(8 + 1);
+                }
+            }
+            0x21 => {
+                let id = reader.u8();
+                reader.u4();
+                let class_id = reader.u8();
+                let length = reader.u4() as usize;
+                dump.instances.insert(id, (class_id, reader.take(length).to_vec()));
+            }
+            0x22 => {
+                let id = reader.u8();
+                reader.u4();
+                let length = reader.u4();
+                reader.u8();
+                dump.object_arrays.insert(id, (0..length).map(|_| reader.u8()).collect());
+            }
+            0x23 => {
+                let id = reader.u8();
+                reader.u4();
+                let length = reader.u4();
+                let basic_type = reader.u1();
+                reader.take(length as usize * value_size(basic_type));
+                dump.primitive_arrays.insert(id, (basic_type, length));
+            }
+            tag => panic!("Unknown sub-record {:#x}", tag),
+        }
+    }
+}
+
+fn parse_dump(bytes: &[u8]) -> ParsedDump {
+    let mut reader = Reader { bytes, position: 0 };
+    assert_eq!(b"JAVA PROFILE 1.0.2\0", reader.take(19));
+    assert_eq!(8, reader.u4());
+    reader.u8();
+
+    let mut dump = ParsedDump::default();
+    while !reader.is_empty() {
+        assert!(!dump.ended, "Records after HEAP DUMP END");
+        let tag = reader.u1();
+        reader.u4();
+        let length = reader.u4() as usize;
+        let mut body = Reader { bytes: reader.take(length), position: 0 };
+        match tag {
+            0x01 => {
+                let id = body.u8();
+                dump.strings.insert(id, String::from_utf8(body.take(length - 8).to_vec()).unwrap());
+            }
+            0x02 => {
+                body.u4();
+                let class_id = body.u8();
+                body.u4();
+                let name = dump.strings[&body.u8()].clone();
+                dump.class_names.insert(class_id, name);
+            }
+            0x05 => {}
+            0x1C => parse_segment(body.bytes, &mut dump),
+            0x2C => dump.ended = true,
+            tag => panic!("Unknown record {:#x}", tag),
+        }
+    }
+    dump
+}
+
+fn dump(heap: &JvmHeap, roots: &HeapDumpRoots, name: &str) -> ParsedDump {
+    let path: PathBuf = std::env::temp_dir().join(format!("{}-{}.hprof", name, std::process::id()));
+    heap.dump_heap(&path, roots).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    parse_dump(&bytes)
+}
+
+#[test]
+pub fn dump_contains_every_object() {
+    let heap = JvmHeap::new();
+    let object = heap.allocate_object(field_layout_class()).unwrap();
+    let array = heap.allocate_array(java_lang_object_class(), 2).unwrap();
+    let primitive_array = heap.allocate_primitive_array(PrimitiveType::Int, 4).unwrap();
+    object.instance_data().put_field(3, JvmValue::from(42)).unwrap();
+    array.instance_data.put_field(1, JvmValue::from(object.clone())).unwrap();
+    heap.forget_recent_allocations();
+
+    let dump = dump(&heap, &HeapDumpRoots { classes: Vec::new(), threads: Vec::new() }, "objects");
+
+    assert!(dump.ended);
+    let (class_id, values) = &dump.instances[&(object.instance_data().key() as u64)];
+    assert_eq!("tests/unit/FieldLayout", dump.class_names[class_id]);
+    assert_eq!(38, values.len());
+    assert_eq!(&42i32.to_be_bytes(), &values[INT_FIELD_OFFSET..INT_FIELD_OFFSET + 4]);
+    assert_eq!(vec![0, object.instance_data().key() as u64], dump.object_arrays[&(array.instance_data.key() as u64)]);
+    assert_eq!((10, 4), dump.primitive_arrays[&(primitive_array.instance_data.key() as u64)]);
+    assert!(dump.class_names.values().any(|name| name == "java/lang/Object"));
+}
+
+#[test]
+pub fn dump_contains_roots() {
+    let heap = JvmHeap::new();
+    let klass = field_layout_class();
+    let thread = heap.allocate_object(java_lang_object_class()).unwrap();
+    let local = heap.allocate_primitive_array(PrimitiveType::Byte, 1).unwrap();
+    heap.forget_recent_allocations();
+    let recent = heap.allocate_primitive_array(PrimitiveType::Byte, 1).unwrap();
+    let roots = HeapDumpRoots {
+        classes: vec![klass.clone()],
+        threads: vec![ThreadRoots { thread_object: thread.clone(), frame_roots: vec![JvmValue::from(local.clone()), JvmValue::null_obj()] }],
+    };
+
+    let dump = dump(&heap, &roots, "roots");
+    heap.forget_recent_allocations();
+
+    assert_eq!(vec![
+        (0x05, std::sync::Arc::as_ptr(&klass) as u64),
+        (0x08, thread.instance_data().key() as u64),
+        (0x03, local.instance_data.key() as u64),
+        (0xFF, recent.instance_data.key() as u64),
+    ], dump.roots);
+}
@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::share::memory::heap::{Heap, JvmHeap};
+use crate::share::memory::oop::oops::ArrayOopDesc;
+use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType};
+use crate::share::utilities::testing::java_lang_object_class;
+
+fn allocate_node(heap: &JvmHeap) -> ArrayOopDesc {
+    heap.allocate_array(java_lang_object_class(), 1).unwrap()
+}
+
+fn link(from: &ArrayOopDesc, to: &ArrayOopDesc) {
+    from.instance_data.put_field(0, JvmValue::from(to.clone())).unwrap();
+}
+
+#[test]
+pub fn empty_objects_are_distinct() {
+    let heap = JvmHeap::new();
+    heap.allocate_primitive_array(PrimitiveType::Int, 0).unwrap();
+    heap.allocate_primitive_array(PrimitiveType::Int, 0).unwrap();
+
+    assert_eq!(2, heap.object_count());
+}
+
+#[test]
+pub fn words_are_equal_only_for_the_same_allocation() {
+    let heap = JvmHeap::new();
+    let first = heap.allocate_primitive_array(PrimitiveType::Int, 0).unwrap();
+    let second = heap.allocate_primitive_array(PrimitiveType::Int, 0).unwrap();
+
+    assert_ne!(first.instance_data, second.instance_data);
+    assert_eq!(first.instance_data, first.clone().instance_data);
+}
+
+#[test]
+pub fn unreachable_objects_are_freed() {
+    let heap = JvmHeap::new();
+    allocate_node(&heap);
+    heap.allocate_primitive_array(PrimitiveType::Int, 4).unwrap();
+
+    heap.forget_recent_allocations();
+    assert_eq!(2, heap.collect_garbage(Vec::new()));
+    assert_eq!(0, heap.object_count());
+}
+
+#[test]
+pub fn objects_reachable_from_roots_are_kept() {
+    let heap = JvmHeap::new();
+    let (first, second, third) = (allocate_node(&heap), allocate_node(&heap), allocate_node(&heap));
+    link(&first, &second);
+    link(&second, &third);
+    allocate_node(&heap);
+
+    heap.forget_recent_allocations();
+    assert_eq!(1, heap.collect_garbage(vec![JvmValue::Int { val: 1 }, JvmValue::from(first.clone())]));
+    assert_eq!(3, heap.object_count());
+    assert_eq!(Ok(JvmValue::from(third)), second.instance_data.get_field(0));
+}
+
+#[test]
+pub fn unreachable_cycles_are_freed() {
+    let heap = JvmHeap::new();
+    let (first, second) = (allocate_node(&heap), allocate_node(&heap));
+    link(&first, &second);
+    link(&second, &first);
+
+    heap.forget_recent_allocations();
+    assert_eq!(2, heap.collect_garbage(Vec::new()));
+    assert_eq!(0, heap.object_count());
+    //the references of the cycle are cleared, so the objects are not kept alive by each other
+    assert_eq!(Ok(JvmValue::null_obj()), first.instance_data.get_field(0));
+    assert_eq!(Ok(JvmValue::null_obj()), second.instance_data.get_field(0));
+}
+
+#[test]
+pub fn reachable_cycles_are_kept() {
+    let heap = JvmHeap::new();
+    let (first, second) = (allocate_node(&heap), allocate_node(&heap));
+    link(&first, &second);
+    link(&second, &first);
+
+    heap.forget_recent_allocations();
+    assert_eq!(0, heap.collect_garbage(vec![JvmValue::from(second.clone())]));
+    assert_eq!(2, heap.object_count());
+    assert_eq!(Ok(JvmValue::from(second)), first.instance_data.get_field(0));
+}
+
+#[test]
+pub fn allocation_exceeding_max_heap_size_throws_out_of_memory_error() {
+    let heap = JvmHeap::with_max_heap_size(1024);
+
+    let exception = heap.allocate_primitive_array(PrimitiveType::Long, 1024).unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/OutOfMemoryError")), exception.exception_class());
+    assert_eq!(0, heap.used_bytes());
+    assert!(heap.allocate_primitive_array(PrimitiveType::Long, 64).is_ok());
+}
+
+#[test]
+pub fn primitive_arrays_are_accounted_by_element_size() {
+    let heap = JvmHeap::new();
+    heap.allocate_primitive_array(PrimitiveType::Byte, 100).unwrap();
+    let byte_array_size = heap.used_bytes();
+    heap.allocate_primitive_array(PrimitiveType::Int, 100).unwrap();
+    let int_array_size = heap.used_bytes() - byte_array_size;
+
+    assert_eq!(byte_array_size + 300, int_array_size);
+}
+
+#[test]
+pub fn exhausted_heap_collects_garbage_before_failing() {
+    let heap = Arc::new(JvmHeap::with_max_heap_size(1024));
+    let collections = Arc::new(AtomicUsize::new(0));
+    let (gc_heap, gc_collections) = (Arc::downgrade(&heap), collections.clone());
+    heap.set_garbage_collector(Box::new(move |_| {
+        gc_collections.fetch_add(1, Ordering::SeqCst);
+        gc_heap.upgrade().map_or(0, |heap| heap.collect_garbage(Vec::new()))
+    }));
+
+    for _ in 0..10 {
+        heap.allocate_primitive_array(PrimitiveType::Long, 64).unwrap();
+        heap.forget_recent_allocations();
+    }
+
+    assert!(collections.load(Ordering::SeqCst) > 0);
+    assert!(heap.used_bytes() <= 1024);
+}
+
+#[test]
+pub fn recent_allocations_survive_garbage_collection() {
+    let heap = JvmHeap::new();
+    allocate_node(&heap);
+    assert_eq!(0, heap.collect_garbage(Vec::new()));
+
+    heap.forget_recent_allocations();
+    assert_eq!(1, heap.collect_garbage(Vec::new()));
+    assert_eq!(0, heap.used_bytes());
+}
+
+#[test]
+pub fn recent_allocations_of_other_threads_survive_garbage_collection() {
+    let heap = Arc::new(JvmHeap::new());
+    let allocating_heap = heap.clone();
+    std::thread::spawn(move || {
+        allocate_node(&allocating_heap);
+    }).join().unwrap();
+
+    heap.forget_recent_allocations();
+    assert_eq!(0, heap.collect_garbage(Vec::new()));
+    assert_eq!(1, heap.object_count());
+}
+
+#[test]
+pub fn identity_hash_is_stable_and_shared_by_references() {
+    let heap = JvmHeap::new();
+    let node = allocate_node(&heap);
+    let reference = node.clone();
+
+    let identity_hash = node.instance_data.identity_hash();
+    assert_ne!(0, identity_hash);
+    assert!(identity_hash > 0);
+    assert_eq!(identity_hash, node.instance_data.identity_hash());
+    assert_eq!(identity_hash, reference.instance_data.identity_hash());
+}
+
+#[test]
+pub fn identity_hashes_of_distinct_objects_differ() {
+    let heap = JvmHeap::new();
+    let identity_hashes: HashSet<i32> = (0..1000)
+        .map(|_| heap.allocate_primitive_array(PrimitiveType::Int, 0).unwrap().instance_data.identity_hash())
+        .collect();
+
+    assert_eq!(1000, identity_hashes.len());
+}
@@ -1,2 +1,5 @@
 pub mod heap;
 pub mod oop;
+pub mod garbage_collector;
+pub mod generational_heap;
+pub mod heap_dump;
@@ -2,6 +2,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::share::classfile::klass::Klass;
 use crate::share::memory::heap::HeapWord;
+use crate::share::runtime::monitor::ObjectMonitor;
 use crate::share::memory::oop::oops::{ObjectOopDesc, PrimitiveArrayOopDesc, ArrayOopDesc};
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType};
@@ -26,6 +27,26 @@ impl Oop {
         }
     }
 
+    pub fn array_length(&self) -> Result<i32, JvmException> {
+        match self {
+            Oop::ArrayOop(desc) => Ok(desc.size),
+            Oop::PrimitiveArrayOop(desc) => Ok(desc.size),
+            Oop::ObjectOop(_) => Err(JvmException::from("Expected array reference!"))
+        }
+    }
+
+    pub fn check_array_index(&self, index: i32) -> Result<(), JvmException> {
+        let length = self.array_length()?;
+        if index < 0 || index >= length {
+            return Err(JvmException::array_index_out_of_bounds(index, length));
+        }
+        Ok(())
+    }
+
+    pub fn monitor(&self) -> &ObjectMonitor {
+        self.instance_data().monitor()
+    }
+
     pub fn java_klass_or_fail(&self) -> Arc<Klass> {
         match self {
             Oop::ObjectOop(oops) => oops.klass(),
@@ -62,7 +83,7 @@ pub mod oops {
 
     #[derive(Debug, Clone, PartialEq)]
     pub struct ObjectOopDesc {
-        // mark: usize,
+        //the header of the object, like its monitor, is kept in the instance data shared by all its references
         //should make this more compact
         klass: KlassPointer,
         instance_data: HeapWord,
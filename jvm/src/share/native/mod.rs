@@ -2,4 +2,6 @@ pub mod native_method_repo;
 pub mod native_methods;
 pub mod class;
 pub mod object;
+pub mod thread;
 pub mod native_helper_classes;
+pub mod system;
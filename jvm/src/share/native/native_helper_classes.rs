@@ -1,15 +1,154 @@
+use crate::share::memory::oop::oops::ObjectOopDesc;
+use crate::share::utilities::jvm_exception::JvmException;
+
+/// Resolves the offset of a field the VM accesses directly by its name and type, so the helpers don't depend on the
+/// order the fields are declared in by the class library.
+fn field_offset(object_ref: &ObjectOopDesc, name: &str, type_descriptor: &str) -> Result<usize, JvmException> {
+    object_ref.klass()
+        .get_instance_field_offset(&String::from(name), &String::from(type_descriptor))
+        .ok_or_else(|| JvmException::no_such_field(&String::from(name)))
+}
+
 pub mod java_lang_String {
+    #![allow(non_snake_case)]
     use crate::share::memory::oop::oops::{ObjectOopDesc, PrimitiveArrayOopDesc};
-    use crate::share::utilities::jvm_value::JvmValue;
-    use crate::share::memory::heap::{JvmHeap, Heap};
+    use crate::share::classfile::klass::Klass;
+    use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType};
+    use crate::share::memory::heap::Heap;
+    use std::sync::Arc;
+    use crate::share::utilities::jvm_exception::JvmException;
+
+    use super::field_offset;
+
+    const BUFFER: &str = "value";
+    const BUFFER_TYPE: &str = "[C";
+
+    pub fn put_buffer(heap: &dyn Heap, string_ref: ObjectOopDesc, buffer: PrimitiveArrayOopDesc) -> Result<(), JvmException> {
+        let buffer = JvmValue::from(buffer);
+        string_ref.instance_data().put_field(field_offset(&string_ref, BUFFER, BUFFER_TYPE)?, buffer.clone())?;
+        heap.write_barrier(string_ref.instance_data(), &buffer);
+        Ok(())
+    }
+
+    /// Allocates a new instance of the given java/lang/String class holding the contents.
+    pub fn allocate(heap: &dyn Heap, string_klass: Arc<Klass>, contents: String) -> Result<ObjectOopDesc, JvmException> {
+        let string_ref = heap.allocate_object(string_klass)?;
+
+        let string_contents = contents.into_bytes();
+        let buffer = heap.allocate_primitive_array(PrimitiveType::Byte, string_contents.len() as i32)?;
+        buffer.copy_bytes(heap, string_contents)?;
+        put_buffer(heap, string_ref.clone(), buffer)?;
+        Ok(string_ref)
+    }
+
+    pub fn to_rust_string(string_ref: &ObjectOopDesc) -> Result<String, JvmException> {
+        let buffer = match string_ref.instance_data().get_field(field_offset(string_ref, BUFFER, BUFFER_TYPE)?)? {
+            JvmValue::ObjRef(buffer_ref) => buffer_ref.dereference()?,
+            invalid => return Err(JvmException::from(format!("String buffer should be an array, but was {:?}", invalid))),
+        };
+
+        let data = buffer.instance_data().data();
+        let bytes = data.read().unwrap().iter()
+            .map(|value| match value {
+                JvmValue::Byte { val } => Ok(*val as u8),
+                JvmValue::Char { val } => Ok(*val as u8),
+                invalid => Err(JvmException::from(format!("Invalid element in String buffer: {:?}", invalid))),
+            })
+            .collect::<Result<Vec<u8>, JvmException>>()?;
+
+        String::from_utf8(bytes).map_err(|err| JvmException::from(format!("String buffer is not valid UTF-8: {}", err)))
+    }
+}
+pub mod java_lang_Thread {
+    #![allow(non_snake_case)]
+    use crate::share::memory::heap::Heap;
+    use crate::share::memory::oop::oops::ObjectOopDesc;
     use crate::share::memory::oop::Oop;
+    use crate::share::native::native_helper_classes::java_lang_String;
+    use crate::share::utilities::jvm_value::{JvmValue, ObjectRef};
     use crate::share::utilities::jvm_exception::JvmException;
-    use std::ops::Deref;
 
-    const BUFFER_OFFSET: usize = 0;
+    use super::field_offset;
 
-    pub fn put_buffer(string_ref: ObjectOopDesc, buffer: PrimitiveArrayOopDesc) -> Result<(), JvmException> {
-        string_ref.instance_data().put_field(BUFFER_OFFSET, JvmValue::from(buffer))
+    pub fn get_name(thread_ref: &ObjectOopDesc) -> Result<Option<String>, JvmException> {
+        match thread_ref.instance_data().get_field(field_offset(thread_ref, "name", "Ljava/lang/String;")?)? {
+            JvmValue::ObjRef(ObjectRef::Null) => Ok(None),
+            JvmValue::ObjRef(name_ref) => match name_ref.dereference()? {
+                Oop::ObjectOop(name) => java_lang_String::to_rust_string(&name).map(Some),
+                invalid => Err(JvmException::from(format!("Thread name should be a String, but was {:?}", invalid))),
+            },
+            invalid => Err(JvmException::from(format!("Thread name should be a reference, but was {:?}", invalid))),
+        }
     }
 
-}
\ No newline at end of file
+    pub fn put_name(heap: &dyn Heap, thread_ref: &ObjectOopDesc, name: ObjectOopDesc) -> Result<(), JvmException> {
+        let name = JvmValue::from(name);
+        thread_ref.instance_data().put_field(field_offset(thread_ref, "name", "Ljava/lang/String;")?, name.clone())?;
+        heap.write_barrier(thread_ref.instance_data(), &name);
+        Ok(())
+    }
+
+    pub fn is_daemon(thread_ref: &ObjectOopDesc) -> Result<bool, JvmException> {
+        get_boolean(thread_ref, "daemon")
+    }
+
+    pub fn is_started(thread_ref: &ObjectOopDesc) -> Result<bool, JvmException> {
+        get_boolean(thread_ref, "started")
+    }
+
+    pub fn set_started(thread_ref: &ObjectOopDesc) -> Result<(), JvmException> {
+        thread_ref.instance_data().put_field(field_offset(thread_ref, "started", "Z")?, JvmValue::Boolean { val: true })
+    }
+
+    //booleans stored by byte-code are ints on the operand stack
+    fn get_boolean(thread_ref: &ObjectOopDesc, name: &str) -> Result<bool, JvmException> {
+        match thread_ref.instance_data().get_field(field_offset(thread_ref, name, "Z")?)? {
+            JvmValue::Boolean { val } => Ok(val),
+            JvmValue::Int { val } => Ok(val != 0),
+            invalid => Err(JvmException::from(format!("Expected a boolean field in Thread, but was {:?}", invalid))),
+        }
+    }
+}
+pub mod java_lang_Throwable {
+    #![allow(non_snake_case)]
+    use crate::share::memory::heap::Heap;
+    use crate::share::memory::oop::Oop;
+    use crate::share::memory::oop::oops::ObjectOopDesc;
+    use crate::share::utilities::jvm_value::{JvmValue, ObjectRef};
+    use crate::share::utilities::jvm_exception::JvmException;
+
+    use super::field_offset;
+
+    const DETAIL_MESSAGE: &str = "detailMessage";
+    const CAUSE: &str = "cause";
+
+    pub fn put_detail_message(heap: &dyn Heap, throwable_ref: ObjectOopDesc, detail_message: ObjectOopDesc) -> Result<(), JvmException> {
+        let detail_message = JvmValue::from(detail_message);
+        throwable_ref.instance_data().put_field(field_offset(&throwable_ref, DETAIL_MESSAGE, "Ljava/lang/String;")?, detail_message.clone())?;
+        heap.write_barrier(throwable_ref.instance_data(), &detail_message);
+        Ok(())
+    }
+
+    pub fn get_detail_message(throwable_ref: &ObjectOopDesc) -> Result<JvmValue, JvmException> {
+        throwable_ref.instance_data().get_field(field_offset(throwable_ref, DETAIL_MESSAGE, "Ljava/lang/String;")?)
+    }
+
+    /// The detail message of the throwable converted to a Rust string, None if it has no message.
+    pub fn detail_message(throwable_ref: &ObjectOopDesc) -> Option<String> {
+        match get_detail_message(throwable_ref) {
+            Ok(JvmValue::ObjRef(ObjectRef::Ref(Oop::ObjectOop(message)))) => super::java_lang_String::to_rust_string(&message).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn put_cause(heap: &dyn Heap, throwable_ref: ObjectOopDesc, cause: Oop) -> Result<(), JvmException> {
+        let cause = JvmValue::from(cause);
+        throwable_ref.instance_data().put_field(field_offset(&throwable_ref, CAUSE, "Ljava/lang/Throwable;")?, cause.clone())?;
+        heap.write_barrier(throwable_ref.instance_data(), &cause);
+        Ok(())
+    }
+
+    pub fn get_cause(throwable_ref: &ObjectOopDesc) -> Result<JvmValue, JvmException> {
+        throwable_ref.instance_data().get_field(field_offset(throwable_ref, CAUSE, "Ljava/lang/Throwable;")?)
+    }
+}
@@ -3,7 +3,24 @@ use crate::share::native::native_methods::NativeMethod;
 use crate::share::utilities::global_symbols::Symbols::{
     java_lang_Object_hashCode,
     java_lang_Object_registerNatives,
+    java_lang_Object_wait,
+    java_lang_Object_notify,
+    java_lang_Object_notifyAll,
     java_lang_Class_registerNatives,
+    java_lang_Thread_nextThreadName,
+    java_lang_Thread_currentThread,
+    java_lang_Thread_yield,
+    java_lang_Thread_sleep,
+    java_lang_Thread_start0,
+    java_lang_Thread_isInterrupted,
+    java_lang_Thread_interrupt0,
+    java_lang_Thread_isAlive,
+    java_lang_Thread_join0,
+    java_lang_Thread_activeCount,
+    java_lang_Thread_enumerate,
+    java_lang_System_gc,
+    java_lang_System_identityHashCode,
+    java_lang_System_getProperty,
 };
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -23,14 +40,49 @@ impl NativeMethodRepo {
             java_lang_Object_hashCode.clone(),
             crate::share::native::object::hash_code,
         );
+        store.insert(
+            java_lang_Object_wait.clone(),
+            crate::share::native::object::wait,
+        );
+        store.insert(
+            java_lang_Object_notify.clone(),
+            crate::share::native::object::notify,
+        );
+        store.insert(
+            java_lang_Object_notifyAll.clone(),
+            crate::share::native::object::notify_all,
+        );
         store.insert(
             java_lang_Class_registerNatives.clone(),
             crate::share::native::class::register_natives,
         );
 
+        store.insert(java_lang_Thread_nextThreadName.clone(), crate::share::native::thread::next_thread_name);
+        store.insert(java_lang_Thread_currentThread.clone(), crate::share::native::thread::current_thread);
+        store.insert(java_lang_Thread_yield.clone(), crate::share::native::thread::yield_thread);
+        store.insert(java_lang_Thread_sleep.clone(), crate::share::native::thread::sleep);
+        store.insert(java_lang_Thread_start0.clone(), crate::share::native::thread::start0);
+        store.insert(java_lang_Thread_isInterrupted.clone(), crate::share::native::thread::is_interrupted);
+        store.insert(java_lang_Thread_interrupt0.clone(), crate::share::native::thread::interrupt0);
+        store.insert(java_lang_Thread_isAlive.clone(), crate::share::native::thread::is_alive);
+        store.insert(java_lang_Thread_join0.clone(), crate::share::native::thread::join0);
+        store.insert(java_lang_Thread_activeCount.clone(), crate::share::native::thread::active_count);
+        store.insert(java_lang_Thread_enumerate.clone(), crate::share::native::thread::enumerate);
+
+        store.insert(java_lang_System_gc.clone(), crate::share::native::system::gc);
+        store.insert(java_lang_System_identityHashCode.clone(), crate::share::native::system::identity_hash_code);
+        store.insert(java_lang_System_getProperty.clone(), crate::share::native::system::get_property);
+
         NativeMethodRepo { store }
     }
 
+    /// Registers the implementation of a native method, replacing the one registered with the same symbol.
+    /// The symbol is the qualified name of the class followed by '_' and the name and descriptor of the method,
+    /// e.g. java/lang/System_gc()V.
+    pub fn register(&mut self, symbol: String, native_method: NativeMethod) {
+        self.store.insert(symbol, native_method);
+    }
+
     pub fn find_method(&self, method: &MethodInfo) -> Option<NativeMethod> {
         let key = format!("{}_{}", method.get_klass().qualified_name(), method.name_desc());
         match self.store.get(key.as_str()) {
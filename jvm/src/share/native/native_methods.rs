@@ -4,20 +4,51 @@ use crate::share::utilities::context::GlobalContext;
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::utilities::jvm_value::JvmValue;
 use std::sync::Arc;
+use crate::share::memory::oop::Oop;
+use crate::share::memory::oop::oops::ObjectOopDesc;
 use utils::ResultIterator;
 
 pub struct NativeMethodArgs<'a> {
     current_class: &'a Klass,
-    context: &'a GlobalContext,
+    context: &'a Arc<GlobalContext>,
     java_args: Vec<JvmValue>,
 }
 
 impl<'a> NativeMethodArgs<'a> {
-    pub fn new<'b>(class: &'b Klass, context: &'b GlobalContext) -> NativeMethodArgs<'b> {
+    pub fn new<'b>(class: &'b Klass, context: &'b Arc<GlobalContext>, java_args: Vec<JvmValue>) -> NativeMethodArgs<'b> {
         NativeMethodArgs {
             current_class: class,
             context,
-            java_args: Vec::new(),
+            java_args,
+        }
+    }
+
+    pub fn current_class(&self) -> &Klass {
+        self.current_class
+    }
+
+    pub fn context(&self) -> &Arc<GlobalContext> {
+        self.context
+    }
+
+    /// The arguments the native method was invoked with, starting with the receiver for instance methods.
+    pub fn java_args(&self) -> &Vec<JvmValue> {
+        &self.java_args
+    }
+
+    /// The receiver of an instance native method, which can be an array for the methods of java/lang/Object.
+    pub fn receiver(&self) -> Result<Oop, JvmException> {
+        match self.java_args.first() {
+            Some(JvmValue::ObjRef(receiver)) => receiver.dereference(),
+            other => Err(JvmException::from(format!("Native method expected a receiver, but got {:?}", other))),
+        }
+    }
+
+    /// The receiver of an instance native method.
+    pub fn this(&self) -> Result<ObjectOopDesc, JvmException> {
+        match self.receiver()? {
+            Oop::ObjectOop(object) => Ok(object),
+            other => Err(JvmException::from(format!("Receiver of native method should be an object, but was {:?}", other))),
         }
     }
 }
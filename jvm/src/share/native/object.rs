@@ -2,13 +2,44 @@ use crate::share::native::native_methods::NativeMethodArgs;
 use crate::share::utilities::jvm_value::JvmValue;
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::native::native_methods;
+use crate::share::runtime::thread::JavaThread;
+use std::time::Duration;
 
 pub fn register_natives(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
     native_methods::register_natives(args);
     Ok(JvmValue::Void {})
 }
 
-pub fn hash_code(_args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
-    //TODO Implement this
-    Ok(JvmValue::Int { val: 1 })
+pub fn hash_code(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    Ok(JvmValue::Int { val: args.receiver()?.instance_data().identity_hash() })
+}
+
+pub fn wait(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let timeout_millis = match args.java_args().get(1) {
+        Some(JvmValue::Long { val }) => *val,
+        other => return Err(JvmException::from(format!("Object.wait expects a long argument, but got {:?}", other))),
+    };
+    if timeout_millis < 0 {
+        return Err(JvmException::illegal_argument("timeout value is negative"));
+    }
+    //a zero timeout means waiting until notified
+    let timeout = Some(timeout_millis)
+        .filter(|millis| *millis > 0)
+        .map(|millis| Duration::from_millis(millis as u64));
+
+    let current_thread = JavaThread::current()?;
+    args.receiver()?
+        .monitor()
+        .wait(timeout, &|| current_thread.is_interrupted(true))?;
+    Ok(JvmValue::Void {})
+}
+
+pub fn notify(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    args.receiver()?.monitor().notify()?;
+    Ok(JvmValue::Void {})
+}
+
+pub fn notify_all(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    args.receiver()?.monitor().notify_all()?;
+    Ok(JvmValue::Void {})
 }
\ No newline at end of file
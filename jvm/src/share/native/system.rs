@@ -0,0 +1,40 @@
+use crate::share::memory::garbage_collector;
+use crate::share::memory::oop::Oop;
+use crate::share::native::native_helper_classes::java_lang_String;
+use crate::share::native::native_methods::NativeMethodArgs;
+use crate::share::utilities::global_symbols::Symbols;
+use crate::share::utilities::jvm_exception::JvmException;
+use crate::share::utilities::jvm_value::{JvmValue, ObjectRef};
+
+pub fn gc(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    garbage_collector::collect_garbage(args.context());
+    Ok(JvmValue::Void {})
+}
+
+/// The identity hash code of the argument, the one returned by Object.hashCode even if it's overridden.
+pub fn identity_hash_code(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let identity_hash = match args.java_args().first() {
+        Some(JvmValue::ObjRef(ObjectRef::Null)) => 0,
+        Some(JvmValue::ObjRef(object_ref)) => object_ref.dereference()?.instance_data().identity_hash(),
+        other => return Err(JvmException::from(format!("System.identityHashCode expects a reference, but got {:?}", other))),
+    };
+    Ok(JvmValue::Int { val: identity_hash })
+}
+
+/// The value of the system property, given on the start of the JVM, or null if it isn't set.
+pub fn get_property(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let key = match args.java_args().first() {
+        Some(JvmValue::ObjRef(ObjectRef::Ref(Oop::ObjectOop(key)))) => java_lang_String::to_rust_string(key)?,
+        Some(JvmValue::ObjRef(ObjectRef::Null)) => return Err(JvmException::null_pointer()),
+        other => return Err(JvmException::from(format!("System.getProperty expects a String, but got {:?}", other))),
+    };
+
+    match args.context().options().system_properties.get(&key) {
+        None => Ok(JvmValue::null_obj()),
+        Some(value) => {
+            let string_class = args.context().class_loader().load_and_init_class(&Symbols::java_lang_String)?;
+            let string_ref = java_lang_String::allocate(args.context().heap().as_ref(), string_class, value.clone())?;
+            Ok(JvmValue::from(string_ref))
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::ops::Deref;
+use std::thread;
+use std::time::Duration;
+
+use crate::share::memory::oop::Oop;
+use crate::share::native::native_helper_classes::java_lang_String;
+use crate::share::native::native_methods::NativeMethodArgs;
+use crate::share::runtime::thread::JavaThread;
+use crate::share::utilities::global_symbols::Symbols;
+use crate::share::utilities::jvm_exception::JvmException;
+use crate::share::utilities::jvm_value::JvmValue;
+
+pub fn next_thread_name(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let context = args.context();
+    let string_class = context.class_loader().load_and_init_class(&Symbols::java_lang_String)?;
+    let name = context.threads().next_thread_name();
+    java_lang_String::allocate(context.heap().deref(), string_class, name).map(JvmValue::from)
+}
+
+pub fn current_thread(_args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    Ok(JvmValue::from(JavaThread::current()?.thread_object().clone()))
+}
+
+pub fn yield_thread(_args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    thread::yield_now();
+    Ok(JvmValue::Void {})
+}
+
+pub fn sleep(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let millis = match args.java_args().first() {
+        Some(JvmValue::Long { val }) => *val,
+        other => return Err(JvmException::from(format!("Thread.sleep expects a long argument, but got {:?}", other))),
+    };
+    if millis < 0 {
+        return Err(JvmException::illegal_argument("timeout value is negative"));
+    }
+
+    JavaThread::current()?.sleep(Duration::from_millis(millis as u64))?;
+    Ok(JvmValue::Void {})
+}
+
+pub fn start0(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    JavaThread::start(args.context(), args.this()?)?;
+    Ok(JvmValue::Void {})
+}
+
+pub fn is_interrupted(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let clear_interrupted = match args.java_args().get(1) {
+        Some(JvmValue::Boolean { val }) => *val,
+        Some(JvmValue::Int { val }) => *val != 0,
+        other => return Err(JvmException::from(format!("Thread.isInterrupted expects a boolean argument, but got {:?}", other))),
+    };
+
+    let interrupted = args.context().threads()
+        .find(&args.this()?)
+        .is_some_and(|thread| thread.is_interrupted(clear_interrupted));
+    //booleans are returned as ints, their computational type on the operand stack
+    Ok(JvmValue::from(interrupted as i32))
+}
+
+pub fn interrupt0(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    //interrupting a thread which is not alive has no effect
+    if let Some(thread) = args.context().threads().find(&args.this()?) {
+        thread.interrupt();
+    }
+    Ok(JvmValue::Void {})
+}
+
+pub fn is_alive(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let alive = args.context().threads()
+        .find(&args.this()?)
+        .is_some_and(|thread| thread.is_alive());
+    Ok(JvmValue::from(alive as i32))
+}
+
+pub fn join0(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    if let Some(thread) = args.context().threads().find(&args.this()?) {
+        thread.join(JavaThread::current()?.as_ref())?;
+    }
+    Ok(JvmValue::Void {})
+}
+
+pub fn active_count(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    Ok(JvmValue::Int { val: args.context().threads().live_threads().len() as i32 })
+}
+
+pub fn enumerate(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let array = match args.java_args().first() {
+        Some(JvmValue::ObjRef(array_ref)) => array_ref.dereference()?,
+        other => return Err(JvmException::from(format!("Thread.enumerate expects an array argument, but got {:?}", other))),
+    };
+    let length = array.array_length()?;
+
+    let heap = args.context().heap();
+    let live_threads = args.context().threads().live_threads();
+    let copied = live_threads.iter()
+        .take(length as usize)
+        .enumerate()
+        .map(|(index, thread)| {
+            let thread_object = JvmValue::from(Oop::ObjectOop(thread.thread_object().clone()));
+            array.instance_data().put_field(index, thread_object.clone())?;
+            heap.write_barrier(array.instance_data(), &thread_object);
+            Ok(())
+        })
+        .collect::<Result<Vec<()>, JvmException>>()?;
+    Ok(JvmValue::Int { val: copied.len() as i32 })
+}
@@ -1,4 +1,4 @@
 pub enum ApiEvent {
     ShutDownEvent,
-    CallMainMethodEvent { init_class: String },
+    CallMainMethodEvent { init_class: String, args: Vec<String> },
 }
\ No newline at end of file
@@ -1,3 +1,6 @@
 pub mod stack_frame;
 pub mod thread;
 pub mod api_event;
+pub mod monitor;
+pub mod safepoint;
+pub mod options;
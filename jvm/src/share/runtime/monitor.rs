@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+use crate::share::runtime::safepoint::Safepoint;
+use crate::share::utilities::jvm_exception::JvmException;
+
+#[cfg(test)]
+#[path = "./monitor_test.rs"]
+mod monitor_test;
+
+//how often a waiting thread checks whether it has been interrupted
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    owner: Option<ThreadId>,
+    recursions: usize,
+    //tickets of the threads in the wait set, in the order they started waiting
+    wait_set: VecDeque<u64>,
+    next_ticket: u64,
+}
+
+/// The monitor associated with every object, see 2.11.10 Section of JVM Specification. It is a reentrant
+/// lock owned by a single thread at a time, which counts how many times its owner entered it. It also keeps
+/// the wait set of the object used by Object.wait and Object.notify.
+#[derive(Debug, Default)]
+pub struct ObjectMonitor {
+    state: Mutex<MonitorState>,
+    monitor_released: Condvar,
+    notified: Condvar,
+}
+
+impl ObjectMonitor {
+    pub fn new() -> ObjectMonitor {
+        ObjectMonitor::default()
+    }
+
+    /// Blocks until the current thread owns the monitor, then increments its entry count. While the monitor
+    /// is owned by another thread the current one is stopped at a safepoint.
+    pub fn enter(&self) {
+        if !self.try_enter() {
+            Safepoint::blocking(|| self.enter_contended());
+        }
+    }
+
+    /// Enters the monitor if it's free or already owned by the current thread, without blocking.
+    pub fn try_enter(&self) -> bool {
+        let current_thread = std::thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        if state.owner.is_some_and(|owner| owner != current_thread) {
+            return false;
+        }
+
+        state.owner = Some(current_thread);
+        state.recursions += 1;
+        true
+    }
+
+    fn enter_contended(&self) {
+        let current_thread = std::thread::current().id();
+        let mut state = self.state.lock().unwrap();
+
+        while state.owner.is_some_and(|owner| owner != current_thread) {
+            state = self.monitor_released.wait(state).unwrap();
+        }
+
+        state.owner = Some(current_thread);
+        state.recursions += 1;
+    }
+
+    /// Decrements the entry count of the monitor and releases it when the count reaches zero. Threads which
+    /// don't own the monitor get an IllegalMonitorStateException.
+    pub fn exit(&self) -> Result<(), JvmException> {
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(std::thread::current().id()) {
+            return Err(JvmException::illegal_monitor_state());
+        }
+
+        state.recursions -= 1;
+        if state.recursions == 0 {
+            state.owner = None;
+            self.monitor_released.notify_one();
+        }
+        Ok(())
+    }
+
+    pub fn is_owned_by_current_thread(&self) -> bool {
+        self.state.lock().unwrap().owner == Some(std::thread::current().id())
+    }
+
+    /// Releases the monitor and blocks until the current thread gets notified, the timeout elapses or
+    /// `is_interrupted` reports an interrupt, then reacquires the monitor with its previous entry count.
+    /// A timeout of None waits without time limit. Meanwhile the current thread is stopped at a safepoint.
+    pub fn wait(&self, timeout: Option<Duration>, is_interrupted: &dyn Fn() -> bool) -> Result<(), JvmException> {
+        Safepoint::blocking(|| self.release_and_wait(timeout, is_interrupted))
+    }
+
+    fn release_and_wait(&self, timeout: Option<Duration>, is_interrupted: &dyn Fn() -> bool) -> Result<(), JvmException> {
+        let current_thread = std::thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(current_thread) {
+            return Err(JvmException::illegal_monitor_state());
+        }
+        if is_interrupted() {
+            return Err(JvmException::interrupted());
+        }
+
+        let recursions = state.recursions;
+        state.owner = None;
+        state.recursions = 0;
+        self.monitor_released.notify_one();
+
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.wait_set.push_back(ticket);
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut interrupted = false;
+        //a notified thread is removed from the wait set by the notifier
+        while state.wait_set.contains(&ticket) {
+            let now = Instant::now();
+            if deadline.is_some_and(|deadline| now >= deadline) {
+                state.wait_set.retain(|waiting| *waiting != ticket);
+                break;
+            }
+            if is_interrupted() {
+                state.wait_set.retain(|waiting| *waiting != ticket);
+                interrupted = true;
+                break;
+            }
+
+            let wait_for = deadline.map_or(INTERRUPT_POLL_INTERVAL, |deadline| (deadline - now).min(INTERRUPT_POLL_INTERVAL));
+            state = self.notified.wait_timeout(state, wait_for).unwrap().0;
+        }
+
+        while state.owner.is_some() {
+            state = self.monitor_released.wait(state).unwrap();
+        }
+        state.owner = Some(current_thread);
+        state.recursions = recursions;
+
+        if interrupted {
+            return Err(JvmException::interrupted());
+        }
+        Ok(())
+    }
+
+    /// Wakes up the thread which has been waiting the longest on this monitor.
+    pub fn notify(&self) -> Result<(), JvmException> {
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(std::thread::current().id()) {
+            return Err(JvmException::illegal_monitor_state());
+        }
+
+        if state.wait_set.pop_front().is_some() {
+            self.notified.notify_all();
+        }
+        Ok(())
+    }
+
+    /// Wakes up every thread waiting on this monitor.
+    pub fn notify_all(&self) -> Result<(), JvmException> {
+        let mut state = self.state.lock().unwrap();
+        if state.owner != Some(std::thread::current().id()) {
+            return Err(JvmException::illegal_monitor_state());
+        }
+
+        state.wait_set.clear();
+        self.notified.notify_all();
+        Ok(())
+    }
+}
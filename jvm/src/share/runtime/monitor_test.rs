@@ -0,0 +1,156 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::share::runtime::monitor::ObjectMonitor;
+
+#[test]
+pub fn enter_is_reentrant() {
+    let monitor = ObjectMonitor::new();
+    monitor.enter();
+    monitor.enter();
+
+    assert_eq!(Ok(()), monitor.exit());
+    assert!(monitor.is_owned_by_current_thread());
+    assert_eq!(Ok(()), monitor.exit());
+    assert!(!monitor.is_owned_by_current_thread());
+}
+
+#[test]
+pub fn exit_without_owning_monitor() {
+    let monitor = ObjectMonitor::new();
+
+    let exception = monitor.exit().unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/IllegalMonitorStateException")), exception.exception_class());
+}
+
+#[test]
+pub fn exit_from_other_thread() {
+    let monitor = Arc::new(ObjectMonitor::new());
+    monitor.enter();
+
+    let other_monitor = monitor.clone();
+    let result = thread::spawn(move || other_monitor.exit()).join().unwrap();
+
+    assert!(result.is_err());
+    assert!(monitor.is_owned_by_current_thread());
+}
+
+#[test]
+pub fn enter_blocks_until_released() {
+    let monitor = Arc::new(ObjectMonitor::new());
+    let entered = Arc::new(AtomicBool::new(false));
+    monitor.enter();
+
+    let (other_monitor, other_entered) = (monitor.clone(), entered.clone());
+    let other_thread = thread::spawn(move || {
+        other_monitor.enter();
+        other_entered.store(true, Ordering::SeqCst);
+        other_monitor.exit()
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    assert!(!entered.load(Ordering::SeqCst));
+
+    assert_eq!(Ok(()), monitor.exit());
+    assert_eq!(Ok(()), other_thread.join().unwrap());
+    assert!(entered.load(Ordering::SeqCst));
+}
+
+#[test]
+pub fn wait_without_owning_monitor() {
+    let monitor = ObjectMonitor::new();
+
+    let exception = monitor.wait(None, &|| false).unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/IllegalMonitorStateException")), exception.exception_class());
+    assert!(monitor.notify().is_err());
+    assert!(monitor.notify_all().is_err());
+}
+
+#[test]
+pub fn wait_times_out_and_reacquires_monitor() {
+    let monitor = ObjectMonitor::new();
+    monitor.enter();
+    monitor.enter();
+
+    assert_eq!(Ok(()), monitor.wait(Some(Duration::from_millis(20)), &|| false));
+
+    assert!(monitor.is_owned_by_current_thread());
+    assert_eq!(Ok(()), monitor.exit());
+    assert_eq!(Ok(()), monitor.exit());
+    assert!(!monitor.is_owned_by_current_thread());
+}
+
+#[test]
+pub fn wait_interrupted() {
+    let monitor = ObjectMonitor::new();
+    let interrupted = Arc::new(AtomicBool::new(false));
+    monitor.enter();
+
+    let other_interrupted = interrupted.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        other_interrupted.store(true, Ordering::SeqCst);
+    });
+
+    let exception = monitor.wait(None, &|| interrupted.load(Ordering::SeqCst)).unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/InterruptedException")), exception.exception_class());
+    assert!(monitor.is_owned_by_current_thread());
+}
+
+#[test]
+pub fn notify_wakes_up_waiting_thread() {
+    let monitor = Arc::new(ObjectMonitor::new());
+    let waiting = Arc::new(AtomicBool::new(false));
+    let notified = Arc::new(AtomicBool::new(false));
+
+    let (other_monitor, other_waiting, other_notified) = (monitor.clone(), waiting.clone(), notified.clone());
+    let waiting_thread = thread::spawn(move || {
+        other_monitor.enter();
+        other_waiting.store(true, Ordering::SeqCst);
+        let result = other_monitor.wait(None, &|| false);
+        other_notified.store(true, Ordering::SeqCst);
+        other_monitor.exit().and(result)
+    });
+
+    //the waiting thread releases the monitor only once it is in the wait set
+    while !waiting.load(Ordering::SeqCst) {
+        thread::yield_now();
+    }
+    monitor.enter();
+    assert_eq!(Ok(()), monitor.notify());
+    thread::sleep(Duration::from_millis(20));
+    assert!(!notified.load(Ordering::SeqCst));
+    assert_eq!(Ok(()), monitor.exit());
+
+    assert_eq!(Ok(()), waiting_thread.join().unwrap());
+    assert!(notified.load(Ordering::SeqCst));
+}
+
+#[test]
+pub fn notify_all_wakes_up_every_waiting_thread() {
+    let monitor = Arc::new(ObjectMonitor::new());
+    let waiting = Arc::new(AtomicUsize::new(0));
+
+    let waiting_threads: Vec<_> = (0..3).map(|_| {
+        let (other_monitor, other_waiting) = (monitor.clone(), waiting.clone());
+        thread::spawn(move || {
+            other_monitor.enter();
+            other_waiting.fetch_add(1, Ordering::SeqCst);
+            let result = other_monitor.wait(None, &|| false);
+            other_monitor.exit().and(result)
+        })
+    }).collect();
+
+    while waiting.load(Ordering::SeqCst) < 3 {
+        thread::yield_now();
+    }
+    monitor.enter();
+    assert_eq!(Ok(()), monitor.notify_all());
+    assert_eq!(Ok(()), monitor.exit());
+
+    for waiting_thread in waiting_threads {
+        assert_eq!(Ok(()), waiting_thread.join().unwrap());
+    }
+}
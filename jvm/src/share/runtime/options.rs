@@ -0,0 +1,16 @@
+use std::collections::HashMap;
+
+/// Settings of a JVM given on its start, like the command line options of java.
+#[derive(Debug, Clone, Default)]
+pub struct JvmOptions {
+    /// The size of the native stack of every Java thread in bytes, like -Xss. Deeper recursion needs larger
+    /// stacks, as every Java frame is executed by a native one. None uses the default size of Rust threads.
+    pub thread_stack_size: Option<usize>,
+    /// The maximum number of frames on the Java stack of every thread, invoking a method in a deeper frame throws
+    /// StackOverflowError. None limits the depth only by the size of the native stack.
+    pub max_stack_depth: Option<usize>,
+    /// Prints every loaded class with the class path entry it was loaded from, like -verbose:class.
+    pub verbose_class: bool,
+    /// The properties returned by System.getProperty, like the ones given with -D.
+    pub system_properties: HashMap<String, String>,
+}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(test)]
+#[path = "./safepoint_test.rs"]
+mod safepoint_test;
+
+thread_local! {
+    static ATTACHED_SAFEPOINT: RefCell<Option<Arc<Safepoint>>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Default)]
+struct SafepointState {
+    //threads executing Java code
+    running_threads: usize,
+    //threads polled the safepoint or are blocked, so they don't touch the heap until the operation completes
+    stopped_threads: usize,
+    operation_in_progress: bool,
+}
+
+/// Coordinates stop-the-world operations, like garbage collection, with the threads executing Java code.
+/// Attached threads poll `is_requested` between instructions and stop once an operation is requested. Threads
+/// blocked in monitors, sleeps or joins count as stopped for the duration of the blocking call.
+#[derive(Debug, Default)]
+pub struct Safepoint {
+    requested: AtomicBool,
+    state: Mutex<SafepointState>,
+    state_changed: Condvar,
+}
+
+impl Safepoint {
+    pub fn new() -> Safepoint {
+        Safepoint::default()
+    }
+
+    /// Registers the current thread as one executing Java code, stop-the-world operations wait for it.
+    pub fn attach_current_thread(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        while state.operation_in_progress {
+            state = self.state_changed.wait(state).unwrap();
+        }
+        state.running_threads += 1;
+        ATTACHED_SAFEPOINT.with(|attached| attached.replace(Some(self.clone())));
+    }
+
+    pub fn detach_current_thread(&self) {
+        ATTACHED_SAFEPOINT.with(|attached| attached.replace(None));
+        let mut state = self.state.lock().unwrap();
+        state.running_threads -= 1;
+        self.state_changed.notify_all();
+    }
+
+    fn attached() -> Option<Arc<Safepoint>> {
+        ATTACHED_SAFEPOINT.with(|attached| attached.borrow().clone())
+    }
+
+    fn is_attached(&self) -> bool {
+        Safepoint::attached().is_some_and(|attached| std::ptr::eq(attached.as_ref(), self))
+    }
+
+    /// Returns true if a stop-the-world operation is waiting for the current thread to stop.
+    pub fn is_requested() -> bool {
+        ATTACHED_SAFEPOINT.with(|attached| {
+            attached.borrow().as_ref().is_some_and(|safepoint| safepoint.requested.load(Ordering::Acquire))
+        })
+    }
+
+    /// Stops the current thread until the requested operation completes.
+    pub fn block() {
+        Safepoint::blocking(|| ())
+    }
+
+    /// Runs a call which may block, e.g. waiting for a monitor. Meanwhile the current thread counts as
+    /// stopped, and it doesn't return until the stop-the-world operation in progress completes.
+    pub fn blocking<T>(call: impl FnOnce() -> T) -> T {
+        match Safepoint::attached() {
+            Some(safepoint) => {
+                safepoint.stop_current_thread();
+                let result = call();
+                safepoint.resume_current_thread();
+                result
+            }
+            None => call(),
+        }
+    }
+
+    fn stop_current_thread(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.stopped_threads += 1;
+        self.state_changed.notify_all();
+    }
+
+    fn resume_current_thread(&self) {
+        let mut state = self.state.lock().unwrap();
+        while state.operation_in_progress {
+            state = self.state_changed.wait(state).unwrap();
+        }
+        state.stopped_threads -= 1;
+    }
+
+    /// Stops every attached thread, then runs the operation. The calling thread has to be at a safepoint,
+    /// i.e. the references it holds have to be visible to the operation.
+    pub fn stop_the_world<T>(&self, operation: impl FnOnce() -> T) -> T {
+        let attached = self.is_attached();
+        let mut state = self.state.lock().unwrap();
+        if attached {
+            state.stopped_threads += 1;
+            self.state_changed.notify_all();
+        }
+
+        //only one operation runs at a time, the others wait like any stopped thread
+        while state.operation_in_progress {
+            state = self.state_changed.wait(state).unwrap();
+        }
+        state.operation_in_progress = true;
+        self.requested.store(true, Ordering::Release);
+
+        while state.stopped_threads < state.running_threads {
+            state = self.state_changed.wait(state).unwrap();
+        }
+        drop(state);
+
+        let result = operation();
+
+        let mut state = self.state.lock().unwrap();
+        state.operation_in_progress = false;
+        self.requested.store(false, Ordering::Release);
+        if attached {
+            state.stopped_threads -= 1;
+        }
+        self.state_changed.notify_all();
+        result
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::share::runtime::safepoint::Safepoint;
+
+#[test]
+pub fn blocking_without_attached_safepoint() {
+    assert!(!Safepoint::is_requested());
+    assert_eq!(42, Safepoint::blocking(|| 42));
+}
+
+#[test]
+pub fn stop_the_world_without_attached_threads() {
+    let safepoint = Safepoint::new();
+
+    assert_eq!(42, safepoint.stop_the_world(|| 42));
+}
+
+#[test]
+pub fn stop_the_world_waits_for_running_threads() {
+    let safepoint = Arc::new(Safepoint::new());
+    let (attached_sender, attached) = mpsc::channel();
+    let progress = Arc::new(AtomicUsize::new(0));
+    let finished = Arc::new(AtomicBool::new(false));
+
+    let (other_safepoint, other_progress, other_finished) = (safepoint.clone(), progress.clone(), finished.clone());
+    let other_thread = thread::spawn(move || {
+        other_safepoint.attach_current_thread();
+        attached_sender.send(()).unwrap();
+        while !other_finished.load(Ordering::SeqCst) {
+            if Safepoint::is_requested() {
+                Safepoint::block();
+            }
+            other_progress.fetch_add(1, Ordering::SeqCst);
+        }
+        other_safepoint.detach_current_thread();
+    });
+    attached.recv().unwrap();
+
+    safepoint.stop_the_world(|| {
+        let stopped_at = progress.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(stopped_at, progress.load(Ordering::SeqCst));
+    });
+
+    finished.store(true, Ordering::SeqCst);
+    other_thread.join().unwrap();
+}
+
+#[test]
+pub fn blocked_threads_count_as_stopped() {
+    let safepoint = Arc::new(Safepoint::new());
+    let (blocked_sender, blocked) = mpsc::channel();
+    let (release_sender, release) = mpsc::channel::<()>();
+    let operation_completed = Arc::new(AtomicBool::new(false));
+
+    let (other_safepoint, other_operation_completed) = (safepoint.clone(), operation_completed.clone());
+    let other_thread = thread::spawn(move || {
+        other_safepoint.attach_current_thread();
+        Safepoint::blocking(|| {
+            blocked_sender.send(()).unwrap();
+            release.recv().unwrap();
+        });
+        //a blocked thread resumes only after the operation has completed
+        let resumed_after_operation = other_operation_completed.load(Ordering::SeqCst);
+        other_safepoint.detach_current_thread();
+        resumed_after_operation
+    });
+    blocked.recv().unwrap();
+
+    safepoint.stop_the_world(|| {
+        release_sender.send(()).unwrap();
+        thread::sleep(Duration::from_millis(20));
+        operation_completed.store(true, Ordering::SeqCst);
+    });
+
+    assert!(other_thread.join().unwrap());
+}
+
+#[test]
+pub fn attached_thread_can_stop_the_world() {
+    let safepoint = Arc::new(Safepoint::new());
+    safepoint.attach_current_thread();
+
+    assert!(!Safepoint::is_requested());
+    assert_eq!(42, safepoint.stop_the_world(|| 42));
+
+    safepoint.detach_current_thread();
+}
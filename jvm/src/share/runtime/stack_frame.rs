@@ -6,6 +6,7 @@ use crate::share::classfile::klass::Klass;
 use crate::share::classfile::method::MethodInfo;
 use crate::share::interpreter::local_variables::{JvmLocalVariableStore, LocalVariableStore};
 use crate::share::memory::heap::Heap;
+use crate::share::memory::oop::Oop;
 use crate::share::native::native_methods::NativeMethodArgs;
 use crate::share::utilities::context::GlobalContext;
 use crate::share::utilities::jvm_exception::JvmException;
@@ -27,18 +28,21 @@ pub trait JvmStackFrame {
 
 pub struct StackFrame<'a> {
     previous: Option<&'a StackFrame<'a>>,
-    context: &'a GlobalContext,
+    context: &'a Arc<GlobalContext>,
     current_class: Arc<Klass>,
     current_method: Option<Arc<MethodInfo>>,
+    //the number of frames below this one on the stack of the thread
+    depth: usize,
 }
 
 impl<'a> StackFrame<'a> {
-    pub fn new(context: &'a GlobalContext, current_class: Arc<Klass>) -> StackFrame<'a> {
+    pub fn new(context: &'a Arc<GlobalContext>, current_class: Arc<Klass>) -> StackFrame<'a> {
         StackFrame {
             previous: None,
             context,
             current_class,
             current_method: None,
+            depth: 0,
         }
     }
 }
@@ -60,17 +64,48 @@ impl JvmStackFrame for StackFrame<'_> {
         self.current_class.constant_pool()
     }
 
+    /// Executes the method in a new frame. Synchronized methods hold the monitor of their receiver, or of the
+    /// class mirror for static methods, while they execute, see 2.11.10 Section of JVM Specification.
     fn execute_method(
         &self,
         method: Arc<MethodInfo>,
         args: Vec<JvmValue>,
+    ) -> Result<JvmValue, JvmException> {
+        if !method.is_synchronized() {
+            return self.execute_in_new_frame(method, args);
+        }
+
+        let lock_object = match args.first() {
+            _ if method.is_static() => Oop::ObjectOop(method.get_klass().get_java_mirror()),
+            Some(JvmValue::ObjRef(receiver)) => receiver.dereference()?,
+            other => return Err(JvmException::from(format!("Synchronized method {} has no receiver, but {:?}", method, other))),
+        };
+
+        lock_object.monitor().enter();
+        let result = self.execute_in_new_frame(method, args);
+        //the monitor is released even if the method completes abruptly
+        let exit_result = lock_object.monitor().exit();
+
+        result.and_then(|return_value| exit_result.map(|_| return_value))
+    }
+}
+
+impl StackFrame<'_> {
+    fn execute_in_new_frame(
+        &self,
+        method: Arc<MethodInfo>,
+        args: Vec<JvmValue>,
     ) -> Result<JvmValue, JvmException> {
         log::trace!("Method to execute: {}", method);
+        if self.context.options().max_stack_depth.is_some_and(|max_stack_depth| self.depth >= max_stack_depth) {
+            return Err(JvmException::stack_overflow());
+        }
         let next_frame = StackFrame {
             previous: Some(self),
-            context: self.context.clone(),
+            context: self.context,
             current_class: method.get_klass(),
             current_method: Some(method.clone()),
+            depth: self.depth + 1,
         };
 
         if method.is_native() {
@@ -81,6 +116,7 @@ impl JvmStackFrame for StackFrame<'_> {
             return native_fn(NativeMethodArgs::new(
                 &next_frame.current_class,
                 &next_frame.context,
+                args,
             ));
         }
 
@@ -90,16 +126,26 @@ impl JvmStackFrame for StackFrame<'_> {
                 let mut local_variables: LocalVariableStore =
                     LocalVariableStore::new(code_info.local_variables() as usize);
 
-                for i in 0..args.len() {
-                    local_variables.store(args.get(i).expect("Should not happen.").clone(), i as u8)
+                //longs and doubles take up two local variable slots
+                let mut index = 0;
+                for arg in args {
+                    let category = arg.category();
+                    local_variables.store(arg, index as u16);
+                    index += category;
                 }
 
-                let result = Interpreter::interpret(
+                let result = Interpreter::new(
                     &next_frame,
                     code_info.bytes(),
                     &mut local_variables,
-                );
-                log::trace!("Returning from byte-code method: {}", method);
+                ).with_exception_table(code_info.exception_table()).do_interpret();
+
+                match &result {
+                    Err(exception) if exception.is_java_exception() => {
+                        log::trace!("Unwinding byte-code method: {} due to uncaught exception: {}", method, exception)
+                    }
+                    _ => log::trace!("Returning from byte-code method: {}", method),
+                }
                 return result;
             }
             _ => Err(JvmException::from(format!("No code information present for non-native method: {}", method))),
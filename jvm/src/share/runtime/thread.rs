@@ -1,15 +1,310 @@
-use std::sync::Arc;
+use std::cell::RefCell;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::Receiver;
 use std::thread;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use crate::share::classfile::constant_pool::Qualifier;
+use crate::share::memory::oop::oops::ObjectOopDesc;
+use crate::share::native::native_helper_classes::{java_lang_String, java_lang_Thread, java_lang_Throwable};
 use crate::share::runtime::api_event::ApiEvent;
+use crate::share::runtime::safepoint::Safepoint;
 use crate::share::runtime::stack_frame::{JvmStackFrame, StackFrame};
 use crate::share::utilities::context::GlobalContext;
+use crate::share::utilities::global_symbols::Symbols;
 use crate::share::utilities::jvm_exception::JvmException;
 use crate::share::utilities::jvm_value::JvmValue;
 
+#[cfg(test)]
+#[path = "./thread_test.rs"]
+mod thread_test;
+
+pub const UNCAUGHT_EXCEPTION_EXIT_CODE: i32 = 1;
+
+//how often a joining thread checks whether it has been interrupted
+const JOIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+thread_local! {
+    static CURRENT_THREAD: RefCell<Option<Arc<JavaThread>>> = const { RefCell::new(None) };
+}
+
+/// A java/lang/Thread instance which is backed by an OS thread once it is started.
+pub struct JavaThread {
+    thread_object: ObjectOopDesc,
+    name: String,
+    daemon: bool,
+    interrupted: Mutex<bool>,
+    interrupt_signal: Condvar,
+    alive: Mutex<bool>,
+    terminated: Condvar,
+    //values of the frames which are suspended, or stopped at a safepoint, visible to the garbage collector
+    frame_roots: Mutex<Vec<Vec<JvmValue>>>,
+}
+
+/// Keeps the published values of a frame visible to the garbage collector until it's dropped.
+pub struct PublishedFrameRoots {
+    thread: Option<Arc<JavaThread>>,
+}
+
+impl Drop for PublishedFrameRoots {
+    fn drop(&mut self) {
+        if let Some(thread) = &self.thread {
+            thread.frame_roots.lock().unwrap().pop();
+        }
+    }
+}
+
+impl JavaThread {
+    pub fn new(thread_object: ObjectOopDesc, name: String, daemon: bool) -> JavaThread {
+        JavaThread {
+            thread_object,
+            name,
+            daemon,
+            interrupted: Mutex::new(false),
+            interrupt_signal: Condvar::new(),
+            alive: Mutex::new(true),
+            terminated: Condvar::new(),
+            frame_roots: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the Java thread the calling OS thread is attached to.
+    pub fn current() -> Result<Arc<JavaThread>, JvmException> {
+        CURRENT_THREAD.with(|current| current.borrow().clone())
+            .ok_or(JvmException::from("Current OS thread is not attached to the JVM"))
+    }
+
+    fn attach(context: &Arc<GlobalContext>, thread: Arc<JavaThread>) {
+        CURRENT_THREAD.with(|current| current.replace(Some(thread)));
+        context.safepoint().attach_current_thread();
+    }
+
+    fn detach(context: &Arc<GlobalContext>) {
+        //nothing the thread allocated is referenced by its frames anymore
+        context.heap().forget_recent_allocations();
+        context.safepoint().detach_current_thread();
+        CURRENT_THREAD.with(|current| current.replace(None));
+    }
+
+    /// Publishes the values of the current frame to the thread executing it, so they are roots of the garbage
+    /// collector while the frame calls into other code. Threads not attached to the JVM publish nothing.
+    pub fn publish_frame_roots(frame_roots: impl FnOnce() -> Vec<JvmValue>) -> PublishedFrameRoots {
+        let thread = CURRENT_THREAD.with(|current| current.borrow().clone());
+        if let Some(thread) = &thread {
+            thread.frame_roots.lock().unwrap().push(frame_roots());
+        }
+        PublishedFrameRoots { thread }
+    }
+
+    /// The values published by the frames of this thread.
+    pub fn frame_roots(&self) -> Vec<JvmValue> {
+        self.frame_roots.lock().unwrap().iter().flatten().cloned().collect()
+    }
+
+    /// Spawns the OS thread executing the run() method of the given java/lang/Thread instance.
+    pub fn start(context: &Arc<GlobalContext>, thread_object: ObjectOopDesc) -> Result<(), JvmException> {
+        if java_lang_Thread::is_started(&thread_object)? {
+            return Err(JvmException::illegal_thread_state());
+        }
+        java_lang_Thread::set_started(&thread_object)?;
+
+        let name = java_lang_Thread::get_name(&thread_object)?.unwrap_or_default();
+        let daemon = java_lang_Thread::is_daemon(&thread_object)?;
+        let java_thread = Arc::new(JavaThread::new(thread_object, name.clone(), daemon));
+        context.threads().register(java_thread.clone());
+
+        let thread_context = context.clone();
+        let handle = JavaThread::builder(context)
+            .name(name)
+            .spawn(move || {
+                JavaThread::attach(&thread_context, java_thread.clone());
+                if let Err(exception) = java_thread.run(&thread_context) {
+                    //same as the default uncaught exception handler of HotSpot
+                    log::error!("Exception in thread \"{}\" {}", java_thread.name, exception);
+                }
+                JavaThread::detach(&thread_context);
+                java_thread.terminate();
+                thread_context.threads().deregister(&java_thread);
+            })
+            .map_err(|err| JvmException::from(format!("Failed to spawn OS thread: {}", err)))?;
+
+        context.threads().add_handle(daemon, handle);
+        Ok(())
+    }
+
+    /// Configures the OS thread executing a Java thread, with the stack size given by the options of the JVM.
+    fn builder(context: &GlobalContext) -> thread::Builder {
+        let builder = thread::Builder::new();
+        match context.options().thread_stack_size {
+            Some(stack_size) => builder.stack_size(stack_size),
+            None => builder,
+        }
+    }
+
+    fn run(&self, context: &Arc<GlobalContext>) -> Result<JvmValue, JvmException> {
+        let klass = self.thread_object.klass();
+        let run_method = klass.find_virtual_method(&String::from("run()V"))
+            .ok_or(JvmException::from(format!("No run()V method found on {}", klass.qualified_name())))?;
+
+        StackFrame::new(context, klass)
+            .execute_method(run_method, vec![JvmValue::from(self.thread_object.clone())])
+    }
+
+    pub fn thread_object(&self) -> &ObjectOopDesc {
+        &self.thread_object
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn is_daemon(&self) -> bool {
+        self.daemon
+    }
+
+    pub fn is_alive(&self) -> bool {
+        *self.alive.lock().unwrap()
+    }
+
+    fn terminate(&self) {
+        *self.alive.lock().unwrap() = false;
+        self.terminated.notify_all();
+    }
+
+    pub fn interrupt(&self) {
+        *self.interrupted.lock().unwrap() = true;
+        self.interrupt_signal.notify_all();
+    }
+
+    pub fn is_interrupted(&self, clear_interrupted: bool) -> bool {
+        let mut interrupted = self.interrupted.lock().unwrap();
+        let was_interrupted = *interrupted;
+        if clear_interrupted {
+            *interrupted = false;
+        }
+        was_interrupted
+    }
+
+    /// Clears the interrupt status and raises InterruptedException if this thread has been interrupted.
+    fn check_interrupted(&self) -> Result<(), JvmException> {
+        if self.is_interrupted(true) {
+            return Err(JvmException::interrupted());
+        }
+        Ok(())
+    }
+
+    /// Blocks the current thread for the given duration unless it gets interrupted.
+    pub fn sleep(&self, duration: Duration) -> Result<(), JvmException> {
+        Safepoint::blocking(|| self.sleep_until_interrupted(duration))
+    }
+
+    fn sleep_until_interrupted(&self, duration: Duration) -> Result<(), JvmException> {
+        let deadline = Instant::now() + duration;
+        let mut interrupted = self.interrupted.lock().unwrap();
+        loop {
+            if *interrupted {
+                *interrupted = false;
+                return Err(JvmException::interrupted());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(());
+            }
+            interrupted = self.interrupt_signal.wait_timeout(interrupted, deadline - now).unwrap().0;
+        }
+    }
+
+    /// Blocks the given current thread until this thread terminates, the wait is abandoned if the current
+    /// thread gets interrupted.
+    pub fn join(&self, current_thread: &JavaThread) -> Result<(), JvmException> {
+        Safepoint::blocking(|| self.wait_for_termination(current_thread))
+    }
+
+    fn wait_for_termination(&self, current_thread: &JavaThread) -> Result<(), JvmException> {
+        let mut alive = self.alive.lock().unwrap();
+        while *alive {
+            current_thread.check_interrupted()?;
+            alive = self.terminated.wait_timeout(alive, JOIN_POLL_INTERVAL).unwrap().0;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps track of the live Java threads of the JVM.
+pub struct ThreadRegistry {
+    threads: Mutex<Vec<Arc<JavaThread>>>,
+    handles: Mutex<Vec<(bool, JoinHandle<()>)>>,
+    thread_init_number: AtomicUsize,
+}
+
+impl ThreadRegistry {
+    pub fn new() -> ThreadRegistry {
+        ThreadRegistry {
+            threads: Mutex::new(Vec::new()),
+            handles: Mutex::new(Vec::new()),
+            thread_init_number: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn register(&self, thread: Arc<JavaThread>) {
+        self.threads.lock().unwrap().push(thread);
+    }
+
+    pub fn deregister(&self, thread: &Arc<JavaThread>) {
+        self.threads.lock().unwrap().retain(|registered| !Arc::ptr_eq(registered, thread));
+    }
+
+    fn add_handle(&self, daemon: bool, handle: JoinHandle<()>) {
+        self.handles.lock().unwrap().push((daemon, handle));
+    }
+
+    /// Returns the live thread backed by the given java/lang/Thread instance.
+    pub fn find(&self, thread_object: &ObjectOopDesc) -> Option<Arc<JavaThread>> {
+        self.threads.lock().unwrap()
+            .iter()
+            .find(|thread| thread.thread_object.instance_data().is_same(thread_object.instance_data()))
+            .cloned()
+    }
+
+    pub fn live_threads(&self) -> Vec<Arc<JavaThread>> {
+        self.threads.lock().unwrap().clone()
+    }
+
+    /// Generates the name of threads created without one, same as HotSpot: Thread-0, Thread-1...
+    pub fn next_thread_name(&self) -> String {
+        format!("Thread-{}", self.thread_init_number.fetch_add(1, Ordering::SeqCst))
+    }
+
+    /// Blocks until every non-daemon thread has terminated, including the ones started meanwhile.
+    pub fn wait_for_non_daemon_threads(&self) {
+        loop {
+            let non_daemon_handles: Vec<JoinHandle<()>> = {
+                let mut handles = self.handles.lock().unwrap();
+                let (non_daemon, rest) = handles.drain(..).partition(|(daemon, _)| !*daemon);
+                *handles = rest;
+                non_daemon.into_iter().map(|(_, handle)| handle).collect()
+            };
+
+            if non_daemon_handles.is_empty() {
+                return;
+            }
+            for handle in non_daemon_handles {
+                if handle.join().is_err() {
+                    log::error!("A Java thread panicked");
+                }
+            }
+        }
+    }
+}
+
+impl Default for ThreadRegistry {
+    fn default() -> Self {
+        ThreadRegistry::new()
+    }
+}
+
 pub struct MainJavaThread {
     context: Arc<GlobalContext>,
 }
@@ -22,20 +317,60 @@ impl MainJavaThread {
     pub fn start(&self, api_event_receiver: Receiver<ApiEvent>) -> JoinHandle<Result<i32, JvmException>> {
         log::trace!("Starting MainJavaThread");
         let context = self.context.clone();
-        thread::spawn(move || -> Result<i32, JvmException> {
+        JavaThread::builder(&self.context).name(String::from("main")).spawn(move || -> Result<i32, JvmException> {
             log::trace!("Bootstrapping classes");
             let class_loader = &context.class_loader();
             class_loader.bootstrap()?;
+            MainJavaThread::preallocate_out_of_memory_error(&context)?;
             loop {
                 match api_event_receiver.recv().unwrap() {
                     ApiEvent::ShutDownEvent => return Ok(0),
-                    ApiEvent::CallMainMethodEvent { init_class } => return MainJavaThread::call_main_method(&context, init_class)
+                    ApiEvent::CallMainMethodEvent { init_class, args } => return MainJavaThread::call_main_method(&context, init_class, args)
                 }
             }
-        })
+        }).expect("Failed to spawn the OS thread of the main thread")
+    }
+
+    /// Allocates the OutOfMemoryError thrown once the heap is exhausted, like the preallocated errors of HotSpot.
+    fn preallocate_out_of_memory_error(context: &Arc<GlobalContext>) -> Result<(), JvmException> {
+        let class_loader = context.class_loader();
+        let error_class = class_loader.load_and_init_class(&Symbols::java_lang_OutOfMemoryError)?;
+        let string_class = class_loader.load_and_init_class(&Symbols::java_lang_String)?;
+
+        let heap = context.heap();
+        let out_of_memory_error = heap.allocate_object(error_class)?;
+        let message = JvmException::out_of_memory().message().cloned().unwrap_or_default();
+        let detail_message = java_lang_String::allocate(heap.as_ref(), string_class, message)?;
+        java_lang_Throwable::put_detail_message(heap.as_ref(), out_of_memory_error.clone(), detail_message)?;
+        heap.set_out_of_memory_error(out_of_memory_error);
+        Ok(())
+    }
+
+    fn call_main_method(context: &Arc<GlobalContext>, init_class_name: String, args: Vec<String>) -> Result<i32, JvmException> {
+        //the main thread is attached before the init class is initialized, as its initializer executes Java code
+        let main_thread = MainJavaThread::attach_main_thread(context)?;
+        let result = MainJavaThread::execute_main_method(context, init_class_name, args);
+
+        JavaThread::detach(context);
+        main_thread.terminate();
+        context.threads().deregister(&main_thread);
+        //the JVM exits only once every non-daemon thread has terminated
+        context.threads().wait_for_non_daemon_threads();
+
+        match result {
+            Ok(JvmValue::Int { val }) => Ok(val),
+            Ok(JvmValue::Void { .. }) => Ok(0),
+            Ok(invalid_value) => Err(JvmException::from(format!("Main method didn't return int, but: {:?}", invalid_value))),
+            Err(exception) if exception.is_java_exception() => {
+                //same as HotSpot, an uncaught exception terminates the main thread with exit code 1
+                log::error!("Exception in thread \"main\" {}", exception);
+                Ok(UNCAUGHT_EXCEPTION_EXIT_CODE)
+            }
+            Err(exception) => Err(exception),
+        }
     }
 
-    fn call_main_method(context: &Arc<GlobalContext>, init_class_name:String) -> Result<i32, JvmException> {
+    fn execute_main_method(context: &Arc<GlobalContext>, init_class_name: String, args: Vec<String>) -> Result<JvmValue, JvmException> {
         log::trace!("Trying to look up init class {}", init_class_name);
         let class_loader = context.class_loader();
         let init_class = class_loader.load_and_init_class(&init_class_name)?;
@@ -48,12 +383,39 @@ impl MainJavaThread {
 
         log::trace!("Executing main method of init class: {}", init_class_name);
 
-        let frame = StackFrame::new(&context, init_class.clone());
+        let main_args = MainJavaThread::allocate_main_args(context, args)?;
+        let frame = StackFrame::new(context, init_class.clone());
+        frame.execute_method(main_method, vec![main_args])
+    }
 
-        match frame.execute_method(main_method, Vec::new())? {
-            JvmValue::Int { val } => Ok(val),
-            JvmValue::Void { .. } => Ok(0),
-            invalid_value => Err(JvmException::from(format!("Main method didn't return int, but: {:?}", invalid_value)))
+    /// Builds the String[] passed to the main method. The array is kept alive by the garbage collector as a
+    /// recent allocation of the main thread until the main method publishes its frame.
+    fn allocate_main_args(context: &Arc<GlobalContext>, args: Vec<String>) -> Result<JvmValue, JvmException> {
+        let string_class = context.class_loader().load_and_init_class(&Symbols::java_lang_String)?;
+        let heap = context.heap();
+        let main_args = heap.allocate_array(string_class.clone(), args.len() as i32)?;
+        for (index, arg) in args.into_iter().enumerate() {
+            let arg = JvmValue::from(java_lang_String::allocate(heap.as_ref(), string_class.clone(), arg)?);
+            main_args.instance_data.put_field(index, arg.clone())?;
+            heap.write_barrier(&main_args.instance_data, &arg);
         }
+        Ok(JvmValue::from(main_args))
+    }
+
+    fn attach_main_thread(context: &Arc<GlobalContext>) -> Result<Arc<JavaThread>, JvmException> {
+        let class_loader = context.class_loader();
+        let thread_class = class_loader.load_and_init_class(&Symbols::java_lang_Thread)?;
+        let string_class = class_loader.load_and_init_class(&Symbols::java_lang_String)?;
+
+        let thread_object = context.heap().allocate_object(thread_class)?;
+        let name = String::from("main");
+        let heap = context.heap();
+        java_lang_Thread::put_name(heap.as_ref(), &thread_object, java_lang_String::allocate(heap.as_ref(), string_class, name.clone())?)?;
+        java_lang_Thread::set_started(&thread_object)?;
+
+        let main_thread = Arc::new(JavaThread::new(thread_object, name, false));
+        context.threads().register(main_thread.clone());
+        JavaThread::attach(context, main_thread.clone());
+        Ok(main_thread)
     }
 }
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::share::memory::oop::Oop;
+use crate::share::runtime::thread::{JavaThread, ThreadRegistry};
+use crate::share::utilities::testing::test_object_oop;
+
+fn test_thread(daemon: bool) -> Arc<JavaThread> {
+    match test_object_oop() {
+        Oop::ObjectOop(thread_object) => Arc::new(JavaThread::new(thread_object, String::from("test"), daemon)),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+pub fn is_interrupted_clears_status_only_when_asked() {
+    let thread = test_thread(false);
+    thread.interrupt();
+
+    assert!(thread.is_interrupted(false));
+    assert!(thread.is_interrupted(true));
+    assert!(!thread.is_interrupted(false));
+}
+
+#[test]
+pub fn sleep_elapses() {
+    let thread = test_thread(false);
+    let start = Instant::now();
+
+    assert_eq!(Ok(()), thread.sleep(Duration::from_millis(20)));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
+
+#[test]
+pub fn sleep_interrupted_by_other_thread() {
+    let thread = test_thread(false);
+    let interrupter = thread.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        interrupter.interrupt();
+    });
+
+    let exception = thread.sleep(Duration::from_secs(60)).unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/InterruptedException")), exception.exception_class());
+    assert!(!thread.is_interrupted(false));
+}
+
+#[test]
+pub fn join_interrupted() {
+    let joined = test_thread(false);
+    let current = test_thread(false);
+    current.interrupt();
+
+    let exception = joined.join(&current).unwrap_err();
+    assert_eq!(Some(&String::from("java/lang/InterruptedException")), exception.exception_class());
+}
+
+#[test]
+pub fn registry_finds_registered_threads() {
+    let registry = ThreadRegistry::new();
+    let thread = test_thread(false);
+    registry.register(thread.clone());
+
+    assert!(registry.find(thread.thread_object()).is_some());
+    assert_eq!(1, registry.live_threads().len());
+
+    registry.deregister(&thread);
+    assert!(registry.find(thread.thread_object()).is_none());
+    assert!(registry.live_threads().is_empty());
+}
+
+#[test]
+pub fn registry_generates_thread_names() {
+    let registry = ThreadRegistry::new();
+
+    assert_eq!("Thread-0", registry.next_thread_name());
+    assert_eq!("Thread-1", registry.next_thread_name());
+}
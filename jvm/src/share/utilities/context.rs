@@ -3,20 +3,33 @@ use std::sync::{Arc, RwLock};
 use crate::share::classfile::class_loader::ClassLoader;
 use crate::share::memory::heap::Heap;
 use crate::share::native::native_method_repo::NativeMethodRepo;
+use crate::share::runtime::options::JvmOptions;
+use crate::share::runtime::safepoint::Safepoint;
+use crate::share::runtime::thread::ThreadRegistry;
 
 pub struct GlobalContext {
     heap:  Arc<dyn Heap>,
     class_loader: RwLock<Option<Arc<dyn ClassLoader>>>,
     native_method_repo: RwLock<Option<Arc<NativeMethodRepo>>>,
+    threads: ThreadRegistry,
+    safepoint: Arc<Safepoint>,
+    options: JvmOptions,
 }
 
 impl GlobalContext {
     pub fn new(heap: Arc<dyn Heap>) -> GlobalContext {
+        GlobalContext::with_options(heap, JvmOptions::default())
+    }
+
+    pub fn with_options(heap: Arc<dyn Heap>, options: JvmOptions) -> GlobalContext {
         log::trace!("Initializing GlobalContext");
         GlobalContext {
             heap,
             class_loader: RwLock::new(None),
             native_method_repo: RwLock::new(None),
+            threads: ThreadRegistry::new(),
+            safepoint: Arc::new(Safepoint::new()),
+            options,
         }
     }
 
@@ -49,4 +62,16 @@ impl GlobalContext {
             .expect("native_method_repo should be set before accessing it!")
             .clone()
     }
+
+    pub fn threads(&self) -> &ThreadRegistry {
+        &self.threads
+    }
+
+    pub fn safepoint(&self) -> &Arc<Safepoint> {
+        &self.safepoint
+    }
+
+    pub fn options(&self) -> &JvmOptions {
+        &self.options
+    }
 }
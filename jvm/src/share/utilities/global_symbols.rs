@@ -4,11 +4,51 @@ pub mod Symbols {
         pub static ref java_lang_Object: String = String::from("java/lang/Object");
         pub static ref java_lang_Object_registerNatives: String = String::from("java/lang/Object_registerNatives()V");
         pub static ref java_lang_Object_hashCode: String = String::from("java/lang/Object_hashCode()I");
+        pub static ref java_lang_Object_wait: String = String::from("java/lang/Object_wait(J)V");
+        pub static ref java_lang_Object_notify: String = String::from("java/lang/Object_notify()V");
+        pub static ref java_lang_Object_notifyAll: String = String::from("java/lang/Object_notifyAll()V");
+
+        pub static ref java_lang_Cloneable: String = String::from("java/lang/Cloneable");
+        pub static ref java_io_Serializable: String = String::from("java/io/Serializable");
 
         pub static ref java_lang_Class_registerNatives: String = String::from("java/lang/Class_registerNatives()V");
 
+        pub static ref java_lang_Thread_nextThreadName: String = String::from("java/lang/Thread_nextThreadName()Ljava/lang/String;");
+        pub static ref java_lang_Thread_currentThread: String = String::from("java/lang/Thread_currentThread()Ljava/lang/Thread;");
+        pub static ref java_lang_Thread_yield: String = String::from("java/lang/Thread_yield()V");
+        pub static ref java_lang_Thread_sleep: String = String::from("java/lang/Thread_sleep(J)V");
+        pub static ref java_lang_Thread_start0: String = String::from("java/lang/Thread_start0()V");
+        pub static ref java_lang_Thread_isInterrupted: String = String::from("java/lang/Thread_isInterrupted(Z)Z");
+        pub static ref java_lang_Thread_interrupt0: String = String::from("java/lang/Thread_interrupt0()V");
+        pub static ref java_lang_Thread_isAlive: String = String::from("java/lang/Thread_isAlive()Z");
+        pub static ref java_lang_Thread_join0: String = String::from("java/lang/Thread_join0()V");
+        pub static ref java_lang_Thread_activeCount: String = String::from("java/lang/Thread_activeCount()I");
+        pub static ref java_lang_Thread_enumerate: String = String::from("java/lang/Thread_enumerate([Ljava/lang/Thread;)I");
+        pub static ref java_lang_System_gc: String = String::from("java/lang/System_gc()V");
+        pub static ref java_lang_System_identityHashCode: String = String::from("java/lang/System_identityHashCode(Ljava/lang/Object;)I");
+        pub static ref java_lang_System_getProperty: String = String::from("java/lang/System_getProperty(Ljava/lang/String;)Ljava/lang/String;");
+
         pub static ref java_lang_String: String = String::from("java/lang/String");
         pub static ref java_lang_Class: String = String::from("java/lang/Class");
+        pub static ref java_lang_Thread: String = String::from("java/lang/Thread");
         pub static ref java_lang_Throwable: String = String::from("java/lang/Throwable");
+        pub static ref java_lang_ArithmeticException: String = String::from("java/lang/ArithmeticException");
+        pub static ref java_lang_NullPointerException: String = String::from("java/lang/NullPointerException");
+        pub static ref java_lang_ArrayIndexOutOfBoundsException: String = String::from("java/lang/ArrayIndexOutOfBoundsException");
+        pub static ref java_lang_ArrayStoreException: String = String::from("java/lang/ArrayStoreException");
+        pub static ref java_lang_ClassCastException: String = String::from("java/lang/ClassCastException");
+        pub static ref java_lang_Error: String = String::from("java/lang/Error");
+        pub static ref java_lang_ExceptionInInitializerError: String = String::from("java/lang/ExceptionInInitializerError");
+        pub static ref java_lang_NoClassDefFoundError: String = String::from("java/lang/NoClassDefFoundError");
+        pub static ref java_lang_OutOfMemoryError: String = String::from("java/lang/OutOfMemoryError");
+        pub static ref java_lang_IllegalArgumentException: String = String::from("java/lang/IllegalArgumentException");
+        pub static ref java_lang_IllegalThreadStateException: String = String::from("java/lang/IllegalThreadStateException");
+        pub static ref java_lang_InterruptedException: String = String::from("java/lang/InterruptedException");
+        pub static ref java_lang_IllegalMonitorStateException: String = String::from("java/lang/IllegalMonitorStateException");
+        pub static ref java_lang_NegativeArraySizeException: String = String::from("java/lang/NegativeArraySizeException");
+        pub static ref java_lang_NoSuchFieldError: String = String::from("java/lang/NoSuchFieldError");
+        pub static ref java_lang_AbstractMethodError: String = String::from("java/lang/AbstractMethodError");
+        pub static ref java_lang_IncompatibleClassChangeError: String = String::from("java/lang/IncompatibleClassChangeError");
+        pub static ref java_lang_StackOverflowError: String = String::from("java/lang/StackOverflowError");
     }
 }
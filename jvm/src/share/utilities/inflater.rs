@@ -0,0 +1,224 @@
+use std::io::{Error, ErrorKind};
+
+#[cfg(test)]
+#[path = "./inflater_test.rs"]
+mod inflater_test;
+
+const MAX_CODE_LENGTH: usize = 15;
+const END_OF_BLOCK: u16 = 256;
+
+//the base lengths and distances of the length and distance symbols, and how many extra bits follow them
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097,
+    6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+//the order the code lengths of the code length alphabet are stored in by dynamic blocks
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Decompresses raw DEFLATE data, without a zlib or gzip wrapper, as described by RFC 1951.
+pub fn inflate(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut reader = BitReader { input, position: 0, bit_buffer: 0, bit_count: 0 };
+    let mut output = Vec::new();
+    loop {
+        let last_block = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => inflate_stored_block(&mut reader, &mut output)?,
+            1 => {
+                let (literals, distances) = fixed_codes()?;
+                inflate_compressed_block(&mut reader, &mut output, &literals, &distances)?
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut reader)?;
+                inflate_compressed_block(&mut reader, &mut output, &literals, &distances)?
+            }
+            _ => return Err(invalid_data("Invalid deflate block type")),
+        }
+        if last_block {
+            return Ok(output);
+        }
+    }
+}
+
+/// Reads the input starting with the least significant bit of every byte.
+struct BitReader<'a> {
+    input: &'a [u8],
+    position: usize,
+    bit_buffer: u32,
+    bit_count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn bits(&mut self, count: u32) -> Result<u32, Error> {
+        while self.bit_count < count {
+            let byte = *self.input.get(self.position).ok_or_else(|| invalid_data("Unexpected end of deflate data"))?;
+            self.position += 1;
+            self.bit_buffer |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+
+        let value = self.bit_buffer & ((1u64 << count) - 1) as u32;
+        self.bit_buffer >>= count;
+        self.bit_count -= count;
+        Ok(value)
+    }
+
+    /// Drops the remaining bits of the current byte.
+    fn align_to_byte(&mut self) {
+        self.bit_buffer = 0;
+        self.bit_count = 0;
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        let bytes = self.input
+            .get(self.position..self.position + count)
+            .ok_or_else(|| invalid_data("Unexpected end of deflate data"))?;
+        self.position += count;
+        Ok(bytes)
+    }
+}
+
+/// A canonical Huffman code, described by how many symbols have codes of each length and the symbols ordered
+/// by the lengths of their codes.
+struct Huffman {
+    counts: [u16; MAX_CODE_LENGTH + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Builds the code from the code lengths of the symbols, 0 meaning the symbol isn't used. Incomplete codes
+    /// are allowed, as a block may use a single distance code.
+    fn new(lengths: &[u8]) -> Result<Huffman, Error> {
+        let mut counts = [0u16; MAX_CODE_LENGTH + 1];
+        lengths.iter().for_each(|length| counts[*length as usize] += 1);
+
+        let mut left: i32 = 1;
+        for count in counts.iter().skip(1) {
+            left = (left << 1) - *count as i32;
+            if left < 0 {
+                return Err(invalid_data("Over-subscribed Huffman code"));
+            }
+        }
+
+        let mut symbols: Vec<u16> = (0..lengths.len() as u16).filter(|symbol| lengths[*symbol as usize] != 0).collect();
+        symbols.sort_by_key(|symbol| lengths[*symbol as usize]);
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, Error> {
+        //codes of the same length are consecutive numbers, the first code of each length is derived from the
+        //codes of the previous length
+        let (mut code, mut first, mut index): (i32, i32, i32) = (0, 0, 0);
+        for count in self.counts.iter().skip(1) {
+            code |= reader.bits(1)? as i32;
+            let count = *count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid_data("Invalid Huffman code"))
+    }
+}
+
+fn inflate_stored_block(reader: &mut BitReader, output: &mut Vec<u8>) -> Result<(), Error> {
+    reader.align_to_byte();
+    let length = reader.bits(16)?;
+    let complement = reader.bits(16)?;
+    if length != !complement & 0xFFFF {
+        return Err(invalid_data("Invalid length of stored deflate block"));
+    }
+    output.extend_from_slice(reader.bytes(length as usize)?);
+    Ok(())
+}
+
+fn inflate_compressed_block(reader: &mut BitReader, output: &mut Vec<u8>, literals: &Huffman, distances: &Huffman) -> Result<(), Error> {
+    loop {
+        let symbol = literals.decode(reader)?;
+        if symbol < END_OF_BLOCK {
+            output.push(symbol as u8);
+            continue;
+        }
+        if symbol == END_OF_BLOCK {
+            return Ok(());
+        }
+
+        let length_index = (symbol - END_OF_BLOCK - 1) as usize;
+        if length_index >= LENGTH_BASE.len() {
+            return Err(invalid_data("Invalid length symbol"));
+        }
+        let length = LENGTH_BASE[length_index] as usize + reader.bits(LENGTH_EXTRA_BITS[length_index] as u32)? as usize;
+
+        let distance_index = distances.decode(reader)? as usize;
+        if distance_index >= DISTANCE_BASE.len() {
+            return Err(invalid_data("Invalid distance symbol"));
+        }
+        let distance = DISTANCE_BASE[distance_index] as usize + reader.bits(DISTANCE_EXTRA_BITS[distance_index] as u32)? as usize;
+        if distance > output.len() {
+            return Err(invalid_data("Distance too far back"));
+        }
+
+        //the copied range may overlap the bytes being written, so it's copied byte by byte
+        let start = output.len() - distance;
+        for offset in 0..length {
+            output.push(output[start + offset]);
+        }
+    }
+}
+
+fn fixed_codes() -> Result<(Huffman, Huffman), Error> {
+    let mut lengths = [0u8; 288];
+    lengths[0..144].iter_mut().for_each(|length| *length = 8);
+    lengths[144..256].iter_mut().for_each(|length| *length = 9);
+    lengths[256..280].iter_mut().for_each(|length| *length = 7);
+    lengths[280..288].iter_mut().for_each(|length| *length = 8);
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), Error> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+
+    let mut code_length_lengths = [0u8; 19];
+    for index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+        code_length_lengths[*index] = reader.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_length_lengths)?;
+
+    let mut lengths: Vec<u8> = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (length, repeat) = match code_lengths.decode(reader)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths.last().ok_or_else(|| invalid_data("Repeated code length without a previous one"))?;
+                (previous, 3 + reader.bits(2)?)
+            }
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?),
+        };
+        if lengths.len() + repeat as usize > literal_count + distance_count {
+            return Err(invalid_data("Too many code lengths"));
+        }
+        lengths.resize(lengths.len() + repeat as usize, length);
+    }
+
+    if lengths[END_OF_BLOCK as usize] == 0 {
+        return Err(invalid_data("Missing end of block code"));
+    }
+    Ok((Huffman::new(&lengths[..literal_count])?, Huffman::new(&lengths[literal_count..])?))
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
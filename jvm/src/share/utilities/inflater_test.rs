@@ -0,0 +1,39 @@
+use crate::share::utilities::inflater::inflate;
+
+//the inputs were compressed by zlib with raw deflate streams
+
+#[test]
+pub fn inflates_stored_block() {
+    let compressed = [1, 3, 0, 252, 255, 97, 98, 99];
+
+    assert_eq!(b"abc".to_vec(), inflate(&compressed).unwrap());
+}
+
+#[test]
+pub fn inflates_block_with_fixed_codes() {
+    let compressed = [203, 72, 205, 201, 201, 87, 200, 64, 39, 1];
+
+    assert_eq!(b"hello hello hello hello".to_vec(), inflate(&compressed).unwrap());
+}
+
+#[test]
+pub fn inflates_block_with_dynamic_codes() {
+    let compressed = [
+        43, 201, 72, 85, 40, 44, 205, 76, 206, 86, 72, 42, 202, 47, 207, 83, 72, 203, 175, 80, 200, 42, 205, 45, 40,
+        86, 200, 47, 75, 45, 82, 40, 1, 74, 231, 36, 86, 85, 42, 164, 228, 167, 235, 128, 121, 131, 64, 49, 0,
+    ];
+
+    assert_eq!(b"the quick brown fox jumps over the lazy dog, ".repeat(4), inflate(&compressed).unwrap());
+}
+
+#[test]
+pub fn truncated_input_is_rejected() {
+    let compressed = [203, 72, 205, 201, 201, 87, 200];
+
+    assert!(inflate(&compressed).is_err());
+}
+
+#[test]
+pub fn invalid_block_type_is_rejected() {
+    assert!(inflate(&[0b111]).is_err());
+}
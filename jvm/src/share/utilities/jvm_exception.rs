@@ -1,11 +1,170 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use crate::share::memory::oop::Oop;
+use crate::share::native::native_helper_classes::java_lang_Throwable;
+use crate::share::utilities::global_symbols::Symbols;
+
+#[cfg(test)]
+#[path = "./jvm_exception_test.rs"]
+mod jvm_exception_test;
+
 #[derive(Debug, PartialEq)]
 pub struct JvmException {
     message: Option<String>,
+    exception_class: Option<String>,
+    throwable: Option<Oop>,
+    cause: Option<Oop>,
 }
 
 impl JvmException {
-    fn new() -> JvmException {
-        JvmException { message: None }
+    /// Creates an exception raised by the VM which has to surface in Java code as an instance of the given
+    /// class, e.g. java/lang/ArithmeticException. The instance itself is allocated by the interpreter when the
+    /// exception is dispatched, see `with_throwable`.
+    pub fn java_exception(exception_class: &str, message: Option<String>) -> JvmException {
+        JvmException {
+            message,
+            exception_class: Some(String::from(exception_class)),
+            throwable: None,
+            cause: None,
+        }
+    }
+
+    /// Wraps a java/lang/Throwable instance thrown by Java code, e.g. through ATHROW. Its detail message is
+    /// copied, so that it's reported if the exception isn't caught.
+    pub fn from_throwable(throwable: Oop) -> JvmException {
+        let message = match &throwable {
+            Oop::ObjectOop(throwable_ref) => java_lang_Throwable::detail_message(throwable_ref),
+            _ => None,
+        };
+        JvmException {
+            message,
+            exception_class: Some(throwable.java_klass_or_fail().qualified_name()),
+            throwable: Some(throwable),
+            cause: None,
+        }
+    }
+
+    /// Attaches the heap allocated instance of a VM raised exception.
+    pub fn with_throwable(self, throwable: Oop) -> JvmException {
+        JvmException {
+            throwable: Some(throwable),
+            ..self
+        }
+    }
+
+    /// Attaches the throwable which caused this exception, it's recorded in the instance allocated for it.
+    pub fn with_cause(self, cause: Oop) -> JvmException {
+        JvmException {
+            cause: Some(cause),
+            ..self
+        }
+    }
+
+    pub fn division_by_zero() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_ArithmeticException, Some(String::from("/ by zero")))
+    }
+
+    pub fn null_pointer() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_NullPointerException, None)
+    }
+
+    pub fn array_index_out_of_bounds(index: i32, length: i32) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_ArrayIndexOutOfBoundsException,
+                                     Some(format!("Index {} out of bounds for length {}", index, length)))
+    }
+
+    pub fn class_cast(from_class: &str, to_class: &str) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_ClassCastException,
+                                     Some(format!("class {} cannot be cast to class {}",
+                                                  from_class.replace('/', "."), to_class.replace('/', "."))))
+    }
+
+    pub fn array_store(value_class: &str) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_ArrayStoreException, Some(value_class.replace('/', ".")))
+    }
+
+    pub fn illegal_monitor_state() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_IllegalMonitorStateException,
+                                     Some(String::from("current thread is not owner")))
+    }
+
+    pub fn exception_in_initializer() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_ExceptionInInitializerError, None)
+    }
+
+    pub fn no_class_def_found(class_name: &str) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_NoClassDefFoundError,
+                                     Some(format!("Could not initialize class {}", class_name.replace('/', "."))))
+    }
+
+    pub fn out_of_memory() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_OutOfMemoryError, Some(String::from("Java heap space")))
+    }
+
+    pub fn stack_overflow() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_StackOverflowError, None)
+    }
+
+    pub fn illegal_argument(message: &str) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_IllegalArgumentException, Some(String::from(message)))
+    }
+
+    pub fn illegal_thread_state() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_IllegalThreadStateException, None)
+    }
+
+    pub fn interrupted() -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_InterruptedException, None)
+    }
+
+    pub fn negative_array_size(size: i32) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_NegativeArraySizeException, Some(size.to_string()))
+    }
+
+    pub fn no_such_field(field_name: &str) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_NoSuchFieldError, Some(String::from(field_name)))
+    }
+
+    pub fn abstract_method(method: &str) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_AbstractMethodError, Some(String::from(method)))
+    }
+
+    pub fn incompatible_class_change(message: String) -> JvmException {
+        JvmException::java_exception(&Symbols::java_lang_IncompatibleClassChangeError, Some(message))
+    }
+
+    pub fn message(&self) -> Option<&String> {
+        self.message.as_ref()
+    }
+
+    pub fn exception_class(&self) -> Option<&String> {
+        self.exception_class.as_ref()
+    }
+
+    pub fn throwable(&self) -> Option<&Oop> {
+        self.throwable.as_ref()
+    }
+
+    pub fn cause(&self) -> Option<&Oop> {
+        self.cause.as_ref()
+    }
+
+    /// Returns true if this exception is visible to Java code, as opposed to internal errors of the VM.
+    pub fn is_java_exception(&self) -> bool {
+        self.exception_class.is_some()
+    }
+}
+
+impl fmt::Display for JvmException {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(exception_class) = &self.exception_class {
+            write!(f, "{}", exception_class.replace('/', "."))?;
+            if self.message.is_some() {
+                write!(f, ": ")?;
+            }
+        }
+        write!(f, "{}", self.message.as_deref().unwrap_or(""))
     }
 }
 
@@ -13,12 +172,15 @@ impl From<String> for JvmException {
     fn from(message: String) -> Self {
         JvmException {
             message: Some(message),
+            exception_class: None,
+            throwable: None,
+            cause: None,
         }
     }
 }
 
 impl From<&'static str> for JvmException {
     fn from(str: &'static str) -> Self {
-        JvmException::from(format!("{}", str))
+        JvmException::from(str.to_string())
     }
 }
@@ -0,0 +1,38 @@
+use crate::share::memory::heap::{HeapWord, JvmHeap};
+use crate::share::memory::oop::Oop;
+use crate::share::memory::oop::oops::{ObjectOopDesc, PrimitiveArrayOopDesc};
+use crate::share::native::native_helper_classes::java_lang_Throwable;
+use crate::share::utilities::jvm_exception::JvmException;
+use crate::share::utilities::jvm_value::{JvmValue, PrimitiveType};
+use crate::share::utilities::testing;
+
+fn string(contents: &str) -> ObjectOopDesc {
+    let bytes = contents.bytes().map(|byte| JvmValue::from(byte as i8)).collect();
+    let buffer = PrimitiveArrayOopDesc { inner_type: PrimitiveType::Byte, size: contents.len() as i32, instance_data: HeapWord::test_object(bytes) };
+    ObjectOopDesc::new(testing::java_lang_string_class(), HeapWord::test_object(vec![JvmValue::from(buffer), JvmValue::Int { val: 0 }]))
+}
+
+fn throwable(detail_message: Option<ObjectOopDesc>) -> Oop {
+    let throwable_class = testing::java_lang_throwable_class();
+    let throwable = ObjectOopDesc::new(throwable_class.clone(), HeapWord::test_object(vec![JvmValue::null_obj(); throwable_class.instance_field_count()]));
+    if let Some(detail_message) = detail_message {
+        java_lang_Throwable::put_detail_message(&JvmHeap::new(), throwable.clone(), detail_message).unwrap();
+    }
+    Oop::ObjectOop(throwable)
+}
+
+#[test]
+pub fn thrown_exception_is_reported_with_detail_message() {
+    let exception = JvmException::from_throwable(throwable(Some(string("boom"))));
+
+    assert_eq!(Some(&String::from("boom")), exception.message());
+    assert_eq!("java.lang.Throwable: boom", exception.to_string());
+}
+
+#[test]
+pub fn thrown_exception_without_detail_message() {
+    let exception = JvmException::from_throwable(throwable(None));
+
+    assert_eq!(None, exception.message());
+    assert_eq!("java.lang.Throwable", exception.to_string());
+}
@@ -13,10 +13,19 @@ pub enum ObjectRef {
 impl ObjectRef {
     pub fn dereference(&self) -> Result<Oop, JvmException> {
         match self {
-            ObjectRef::Null => Err(JvmException::from("NPE!!!")),
+            ObjectRef::Null => Err(JvmException::null_pointer()),
             ObjectRef::Ref(oop) => Ok(oop.clone())
         }
     }
+
+    /// Reference equality as used by IF_ACMPEQ and IF_ACMPNE.
+    pub fn is_same_object(&self, other: &ObjectRef) -> bool {
+        match (self, other) {
+            (ObjectRef::Null, ObjectRef::Null) => true,
+            (ObjectRef::Ref(oop), ObjectRef::Ref(other_oop)) => oop.instance_data().is_same(other_oop.instance_data()),
+            _ => false,
+        }
+    }
 }
 
 impl Default for ObjectRef {
@@ -34,7 +43,7 @@ pub enum JvmValue {
     Long { val: i64 },
     Float { val: f32 },
     Double { val: f64 },
-    Char { val: char },
+    Char { val: u16 },
     ObjRef(ObjectRef),
     ReturnAddr { val: usize },
     Void {},
@@ -44,6 +53,39 @@ impl JvmValue {
     pub fn null_obj() -> JvmValue {
         JvmValue::ObjRef(ObjectRef::default())
     }
+
+    /// Computational type category of the value as described in 2.11.1 Section of JVM Specification.
+    /// Category 2 values (long and double) occupy two slots in the local variable array.
+    pub fn category(&self) -> usize {
+        match self {
+            JvmValue::Long { .. } | JvmValue::Double { .. } => 2,
+            _ => 1
+        }
+    }
+
+    /// Converts boolean, byte, short and char values to int, their computational type as described in
+    /// 2.11.1 Section of JVM Specification. Other values are returned as they are.
+    pub fn widen(self) -> JvmValue {
+        match self {
+            JvmValue::Boolean { val } => JvmValue::Int { val: val as i32 },
+            JvmValue::Byte { val } => JvmValue::Int { val: val as i32 },
+            JvmValue::Short { val } => JvmValue::Int { val: val as i32 },
+            JvmValue::Char { val } => JvmValue::Int { val: val as i32 },
+            other => other
+        }
+    }
+
+    /// Truncates an int to the given primitive type, e.g. when it is stored into a byte array. Booleans keep
+    /// only the lowest bit of the int, see BASTORE in 6.5 Section of JVM Specification.
+    pub fn narrow_to(self, primitive_type: &PrimitiveType) -> JvmValue {
+        match (self, primitive_type) {
+            (JvmValue::Int { val }, PrimitiveType::Boolean) => JvmValue::Boolean { val: val & 1 == 1 },
+            (JvmValue::Int { val }, PrimitiveType::Byte) => JvmValue::Byte { val: val as i8 },
+            (JvmValue::Int { val }, PrimitiveType::Short) => JvmValue::Short { val: val as i16 },
+            (JvmValue::Int { val }, PrimitiveType::Char) => JvmValue::Char { val: val as u16 },
+            (other, _) => other
+        }
+    }
 }
 
 impl From<Oop> for JvmValue {
@@ -80,7 +122,7 @@ impl From<PrimitiveType> for JvmValue {
             PrimitiveType::Long => JvmValue::Long { val: 0 },
             PrimitiveType::Float => JvmValue::Float { val: 0.0 },
             PrimitiveType::Double => JvmValue::Double { val: 0.0 },
-            PrimitiveType::Char => JvmValue::Char { val: '\0' },
+            PrimitiveType::Char => JvmValue::Char { val: 0 },
         }
     }
 }
@@ -97,7 +139,19 @@ impl From<i32> for JvmValue {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl From<i64> for JvmValue {
+    fn from(val: i64) -> Self {
+        JvmValue::Long { val }
+    }
+}
+
+impl From<f64> for JvmValue {
+    fn from(val: f64) -> Self {
+        JvmValue::Double { val }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PrimitiveType {
     Boolean,
     Byte,
@@ -109,6 +163,48 @@ pub enum PrimitiveType {
     Char,
 }
 
+impl PrimitiveType {
+    /// The field descriptor of the type, e.g. I for int, see 4.3.2 Section of JVM Specification.
+    pub fn descriptor(&self) -> char {
+        match self {
+            PrimitiveType::Boolean => 'Z',
+            PrimitiveType::Byte => 'B',
+            PrimitiveType::Short => 'S',
+            PrimitiveType::Int => 'I',
+            PrimitiveType::Long => 'J',
+            PrimitiveType::Float => 'F',
+            PrimitiveType::Double => 'D',
+            PrimitiveType::Char => 'C',
+        }
+    }
+
+    /// The number of bytes a value of the type occupies in arrays.
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            PrimitiveType::Boolean | PrimitiveType::Byte => 1,
+            PrimitiveType::Short | PrimitiveType::Char => 2,
+            PrimitiveType::Int | PrimitiveType::Float => 4,
+            PrimitiveType::Long | PrimitiveType::Double => 8,
+        }
+    }
+}
+
+impl PrimitiveType {
+    pub fn from_descriptor(descriptor: &str) -> Option<PrimitiveType> {
+        match descriptor {
+            "Z" => Some(PrimitiveType::Boolean),
+            "B" => Some(PrimitiveType::Byte),
+            "S" => Some(PrimitiveType::Short),
+            "I" => Some(PrimitiveType::Int),
+            "J" => Some(PrimitiveType::Long),
+            "F" => Some(PrimitiveType::Float),
+            "D" => Some(PrimitiveType::Double),
+            "C" => Some(PrimitiveType::Char),
+            _ => None
+        }
+    }
+}
+
 impl From<i32> for PrimitiveType {
     fn from(val: i32) -> Self {
         match val {
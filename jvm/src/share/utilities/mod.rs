@@ -1,7 +1,9 @@
 pub mod context;
 pub mod global_symbols;
+pub mod inflater;
 pub mod jvm_exception;
 pub mod jvm_value;
+pub mod zip;
 
 #[cfg(test)]
 pub mod testing;
@@ -12,13 +12,58 @@ pub fn test_class() -> Arc<Klass> {
     let absolute_path = format!("{}/{}", "/home/barnab/projects/rust-jvm/resources/tests/unit", "UnitTestClass.class");
     log::trace!("Reading absolute file: {}", absolute_path);
 
+    let klass = ClassParser::from(std::fs::read(absolute_path.clone()).unwrap()).parse_class().unwrap();
+    klass.set_super_class(java_lang_object_class());
+    klass
+}
+
+/// A class declaring an instance field of every type, in no particular order.
+pub fn field_layout_class() -> Arc<Klass> {
+    let absolute_path = format!("{}/{}", "/home/barnab/projects/rust-jvm/resources/tests/unit", "FieldLayout.class");
+    log::trace!("Reading absolute file: {}", absolute_path);
+
+    let klass = ClassParser::from(std::fs::read(absolute_path.clone()).unwrap()).parse_class().unwrap();
+    klass.set_super_class(java_lang_object_class());
+    klass
+}
+
+pub fn java_lang_object_class() -> Arc<Klass> {
+    let absolute_path = format!("{}/{}", "/home/barnab/projects/rust-jvm/resources/java/lang", "Object.class");
+    log::trace!("Reading absolute file: {}", absolute_path);
+
     ClassParser::from(std::fs::read(absolute_path.clone()).unwrap()).parse_class().unwrap()
 }
 
+pub fn java_lang_string_class() -> Arc<Klass> {
+    let absolute_path = format!("{}/{}", "/home/barnab/projects/rust-jvm/resources/java/lang", "String.class");
+    log::trace!("Reading absolute file: {}", absolute_path);
+
+    ClassParser::from(std::fs::read(absolute_path.clone()).unwrap()).parse_class().unwrap()
+}
+
+pub fn java_lang_throwable_class() -> Arc<Klass> {
+    let absolute_path = format!("{}/{}", "/home/barnab/projects/rust-jvm/resources/java/lang", "Throwable.class");
+    log::trace!("Reading absolute file: {}", absolute_path);
+
+    let klass = ClassParser::from(std::fs::read(absolute_path.clone()).unwrap()).parse_class().unwrap();
+    klass.set_super_class(java_lang_object_class());
+    klass
+}
+
+thread_local! {
+    static TEST_OBJECT: Oop = ObjectOop(ObjectOopDesc::new(test_class(), HeapWord::test_object(vec![])));
+}
+
+/// The same object on every call of a test, so the references compare equal by identity.
 pub fn test_object_oop() -> Oop {
-    ObjectOop(ObjectOopDesc::new(test_class(), HeapWord::test_object(vec![])))
+    TEST_OBJECT.with(|test_object| test_object.clone())
 }
 
 pub fn test_object_ref() -> JvmValue {
     JvmValue::ObjRef(Ref(test_object_oop()))
 }
+
+/// A new object of the test class, distinct from the one returned by [`test_object_ref`].
+pub fn new_test_object_ref() -> JvmValue {
+    JvmValue::ObjRef(Ref(ObjectOop(ObjectOopDesc::new(test_class(), HeapWord::test_object(vec![])))))
+}
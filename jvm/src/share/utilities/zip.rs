@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::share::utilities::inflater::inflate;
+
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0201_4b50;
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+const CENTRAL_DIRECTORY_HEADER_SIZE: usize = 46;
+const LOCAL_HEADER_SIZE: usize = 30;
+const MAX_COMMENT_SIZE: usize = 0xFFFF;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+const ENCRYPTED_FLAG: u16 = 1;
+
+/// The location of an entry, as recorded by the central directory.
+#[derive(Debug)]
+struct ZipEntry {
+    method: u16,
+    flags: u16,
+    crc: u32,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// A ZIP archive, like a jar, whose entries are stored or compressed with DEFLATE. The archive is read into
+/// memory once, then its entries are looked up by their names in the central directory. ZIP64 archives aren't
+/// supported.
+#[derive(Debug)]
+pub struct ZipArchive {
+    path: PathBuf,
+    data: Vec<u8>,
+    entries: HashMap<String, ZipEntry>,
+}
+
+impl ZipArchive {
+    pub fn open(path: &Path) -> Result<ZipArchive, Error> {
+        let data = std::fs::read(path)?;
+        let entries = ZipArchive::read_central_directory(&data)
+            .map_err(|err| Error::new(err.kind(), format!("Invalid ZIP archive {}: {}", path.display(), err)))?;
+        Ok(ZipArchive { path: path.to_path_buf(), data, entries })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the uncompressed contents of the entry, or returns None if the archive has no such entry.
+    pub fn read(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        match self.entries.get(name) {
+            None => Ok(None),
+            Some(entry) => self.read_entry(entry)
+                .map(Some)
+                .map_err(|err| Error::new(err.kind(), format!("Failed to read {} from {}: {}", name, self.path.display(), err))),
+        }
+    }
+
+    fn read_entry(&self, entry: &ZipEntry) -> Result<Vec<u8>, Error> {
+        if entry.flags & ENCRYPTED_FLAG != 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "Encrypted entries are not supported"));
+        }
+
+        let local_header = slice(&self.data, entry.local_header_offset, LOCAL_HEADER_SIZE)?;
+        if LittleEndian::read_u32(local_header) != LOCAL_HEADER_SIGNATURE {
+            return Err(invalid_data("Invalid local header signature"));
+        }
+        //the name and extra field of the local header may differ from the ones in the central directory
+        let data_offset = entry.local_header_offset + LOCAL_HEADER_SIZE
+            + LittleEndian::read_u16(&local_header[26..]) as usize
+            + LittleEndian::read_u16(&local_header[28..]) as usize;
+        let compressed = slice(&self.data, data_offset, entry.compressed_size)?;
+
+        let contents = match entry.method {
+            STORED => compressed.to_vec(),
+            DEFLATED => inflate(compressed)?,
+            method => return Err(Error::new(ErrorKind::InvalidData, format!("Unsupported compression method {}", method))),
+        };
+        if contents.len() != entry.uncompressed_size || crc32(&contents) != entry.crc {
+            return Err(invalid_data("Corrupted entry"));
+        }
+        Ok(contents)
+    }
+
+    fn read_central_directory(data: &[u8]) -> Result<HashMap<String, ZipEntry>, Error> {
+        let end = ZipArchive::find_end_of_central_directory(data)?;
+        let entry_count = LittleEndian::read_u16(&data[end + 10..]) as usize;
+        let mut offset = LittleEndian::read_u32(&data[end + 16..]) as usize;
+        if entry_count == 0xFFFF || offset == 0xFFFF_FFFF {
+            return Err(invalid_data("ZIP64 archives are not supported"));
+        }
+
+        let mut entries = HashMap::with_capacity(entry_count);
+        for _ in 0..entry_count {
+            let header = slice(data, offset, CENTRAL_DIRECTORY_HEADER_SIZE)?;
+            if LittleEndian::read_u32(header) != CENTRAL_DIRECTORY_SIGNATURE {
+                return Err(invalid_data("Invalid central directory signature"));
+            }
+            let name_length = LittleEndian::read_u16(&header[28..]) as usize;
+            let extra_length = LittleEndian::read_u16(&header[30..]) as usize;
+            let comment_length = LittleEndian::read_u16(&header[32..]) as usize;
+            let name = slice(data, offset + CENTRAL_DIRECTORY_HEADER_SIZE, name_length)?;
+
+            entries.insert(String::from_utf8_lossy(name).into_owned(), ZipEntry {
+                flags: LittleEndian::read_u16(&header[8..]),
+                method: LittleEndian::read_u16(&header[10..]),
+                crc: LittleEndian::read_u32(&header[16..]),
+                compressed_size: LittleEndian::read_u32(&header[20..]) as usize,
+                uncompressed_size: LittleEndian::read_u32(&header[24..]) as usize,
+                local_header_offset: LittleEndian::read_u32(&header[42..]) as usize,
+            });
+            offset += CENTRAL_DIRECTORY_HEADER_SIZE + name_length + extra_length + comment_length;
+        }
+        Ok(entries)
+    }
+
+    /// The end of central directory record is at the end of the archive, followed only by the archive comment,
+    /// so it's searched for backwards.
+    fn find_end_of_central_directory(data: &[u8]) -> Result<usize, Error> {
+        if data.len() < END_OF_CENTRAL_DIRECTORY_SIZE {
+            return Err(invalid_data("Missing end of central directory"));
+        }
+        let last = data.len() - END_OF_CENTRAL_DIRECTORY_SIZE;
+        let first = last.saturating_sub(MAX_COMMENT_SIZE);
+        (first..=last)
+            .rev()
+            .find(|offset| LittleEndian::read_u32(&data[*offset..]) == END_OF_CENTRAL_DIRECTORY_SIGNATURE)
+            .ok_or_else(|| invalid_data("Missing end of central directory"))
+    }
+}
+
+fn slice(data: &[u8], offset: usize, length: usize) -> Result<&[u8], Error> {
+    data.get(offset..offset + length).ok_or_else(|| invalid_data("Unexpected end of archive"))
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// The CRC-32 checksum of ZIP archives, with the reversed polynomial 0xEDB88320.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (!(crc & 1)).wrapping_add(1));
+        }
+    }
+    !crc
+}
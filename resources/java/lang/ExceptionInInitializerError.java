@@ -0,0 +1,23 @@
+package java.lang;
+
+/**
+ * A reduced java.lang.ExceptionInInitializerError without serialization support, so that initializing it doesn't
+ * depend on the rest of the class library. Compiled with: javac -source 8 -target 8 -d . java/lang/ExceptionInInitializerError.java
+ */
+public class ExceptionInInitializerError extends LinkageError {
+
+    public ExceptionInInitializerError() {
+    }
+
+    public ExceptionInInitializerError(Throwable thrown) {
+        super(null, thrown);
+    }
+
+    public ExceptionInInitializerError(String s) {
+        super(s, null);
+    }
+
+    public Throwable getException() {
+        return getCause();
+    }
+}
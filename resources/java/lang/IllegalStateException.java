@@ -0,0 +1,25 @@
+package java.lang;
+
+/**
+ * Same as the JDK's java.lang.IllegalStateException, which is thrown by Throwable.initCause.
+ * Compiled with: javac -source 8 -target 8 -d . java/lang/IllegalStateException.java
+ */
+public class IllegalStateException extends RuntimeException {
+    static final long serialVersionUID = -1848914673093119416L;
+
+    public IllegalStateException() {
+        super();
+    }
+
+    public IllegalStateException(String s) {
+        super(s);
+    }
+
+    public IllegalStateException(String message, Throwable cause) {
+        super(message, cause);
+    }
+
+    public IllegalStateException(Throwable cause) {
+        super(cause);
+    }
+}
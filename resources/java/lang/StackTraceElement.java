@@ -0,0 +1,45 @@
+package java.lang;
+
+/**
+ * A reduced java.lang.StackTraceElement holding the location of a frame, without the module information and
+ * the formatting of the JDK's. Compiled with: javac -source 8 -target 8 -d . java/lang/StackTraceElement.java
+ */
+public final class StackTraceElement implements java.io.Serializable {
+    private String declaringClass;
+    private String methodName;
+    private String fileName;
+    private int lineNumber;
+
+    public StackTraceElement(String declaringClass, String methodName, String fileName, int lineNumber) {
+        if (declaringClass == null) {
+            throw new NullPointerException("Declaring class is null");
+        }
+        if (methodName == null) {
+            throw new NullPointerException("Method name is null");
+        }
+        this.declaringClass = declaringClass;
+        this.methodName = methodName;
+        this.fileName = fileName;
+        this.lineNumber = lineNumber;
+    }
+
+    public String getFileName() {
+        return fileName;
+    }
+
+    public int getLineNumber() {
+        return lineNumber;
+    }
+
+    public String getClassName() {
+        return declaringClass;
+    }
+
+    public String getMethodName() {
+        return methodName;
+    }
+
+    public boolean isNativeMethod() {
+        return lineNumber == -2;
+    }
+}
@@ -0,0 +1,16 @@
+package java.lang;
+
+/**
+ * A reduced java.lang.System, providing only the services implemented by the VM.
+ * Compiled with: javac -source 8 -target 8 -d . java/lang/System.java
+ */
+public final class System {
+    private System() {
+    }
+
+    public static native void gc();
+
+    public static native int identityHashCode(Object x);
+
+    public static native String getProperty(String key);
+}
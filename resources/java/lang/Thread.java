@@ -0,0 +1,90 @@
+package java.lang;
+
+/**
+ * A reduced java.lang.Thread, the lifecycle of threads is implemented by the natives of the VM, which back every
+ * started thread with an OS thread. Compiled with: javac -source 8 -target 8 -d . java/lang/Thread.java
+ */
+public class Thread implements Runnable {
+    private String name;
+    private Runnable target;
+    private boolean daemon;
+    private boolean started;
+
+    public Thread() {
+        this(null, nextThreadName());
+    }
+
+    public Thread(Runnable target) {
+        this(target, nextThreadName());
+    }
+
+    public Thread(String name) {
+        this(null, name);
+    }
+
+    public Thread(Runnable target, String name) {
+        this.target = target;
+        this.name = name;
+    }
+
+    private static native String nextThreadName();
+
+    public static native Thread currentThread();
+
+    public static native void yield();
+
+    public static native void sleep(long millis) throws InterruptedException;
+
+    public synchronized void start() {
+        start0();
+    }
+
+    private native void start0();
+
+    @Override
+    public void run() {
+        if (target != null) {
+            target.run();
+        }
+    }
+
+    public void interrupt() {
+        interrupt0();
+    }
+
+    public static boolean interrupted() {
+        return currentThread().isInterrupted(true);
+    }
+
+    public boolean isInterrupted() {
+        return isInterrupted(false);
+    }
+
+    private native boolean isInterrupted(boolean clearInterrupted);
+
+    private native void interrupt0();
+
+    public final native boolean isAlive();
+
+    public final void join() throws InterruptedException {
+        join0();
+    }
+
+    private native void join0() throws InterruptedException;
+
+    public final void setDaemon(boolean on) {
+        daemon = on;
+    }
+
+    public final boolean isDaemon() {
+        return daemon;
+    }
+
+    public final String getName() {
+        return name;
+    }
+
+    public static native int activeCount();
+
+    public static native int enumerate(Thread[] threads);
+}
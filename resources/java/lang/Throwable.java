@@ -0,0 +1,145 @@
+package java.lang;
+
+/**
+ * A reduced java.lang.Throwable. The static initializer of the JDK's Throwable depends on java.util.ArrayList,
+ * java.util.Collections and Class.desiredAssertionStatus, none of which the VM can load and initialize yet, so
+ * every exception would fail with an ExceptionInInitializerError once classes are initialized before their first
+ * use. This class keeps the fields of the JDK's Throwable in the same order, and its stack trace and suppressed
+ * exceptions API, storing the suppressed exceptions in an array instead of a List. The VM doesn't record stack
+ * traces, so they are empty unless set with setStackTrace.
+ * Compiled with: javac -source 8 -target 8 -d . java/lang/Throwable.java
+ */
+public class Throwable implements java.io.Serializable {
+    private static final StackTraceElement[] UNASSIGNED_STACK = new StackTraceElement[0];
+    private static final Throwable[] EMPTY_THROWABLE_ARRAY = new Throwable[0];
+
+    private transient Object backtrace;
+    private String detailMessage;
+    private Throwable cause = this;
+    private StackTraceElement[] stackTrace = UNASSIGNED_STACK;
+    private Throwable[] suppressedExceptions = EMPTY_THROWABLE_ARRAY;
+
+    public Throwable() {
+        fillInStackTrace();
+    }
+
+    public Throwable(String message) {
+        fillInStackTrace();
+        this.detailMessage = message;
+    }
+
+    public Throwable(String message, Throwable cause) {
+        fillInStackTrace();
+        this.detailMessage = message;
+        this.cause = cause;
+    }
+
+    public Throwable(Throwable cause) {
+        fillInStackTrace();
+        this.detailMessage = (cause == null ? null : cause.detailMessage);
+        this.cause = cause;
+    }
+
+    protected Throwable(String message, Throwable cause, boolean enableSuppression, boolean writableStackTrace) {
+        if (writableStackTrace) {
+            fillInStackTrace();
+        } else {
+            stackTrace = null;
+        }
+        this.detailMessage = message;
+        this.cause = cause;
+        if (!enableSuppression) {
+            suppressedExceptions = null;
+        }
+    }
+
+    public String getMessage() {
+        return detailMessage;
+    }
+
+    public String getLocalizedMessage() {
+        return getMessage();
+    }
+
+    public synchronized Throwable getCause() {
+        return (cause == this ? null : cause);
+    }
+
+    public synchronized Throwable initCause(Throwable cause) {
+        if (this.cause != this) {
+            throw new IllegalStateException("Can't overwrite cause", this);
+        }
+        if (cause == this) {
+            throw new IllegalArgumentException("Self-causation not permitted", this);
+        }
+        this.cause = cause;
+        return this;
+    }
+
+    public synchronized Throwable fillInStackTrace() {
+        if (stackTrace != null) {
+            stackTrace = UNASSIGNED_STACK;
+        }
+        return this;
+    }
+
+    public StackTraceElement[] getStackTrace() {
+        StackTraceElement[] ourStackTrace = getOurStackTrace();
+        StackTraceElement[] copy = new StackTraceElement[ourStackTrace.length];
+        for (int i = 0; i < copy.length; i++) {
+            copy[i] = ourStackTrace[i];
+        }
+        return copy;
+    }
+
+    private synchronized StackTraceElement[] getOurStackTrace() {
+        return stackTrace == null ? UNASSIGNED_STACK : stackTrace;
+    }
+
+    public void setStackTrace(StackTraceElement[] stackTrace) {
+        StackTraceElement[] defensiveCopy = new StackTraceElement[stackTrace.length];
+        for (int i = 0; i < defensiveCopy.length; i++) {
+            if (stackTrace[i] == null) {
+                throw new NullPointerException("Null stack trace element");
+            }
+            defensiveCopy[i] = stackTrace[i];
+        }
+
+        synchronized (this) {
+            if (this.stackTrace == null) {
+                return;
+            }
+            this.stackTrace = defensiveCopy;
+        }
+    }
+
+    public final synchronized void addSuppressed(Throwable exception) {
+        if (exception == this) {
+            throw new IllegalArgumentException("Self-suppression not permitted", exception);
+        }
+        if (exception == null) {
+            throw new NullPointerException("Cannot suppress a null exception.");
+        }
+        if (suppressedExceptions == null) {
+            return;
+        }
+
+        Throwable[] suppressed = new Throwable[suppressedExceptions.length + 1];
+        for (int i = 0; i < suppressedExceptions.length; i++) {
+            suppressed[i] = suppressedExceptions[i];
+        }
+        suppressed[suppressedExceptions.length] = exception;
+        suppressedExceptions = suppressed;
+    }
+
+    public final synchronized Throwable[] getSuppressed() {
+        if (suppressedExceptions == null || suppressedExceptions.length == 0) {
+            return EMPTY_THROWABLE_ARRAY;
+        }
+        Throwable[] suppressed = new Throwable[suppressedExceptions.length];
+        for (int i = 0; i < suppressed.length; i++) {
+            suppressed[i] = suppressedExceptions[i];
+        }
+        return suppressed;
+    }
+}
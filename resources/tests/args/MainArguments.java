@@ -0,0 +1,28 @@
+package tests.args;
+
+/**
+ * Expects the arguments "tests.argument" and "second" if the system property tests.argument is set, and no
+ * arguments otherwise.
+ */
+public class MainArguments {
+
+    public static void main(String[] args) {
+        assertTrue(args != null);
+        if (System.getProperty("tests.argument") == null) {
+            assertTrue(args.length == 0);
+            return;
+        }
+
+        assertTrue(args.length == 2);
+        //the contents of the first argument are checked by looking up the property named by it
+        assertTrue(System.getProperty(args[0]) != null);
+        assertTrue(args[1] != null);
+        assertTrue(args[0] != args[1]);
+    }
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
@@ -0,0 +1,81 @@
+package tests.arrays;
+
+public class PrimitiveArrays {
+    public static void main(String... args) {
+        assertEquals(45, sumOfSquares(new int[10]));
+        assertEquals(-128, narrowedByte(new byte[1]));
+        assertEquals(65535, narrowedChar(new char[1]));
+        assertEquals(-1, narrowedShort(new short[1]));
+        assertTrue(booleans(new boolean[2]));
+        assertTrue(longsAndDoubles(new long[2], new double[2]));
+        assertEquals(12, matrix(new int[3][4]));
+        assertEquals(2, partialMatrix(new int[2][]));
+    }
+
+    private static int sumOfSquares(int[] values) {
+        for (int i = 0; i < values.length; i++) {
+            values[i] = i;
+        }
+        int sum = 0;
+        for (int i = 0; i < values.length; i++) {
+            sum += values[i];
+        }
+        return sum;
+    }
+
+    private static int narrowedByte(byte[] bytes) {
+        bytes[0] = (byte) 128;
+        return bytes[0];
+    }
+
+    private static int narrowedChar(char[] chars) {
+        chars[0] = (char) -1;
+        return chars[0];
+    }
+
+    private static int narrowedShort(short[] shorts) {
+        shorts[0] = (short) 65535;
+        return shorts[0];
+    }
+
+    private static boolean booleans(boolean[] flags) {
+        flags[1] = true;
+        return !flags[0] && flags[1];
+    }
+
+    private static boolean longsAndDoubles(long[] longs, double[] doubles) {
+        longs[1] = 1L << 40;
+        doubles[1] = 0.5;
+        return longs[1] == 1099511627776L && doubles[1] * 2 == 1.0 && longs[0] == 0L;
+    }
+
+    private static int matrix(int[][] matrix) {
+        int count = 0;
+        for (int i = 0; i < matrix.length; i++) {
+            int[] row = matrix[i];
+            for (int j = 0; j < row.length; j++) {
+                row[j] = 1;
+                count += matrix[i][j];
+            }
+        }
+        return count;
+    }
+
+    private static int partialMatrix(int[][] matrix) {
+        if (matrix[1] != null) {
+            return -1;
+        }
+        matrix[1] = new int[2];
+        return matrix[1].length;
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
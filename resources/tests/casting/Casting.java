@@ -0,0 +1,82 @@
+package tests.casting;
+
+public class Casting {
+    public static void main(String... args) {
+        classCasts(new Dog());
+        interfaceCasts(new Dog());
+        instanceOfChecks(new Animal());
+        arrayChecks(new Dog[1]);
+        nullChecks(null);
+    }
+
+    private static void classCasts(Object object) {
+        Animal animal = (Animal) object;
+        assertEquals(4, animal.legs());
+        assertEquals(4, ((Dog) animal).legs());
+    }
+
+    private static void interfaceCasts(Object object) {
+        Walking walking = (Walking) object;
+        assertEquals(4, walking.legs());
+        assertTrue(object instanceof Moving);
+    }
+
+    private static void instanceOfChecks(Object object) {
+        assertTrue(object instanceof Animal);
+        assertTrue(object instanceof Object);
+        assertFalse(object instanceof Dog);
+        assertFalse(object instanceof Walking);
+    }
+
+    private static void arrayChecks(Object array) {
+        assertTrue(array instanceof Dog[]);
+        assertTrue(array instanceof Animal[]);
+        assertTrue(array instanceof Walking[]);
+        assertTrue(array instanceof Object[]);
+        assertTrue(array instanceof Cloneable);
+        assertFalse(array instanceof Animal);
+        assertFalse(array instanceof Cat[]);
+    }
+
+    private static void nullChecks(Object object) {
+        assertFalse(object instanceof Object);
+        Animal animal = (Animal) object;
+        assertFalse(animal instanceof Animal);
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertFalse(boolean condition) {
+        assertTrue(!condition);
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+
+    interface Moving {
+    }
+
+    interface Walking extends Moving {
+        int legs();
+    }
+
+    static class Animal {
+        public int legs() {
+            return 2;
+        }
+    }
+
+    static class Dog extends Animal implements Walking {
+        public int legs() {
+            return 4;
+        }
+    }
+
+    static class Cat extends Animal {
+    }
+}
@@ -0,0 +1,176 @@
+package tests.classinit;
+
+public class ClassInitialization {
+
+    public static void main(String... args) throws InterruptedException {
+        //superclasses are initialized before their subclasses
+        assertEquals(7, Child.value);
+        assertEquals(12, Recorder.sequence);
+
+        //only superinterfaces declaring default methods are initialized together with the class
+        Recorder.sequence = 0;
+        new Implementation();
+        assertEquals(34, Recorder.sequence);
+        new PlainImplementation();
+        assertEquals(34, Recorder.sequence);
+
+        //the initializer can refer to its own class while it's being initialized
+        assertEquals(6, Recursive.first);
+
+        boolean wrapped = false;
+        try {
+            Failing.touch();
+        } catch (ExceptionInInitializerError e) {
+            wrapped = true;
+        }
+        assertTrue(wrapped);
+
+        boolean erroneous = false;
+        try {
+            Failing.touch();
+        } catch (NoClassDefFoundError e) {
+            erroneous = true;
+        }
+        assertTrue(erroneous);
+
+        boolean notWrapped = false;
+        try {
+            ErrorThrowing.touch();
+        } catch (ExceptionInInitializerError e) {
+            notWrapped = false;
+        } catch (LinkageError e) {
+            notWrapped = true;
+        }
+        assertTrue(notWrapped);
+
+        //a thread accessing a class being initialized by another thread waits for the initialization
+        Reader first = new Reader();
+        Reader second = new Reader();
+        first.start();
+        second.start();
+        first.join();
+        second.join();
+        assertEquals(42, first.observed);
+        assertEquals(42, second.observed);
+        assertEquals(1, Slow.initializations);
+    }
+
+    static class Recorder {
+        static int sequence;
+
+        static int record(int id) {
+            sequence = sequence * 10 + id;
+            return id;
+        }
+    }
+
+    static class Parent {
+        static {
+            Recorder.record(1);
+        }
+    }
+
+    static class Child extends Parent {
+        static int value;
+
+        static {
+            Recorder.record(2);
+            value = 7;
+        }
+    }
+
+    interface WithDefault {
+        int ID = Recorder.record(3);
+
+        default int id() {
+            return ID;
+        }
+    }
+
+    interface WithoutDefault {
+        int ID = Recorder.record(5);
+
+        int id();
+    }
+
+    static class Implementation implements WithDefault {
+        static {
+            Recorder.record(4);
+        }
+    }
+
+    static class PlainImplementation implements WithoutDefault {
+        public int id() {
+            return 0;
+        }
+    }
+
+    static class Recursive {
+        static int second = 5;
+        static int first = next();
+
+        static int next() {
+            return Recursive.second + 1;
+        }
+    }
+
+    static class Failing {
+        static int value = 1 / zero();
+
+        static int zero() {
+            return 0;
+        }
+
+        static void touch() {
+        }
+    }
+
+    static class ErrorThrowing {
+        static {
+            if (alwaysTrue()) {
+                throw new LinkageError();
+            }
+        }
+
+        static boolean alwaysTrue() {
+            return true;
+        }
+
+        static void touch() {
+        }
+    }
+
+    static class Slow {
+        static int initializations;
+        static int value;
+
+        static {
+            try {
+                Thread.sleep(50);
+            } catch (InterruptedException e) {
+                ((Object) null).hashCode();
+            }
+            initializations++;
+            value = 42;
+        }
+    }
+
+    static class Reader extends Thread {
+        int observed;
+
+        @Override
+        public void run() {
+            observed = Slow.value;
+        }
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
@@ -0,0 +1,24 @@
+package tests.classpath;
+
+import tests.classpath.lib.Library;
+
+/**
+ * Packaged into packaged.jar and executable.jar, while Library is stored uncompressed in library.zip, none of
+ * them is in the resources directory. executable.jar refers to library.zip in the Class-Path of its manifest.
+ */
+public class Packaged {
+
+    public static void main(String... args) {
+        assertTrue(Library.square(7) == 49);
+
+        Library library = new Library(3);
+        library.add(4);
+        assertTrue(library.total() == 7);
+    }
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
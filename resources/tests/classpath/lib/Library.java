@@ -0,0 +1,21 @@
+package tests.classpath.lib;
+
+public class Library {
+    private int total;
+
+    public Library(int initial) {
+        total = initial;
+    }
+
+    public static int square(int value) {
+        return value * value;
+    }
+
+    public void add(int value) {
+        total += value;
+    }
+
+    public int total() {
+        return total;
+    }
+}
@@ -0,0 +1,125 @@
+package tests.controlflow;
+
+public class ControlFlow {
+    public static void main(String... args) {
+        assertEquals(55, sumTo(10));
+        assertEquals(120, factorial(5));
+        assertEquals(3, nestedLoops(3));
+
+        assertEquals(10, denseSwitch(0));
+        assertEquals(30, denseSwitch(2));
+        assertEquals(-1, denseSwitch(7));
+        assertEquals(-1, denseSwitch(-3));
+
+        assertEquals(1, sparseSwitch(-1000));
+        assertEquals(2, sparseSwitch(42));
+        assertEquals(3, sparseSwitch(100000));
+        assertEquals(0, sparseSwitch(5));
+
+        assertEquals(6, fallThrough(1));
+        assertEquals(5, fallThrough(2));
+
+        Object object = new Object();
+        assertTrue(same(object, object));
+        assertFalse(same(object, new Object()));
+        assertTrue(isNull(null));
+        assertFalse(isNull(object));
+    }
+
+    private static int sumTo(int n) {
+        int sum = 0;
+        for (int i = 1; i <= n; i++) {
+            sum += i;
+        }
+        return sum;
+    }
+
+    private static int factorial(int n) {
+        int result = 1;
+        while (n > 1) {
+            result *= n;
+            n--;
+        }
+        return result;
+    }
+
+    private static int nestedLoops(int n) {
+        int count = 0;
+        for (int i = 0; i < n; i++) {
+            for (int j = 0; j < n; j++) {
+                if (i == j) {
+                    count++;
+                } else if (j > i) {
+                    break;
+                }
+            }
+        }
+        return count;
+    }
+
+    private static int denseSwitch(int value) {
+        switch (value) {
+            case 0:
+                return 10;
+            case 1:
+                return 20;
+            case 2:
+                return 30;
+            case 3:
+                return 40;
+            default:
+                return -1;
+        }
+    }
+
+    private static int sparseSwitch(int value) {
+        switch (value) {
+            case -1000:
+                return 1;
+            case 42:
+                return 2;
+            case 100000:
+                return 3;
+            default:
+                return 0;
+        }
+    }
+
+    private static int fallThrough(int value) {
+        int result = 0;
+        switch (value) {
+            case 1:
+                result += 1;
+            case 2:
+                result += 2;
+            case 3:
+                result += 3;
+                break;
+            default:
+                result = -1;
+        }
+        return result;
+    }
+
+    private static boolean same(Object first, Object second) {
+        return first == second;
+    }
+
+    private static boolean isNull(Object object) {
+        return object == null;
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertFalse(boolean condition) {
+        assertTrue(!condition);
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
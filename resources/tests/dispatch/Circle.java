@@ -0,0 +1,11 @@
+package tests.dispatch;
+
+/**
+ * IncompatibleChanges was compiled while Circle implemented Shape, Circle.class is of this later version which
+ * doesn't. IncompatibleChanges doesn't compile against it.
+ */
+public class Circle {
+    public int sides() {
+        return 0;
+    }
+}
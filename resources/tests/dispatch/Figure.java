@@ -0,0 +1,8 @@
+package tests.dispatch;
+
+/**
+ * corners() was added after Square had been compiled, see Square.
+ */
+public abstract class Figure {
+    abstract int corners();
+}
@@ -0,0 +1,52 @@
+package tests.dispatch;
+
+/**
+ * Invokes methods of classes which changed incompatibly after their callers had been compiled, see Square, Circle and Triangle.
+ */
+public class IncompatibleChanges {
+    public static void main(String... args) {
+        Square square = new Square();
+        Shape shape = square;
+        assertTrue(shape.sides() == 4);
+        try {
+            shape.area();
+            fail();
+        } catch (AbstractMethodError e) {
+            assertTrue(e.getMessage() != null);
+        }
+
+        Figure figure = square;
+        try {
+            figure.corners();
+            fail();
+        } catch (AbstractMethodError e) {
+            assertTrue(e.getMessage() != null);
+        }
+
+        Shape circle = new Circle();
+        try {
+            circle.sides();
+            fail();
+        } catch (IncompatibleClassChangeError e) {
+            assertTrue(!(e instanceof AbstractMethodError));
+        }
+
+        Figure triangle = new Triangle();
+        try {
+            triangle.corners();
+            fail();
+        } catch (IncompatibleClassChangeError e) {
+            assertTrue(!(e instanceof AbstractMethodError));
+        }
+    }
+
+    static void fail() {
+        ((Object) null).hashCode();
+    }
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            fail();
+        }
+    }
+}
@@ -0,0 +1,10 @@
+package tests.dispatch;
+
+/**
+ * area() was added after Square had been compiled, see Square.
+ */
+public interface Shape {
+    int sides();
+
+    int area();
+}
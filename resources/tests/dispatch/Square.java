@@ -0,0 +1,11 @@
+package tests.dispatch;
+
+/**
+ * Compiled against the former versions of Shape and Figure, which didn't declare area() and corners() yet, so
+ * Square.class doesn't implement them. It doesn't compile against the current versions.
+ */
+public class Square extends Figure implements Shape {
+    public int sides() {
+        return 4;
+    }
+}
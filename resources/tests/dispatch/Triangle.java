@@ -0,0 +1,11 @@
+package tests.dispatch;
+
+/**
+ * IncompatibleChanges was compiled while Triangle extended Figure, Triangle.class is of this later version which
+ * doesn't. Its corners() is unrelated to Figure.corners(), even though it takes the same vtable slot.
+ */
+public class Triangle {
+    int corners() {
+        return 3;
+    }
+}
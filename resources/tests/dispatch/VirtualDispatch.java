@@ -0,0 +1,98 @@
+package tests.dispatch;
+
+public class VirtualDispatch {
+    public static void main(String... args) {
+        overriddenMethods();
+        superAndPrivateMethods();
+        inheritedMethods();
+        defaultMethods();
+        overriddenDefaultMethods();
+    }
+
+    private static void overriddenMethods() {
+        Animal dog = new Dog();
+        assertEquals(4, dog.legs());
+        assertEquals(14, dog.describe());
+    }
+
+    private static void superAndPrivateMethods() {
+        Animal bird = new Bird();
+        assertEquals(108, bird.describe());
+        assertEquals(1, bird.secret());
+    }
+
+    private static void inheritedMethods() {
+        Animal animal = new Animal();
+        assertEquals(2, animal.describe());
+    }
+
+    private static void defaultMethods() {
+        Walking dog = new Dog();
+        assertEquals(40, dog.speed());
+        assertEquals(4, dog.legs());
+    }
+
+    private static void overriddenDefaultMethods() {
+        Walking cat = new Cat();
+        assertEquals(7, cat.speed());
+        assertEquals(4, cat.legs());
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        if (expected != actual) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    interface Walking {
+        int legs();
+
+        default int speed() {
+            return legs() * 10;
+        }
+    }
+
+    static class Animal {
+        int legs() {
+            return 0;
+        }
+
+        int describe() {
+            return legs() * 3 + 2;
+        }
+
+        int secret() {
+            return hidden();
+        }
+
+        private int hidden() {
+            return 1;
+        }
+    }
+
+    static class Dog extends Animal implements Walking {
+        public int legs() {
+            return 4;
+        }
+    }
+
+    static class Cat extends Dog {
+        public int speed() {
+            return 7;
+        }
+    }
+
+    static class Bird extends Animal {
+        int legs() {
+            return 2;
+        }
+
+        int describe() {
+            return super.describe() + 100;
+        }
+
+        private int hidden() {
+            return 2;
+        }
+    }
+}
@@ -0,0 +1,46 @@
+package tests.exceptions;
+
+/**
+ * Catches exceptions thrown by the VM and by Java code, and reads their messages and causes.
+ */
+public class ExceptionMessages {
+
+    public static void main(String... args) {
+        try {
+            divide(1, 0);
+            fail();
+        } catch (ArithmeticException e) {
+            assertTrue(e.getMessage() != null);
+            assertTrue(e.getLocalizedMessage() == e.getMessage());
+        }
+
+        String message = "boom";
+        RuntimeException cause = new RuntimeException();
+        try {
+            throw new IllegalArgumentException(message, cause);
+        } catch (IllegalArgumentException e) {
+            assertTrue(e.getMessage() == message);
+            assertTrue(e.getCause() == cause);
+        }
+        assertTrue(cause.getMessage() == null);
+        assertTrue(identity(cause) == cause);
+    }
+
+    static int divide(int dividend, int divisor) {
+        return dividend / divisor;
+    }
+
+    static Object identity(Object object) {
+        return object;
+    }
+
+    static void fail() {
+        ((Object) null).hashCode();
+    }
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            fail();
+        }
+    }
+}
@@ -0,0 +1,58 @@
+package tests.exceptions;
+
+/**
+ * Uses the stack trace, cause and suppressed exceptions API of Throwable.
+ */
+public class ThrowableApi {
+
+    public static void main(String... args) {
+        Throwable throwable = new RuntimeException();
+        assertTrue(throwable.getCause() == null);
+        assertTrue(throwable.getStackTrace() != null);
+        assertTrue(throwable.getSuppressed().length == 0);
+
+        Throwable cause = new IllegalArgumentException();
+        assertTrue(throwable.initCause(cause) == throwable);
+        assertTrue(throwable.getCause() == cause);
+        boolean overwritten = false;
+        try {
+            throwable.initCause(cause);
+        } catch (IllegalStateException e) {
+            overwritten = true;
+        }
+        assertTrue(overwritten);
+
+        Throwable suppressed = new RuntimeException();
+        throwable.addSuppressed(suppressed);
+        assertTrue(throwable.getSuppressed().length == 1);
+        assertTrue(throwable.getSuppressed()[0] == suppressed);
+        boolean selfSuppressed = false;
+        try {
+            throwable.addSuppressed(throwable);
+        } catch (IllegalArgumentException e) {
+            selfSuppressed = true;
+        }
+        assertTrue(selfSuppressed);
+        boolean nullSuppressed = false;
+        try {
+            throwable.addSuppressed(null);
+        } catch (NullPointerException e) {
+            nullSuppressed = true;
+        }
+        assertTrue(nullSuppressed);
+
+        String className = "tests.exceptions.ThrowableApi";
+        StackTraceElement element = new StackTraceElement(className, "main", "ThrowableApi.java", 42);
+        throwable.setStackTrace(new StackTraceElement[]{element});
+        assertTrue(throwable.getStackTrace().length == 1);
+        assertTrue(throwable.getStackTrace()[0] == element);
+        assertTrue(throwable.getStackTrace()[0].getClassName() == className);
+        assertTrue(throwable.getStackTrace()[0].getLineNumber() == 42);
+    }
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
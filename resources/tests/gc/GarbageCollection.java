@@ -0,0 +1,141 @@
+package tests.gc;
+
+public class GarbageCollection {
+    static Node staticChain;
+
+    public static void main(String... args) throws InterruptedException {
+        staticChain = new Node(1);
+        staticChain.append(new Node(2));
+        Node localChain = new Node(10);
+        localChain.append(new Node(20));
+        Node[] array = new Node[2];
+        array[1] = new Node(100);
+        array[1].append(new Node(200));
+
+        Gate ready = new Gate();
+        Gate collected = new Gate();
+        Keeper keeper = new Keeper(ready, collected);
+        keeper.start();
+        ready.pass();
+
+        Churner churner = new Churner();
+        churner.start();
+
+        for (int i = 0; i < 10; i++) {
+            makeCyclicGarbage(100);
+            System.gc();
+        }
+
+        churner.finish();
+        churner.join();
+        collected.open();
+        keeper.join();
+
+        assertEquals(3, staticChain.sum());
+        assertEquals(30, localChain.sum());
+        assertTrue(array[0] == null);
+        assertEquals(300, array[1].sum());
+        assertTrue(keeper.intact);
+        assertTrue(churner.cycles > 0);
+    }
+
+    static void makeCyclicGarbage(int count) {
+        for (int i = 0; i < count; i++) {
+            Node first = new Node(i);
+            Node second = new Node(i);
+            first.next = second;
+            second.next = first;
+        }
+    }
+
+    static class Node {
+        final int value;
+        Node next;
+
+        Node(int value) {
+            this.value = value;
+        }
+
+        void append(Node node) {
+            next = node;
+        }
+
+        int sum() {
+            int sum = 0;
+            for (Node node = this; node != null; node = node.next) {
+                sum += node.value;
+            }
+            return sum;
+        }
+    }
+
+    static class Gate {
+        boolean open;
+
+        synchronized void open() {
+            open = true;
+            notifyAll();
+        }
+
+        synchronized void pass() throws InterruptedException {
+            while (!open) {
+                wait();
+            }
+        }
+    }
+
+    static class Keeper extends Thread {
+        final Gate ready;
+        final Gate collected;
+        boolean intact;
+
+        Keeper(Gate ready, Gate collected) {
+            this.ready = ready;
+            this.collected = collected;
+        }
+
+        @Override
+        public void run() {
+            Node kept = new Node(1000);
+            kept.append(new Node(2000));
+            ready.open();
+            try {
+                collected.pass();
+            } catch (InterruptedException e) {
+                ((Object) null).hashCode();
+            }
+            intact = kept.sum() == 3000;
+        }
+    }
+
+    static class Churner extends Thread {
+        boolean stopped;
+        int cycles;
+
+        synchronized void finish() {
+            stopped = true;
+        }
+
+        synchronized boolean isStopped() {
+            return stopped;
+        }
+
+        @Override
+        public void run() {
+            while (!isStopped()) {
+                makeCyclicGarbage(10);
+                cycles++;
+            }
+        }
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
@@ -0,0 +1,63 @@
+package tests.gc;
+
+public class GenerationalCollection {
+    static Node staticNode;
+
+    public static void main(String... args) {
+        Node holder = new Node(1);
+        Node[] array = new Node[2];
+        //survives enough young collections to be promoted to the old generation
+        churn(40);
+
+        holder.next = new Node(2, new Node(3));
+        array[1] = new Node(10, new Node(20));
+        staticNode = new Node(100, new Node(200));
+        //the young objects are referenced only by the old ones, the write barrier has to keep them alive
+        churn(40);
+
+        assertEquals(6, holder.sum());
+        assertTrue(array[0] == null);
+        assertEquals(30, array[1].sum());
+        assertEquals(300, staticNode.sum());
+    }
+
+    static void churn(int rounds) {
+        for (int round = 0; round < rounds; round++) {
+            for (int i = 0; i < 100; i++) {
+                int[] garbage = new int[256];
+            }
+        }
+    }
+
+    static class Node {
+        final int value;
+        Node next;
+
+        Node(int value) {
+            this.value = value;
+        }
+
+        Node(int value, Node next) {
+            this.value = value;
+            this.next = next;
+        }
+
+        int sum() {
+            int sum = 0;
+            for (Node node = this; node != null; node = node.next) {
+                sum += node.value;
+            }
+            return sum;
+        }
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
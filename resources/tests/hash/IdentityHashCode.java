@@ -0,0 +1,56 @@
+package tests.hash;
+
+public class IdentityHashCode {
+
+    public static void main(String... args) {
+        Object object = new Object();
+        int hash = object.hashCode();
+        assertTrue(hash == object.hashCode());
+        assertTrue(hash == System.identityHashCode(object));
+        assertTrue(System.identityHashCode(null) == 0);
+
+        Constant constant = new Constant();
+        int constantIdentity = System.identityHashCode(constant);
+        assertTrue(constant.hashCode() == 42);
+        assertTrue(constantIdentity == System.identityHashCode(constant));
+
+        //hash codes of distinct objects spread over the buckets of a hash table
+        boolean[] buckets = new boolean[64];
+        int usedBuckets = 0;
+        for (int i = 0; i < 256; i++) {
+            int bucket = new Object().hashCode() & 63;
+            if (!buckets[bucket]) {
+                buckets[bucket] = true;
+                usedBuckets++;
+            }
+        }
+        assertTrue(usedBuckets > 32);
+
+        //the hash codes don't change when the objects are moved or promoted by the garbage collector
+        churn(40);
+        System.gc();
+        assertTrue(hash == object.hashCode());
+        assertTrue(constantIdentity == System.identityHashCode(constant));
+    }
+
+    static void churn(int rounds) {
+        for (int round = 0; round < rounds; round++) {
+            for (int i = 0; i < 100; i++) {
+                int[] garbage = new int[256];
+            }
+        }
+    }
+
+    static class Constant {
+        @Override
+        public int hashCode() {
+            return 42;
+        }
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
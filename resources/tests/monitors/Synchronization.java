@@ -0,0 +1,52 @@
+package tests.monitors;
+
+public class Synchronization {
+    private int counter;
+
+    public static void main(String... args) {
+        Synchronization synchronization = new Synchronization();
+        assertEquals(3, synchronization.nestedBlocks());
+        assertEquals(4, synchronization.synchronizedMethod());
+        assertEquals(5, staticSynchronizedMethod(5));
+        assertEquals(2, synchronization.blockOnArray(new int[1]));
+    }
+
+    private int nestedBlocks() {
+        synchronized (this) {
+            counter++;
+            synchronized (this) {
+                counter++;
+            }
+            counter++;
+        }
+        return counter;
+    }
+
+    private synchronized int synchronizedMethod() {
+        synchronized (this) {
+            counter++;
+        }
+        return counter;
+    }
+
+    private static synchronized int staticSynchronizedMethod(int value) {
+        return value;
+    }
+
+    private int blockOnArray(int[] array) {
+        synchronized (array) {
+            array[0] = 2;
+        }
+        return array[0];
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
@@ -0,0 +1,27 @@
+package tests.natives;
+
+/**
+ * Calls a native method which allocates enough to collect garbage while it runs.
+ */
+public class AllocatingNatives {
+
+    public static void main(String... args) {
+        int[] kept = new int[]{1, 2, 3};
+        Object[] holder = new Object[]{kept};
+        for (int i = 0; i < 20; i++) {
+            int[] filled = fill(3, i);
+            assertTrue(filled.length == 3);
+            assertTrue(filled[2] == i);
+            assertTrue(kept[2] == 3);
+            assertTrue(holder[0] == kept);
+        }
+    }
+
+    static native int[] fill(int length, int value);
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
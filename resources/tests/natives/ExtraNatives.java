@@ -0,0 +1,22 @@
+package tests.natives;
+
+/**
+ * Expects its native methods to be implemented by the embedder of the JVM.
+ */
+public class ExtraNatives {
+
+    public static void main(String... args) {
+        assertTrue(add(40, 2) == 42);
+        assertTrue(new ExtraNatives().negate(7) == -7);
+    }
+
+    static native int add(int first, int second);
+
+    native int negate(int value);
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
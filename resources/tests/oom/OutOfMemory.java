@@ -0,0 +1,61 @@
+package tests.oom;
+
+public class OutOfMemory {
+
+    public static void main(String... args) {
+        boolean thrown = false;
+        try {
+            long[] tooLarge = new long[16 * 1024 * 1024];
+        } catch (OutOfMemoryError e) {
+            thrown = true;
+        }
+        assertTrue(thrown);
+
+        //the garbage allocated in total exceeds the heap, it's collected before the heap is exhausted
+        for (int i = 0; i < 200; i++) {
+            int[] garbage = new int[64 * 1024];
+        }
+
+        Chunk retained = null;
+        int chunks = 0;
+        thrown = false;
+        try {
+            while (true) {
+                retained = new Chunk(retained);
+                chunks++;
+            }
+        } catch (OutOfMemoryError e) {
+            thrown = true;
+        }
+        assertTrue(thrown);
+        assertTrue(chunks > 0);
+        assertTrue(retained.count() == chunks);
+
+        retained = null;
+        int[] afterRelease = new int[64 * 1024];
+        afterRelease[0] = 1;
+    }
+
+    static class Chunk {
+        final Chunk previous;
+        final int[] data = new int[16 * 1024];
+
+        Chunk(Chunk previous) {
+            this.previous = previous;
+        }
+
+        int count() {
+            int count = 0;
+            for (Chunk chunk = this; chunk != null; chunk = chunk.previous) {
+                count++;
+            }
+            return count;
+        }
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
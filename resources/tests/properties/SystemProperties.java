@@ -0,0 +1,26 @@
+package tests.properties;
+
+/**
+ * Expects to be started with the system property tests.property set, e.g. with -Dtests.property=value.
+ */
+public class SystemProperties {
+
+    public static void main(String... args) {
+        assertTrue(System.getProperty("tests.property") != null);
+        assertTrue(System.getProperty("tests.missing") == null);
+
+        boolean thrown = false;
+        try {
+            System.getProperty(null);
+        } catch (NullPointerException e) {
+            thrown = true;
+        }
+        assertTrue(thrown);
+    }
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+}
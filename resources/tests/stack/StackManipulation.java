@@ -0,0 +1,377 @@
+package tests.stack;
+
+public class StackManipulation {
+    private long total;
+    private int count;
+
+    public static void main(String... args) {
+        discardedResults();
+        assertEquals(10, compoundAssignments(new long[2], new int[2]));
+        assertEquals(7, chainedFieldAssignments(new StackManipulation()));
+        assertEquals(6, manyLocals(new Object(), new Object(), new Object(), new Object(), new Object()));
+        assertEquals(44850, wideLocals());
+    }
+
+    private static void discardedResults() {
+        intValue();
+        longValue();
+        doubleValue();
+    }
+
+    private static int compoundAssignments(long[] longs, int[] ints) {
+        longs[1] += 7L;
+        longs[1]++;
+        ints[0] += 2;
+        ints[0]++;
+        return (int) longs[1] + ints[0] - 1;
+    }
+
+    private static int chainedFieldAssignments(StackManipulation stack) {
+        long value = stack.total = 3L;
+        int other = stack.count = 4;
+        return (int) (value + other);
+    }
+
+    private static int manyLocals(Object first, Object second, Object third, Object fourth, Object fifth) {
+        Object sixth = fifth;
+        int counter = 0;
+        Object[] objects = new Object[]{first, second, third, fourth, fifth, sixth};
+        for (int i = 0; i < objects.length; i++) {
+            if (objects[i] != null) {
+                counter++;
+            }
+        }
+        return counter;
+    }
+
+    private static int wideLocals() {
+        int v0 = 0;
+        int v1 = 1;
+        int v2 = 2;
+        int v3 = 3;
+        int v4 = 4;
+        int v5 = 5;
+        int v6 = 6;
+        int v7 = 7;
+        int v8 = 8;
+        int v9 = 9;
+        int v10 = 10;
+        int v11 = 11;
+        int v12 = 12;
+        int v13 = 13;
+        int v14 = 14;
+        int v15 = 15;
+        int v16 = 16;
+        int v17 = 17;
+        int v18 = 18;
+        int v19 = 19;
+        int v20 = 20;
+        int v21 = 21;
+        int v22 = 22;
+        int v23 = 23;
+        int v24 = 24;
+        int v25 = 25;
+        int v26 = 26;
+        int v27 = 27;
+        int v28 = 28;
+        int v29 = 29;
+        int v30 = 30;
+        int v31 = 31;
+        int v32 = 32;
+        int v33 = 33;
+        int v34 = 34;
+        int v35 = 35;
+        int v36 = 36;
+        int v37 = 37;
+        int v38 = 38;
+        int v39 = 39;
+        int v40 = 40;
+        int v41 = 41;
+        int v42 = 42;
+        int v43 = 43;
+        int v44 = 44;
+        int v45 = 45;
+        int v46 = 46;
+        int v47 = 47;
+        int v48 = 48;
+        int v49 = 49;
+        int v50 = 50;
+        int v51 = 51;
+        int v52 = 52;
+        int v53 = 53;
+        int v54 = 54;
+        int v55 = 55;
+        int v56 = 56;
+        int v57 = 57;
+        int v58 = 58;
+        int v59 = 59;
+        int v60 = 60;
+        int v61 = 61;
+        int v62 = 62;
+        int v63 = 63;
+        int v64 = 64;
+        int v65 = 65;
+        int v66 = 66;
+        int v67 = 67;
+        int v68 = 68;
+        int v69 = 69;
+        int v70 = 70;
+        int v71 = 71;
+        int v72 = 72;
+        int v73 = 73;
+        int v74 = 74;
+        int v75 = 75;
+        int v76 = 76;
+        int v77 = 77;
+        int v78 = 78;
+        int v79 = 79;
+        int v80 = 80;
+        int v81 = 81;
+        int v82 = 82;
+        int v83 = 83;
+        int v84 = 84;
+        int v85 = 85;
+        int v86 = 86;
+        int v87 = 87;
+        int v88 = 88;
+        int v89 = 89;
+        int v90 = 90;
+        int v91 = 91;
+        int v92 = 92;
+        int v93 = 93;
+        int v94 = 94;
+        int v95 = 95;
+        int v96 = 96;
+        int v97 = 97;
+        int v98 = 98;
+        int v99 = 99;
+        int v100 = 100;
+        int v101 = 101;
+        int v102 = 102;
+        int v103 = 103;
+        int v104 = 104;
+        int v105 = 105;
+        int v106 = 106;
+        int v107 = 107;
+        int v108 = 108;
+        int v109 = 109;
+        int v110 = 110;
+        int v111 = 111;
+        int v112 = 112;
+        int v113 = 113;
+        int v114 = 114;
+        int v115 = 115;
+        int v116 = 116;
+        int v117 = 117;
+        int v118 = 118;
+        int v119 = 119;
+        int v120 = 120;
+        int v121 = 121;
+        int v122 = 122;
+        int v123 = 123;
+        int v124 = 124;
+        int v125 = 125;
+        int v126 = 126;
+        int v127 = 127;
+        int v128 = 128;
+        int v129 = 129;
+        int v130 = 130;
+        int v131 = 131;
+        int v132 = 132;
+        int v133 = 133;
+        int v134 = 134;
+        int v135 = 135;
+        int v136 = 136;
+        int v137 = 137;
+        int v138 = 138;
+        int v139 = 139;
+        int v140 = 140;
+        int v141 = 141;
+        int v142 = 142;
+        int v143 = 143;
+        int v144 = 144;
+        int v145 = 145;
+        int v146 = 146;
+        int v147 = 147;
+        int v148 = 148;
+        int v149 = 149;
+        int v150 = 150;
+        int v151 = 151;
+        int v152 = 152;
+        int v153 = 153;
+        int v154 = 154;
+        int v155 = 155;
+        int v156 = 156;
+        int v157 = 157;
+        int v158 = 158;
+        int v159 = 159;
+        int v160 = 160;
+        int v161 = 161;
+        int v162 = 162;
+        int v163 = 163;
+        int v164 = 164;
+        int v165 = 165;
+        int v166 = 166;
+        int v167 = 167;
+        int v168 = 168;
+        int v169 = 169;
+        int v170 = 170;
+        int v171 = 171;
+        int v172 = 172;
+        int v173 = 173;
+        int v174 = 174;
+        int v175 = 175;
+        int v176 = 176;
+        int v177 = 177;
+        int v178 = 178;
+        int v179 = 179;
+        int v180 = 180;
+        int v181 = 181;
+        int v182 = 182;
+        int v183 = 183;
+        int v184 = 184;
+        int v185 = 185;
+        int v186 = 186;
+        int v187 = 187;
+        int v188 = 188;
+        int v189 = 189;
+        int v190 = 190;
+        int v191 = 191;
+        int v192 = 192;
+        int v193 = 193;
+        int v194 = 194;
+        int v195 = 195;
+        int v196 = 196;
+        int v197 = 197;
+        int v198 = 198;
+        int v199 = 199;
+        int v200 = 200;
+        int v201 = 201;
+        int v202 = 202;
+        int v203 = 203;
+        int v204 = 204;
+        int v205 = 205;
+        int v206 = 206;
+        int v207 = 207;
+        int v208 = 208;
+        int v209 = 209;
+        int v210 = 210;
+        int v211 = 211;
+        int v212 = 212;
+        int v213 = 213;
+        int v214 = 214;
+        int v215 = 215;
+        int v216 = 216;
+        int v217 = 217;
+        int v218 = 218;
+        int v219 = 219;
+        int v220 = 220;
+        int v221 = 221;
+        int v222 = 222;
+        int v223 = 223;
+        int v224 = 224;
+        int v225 = 225;
+        int v226 = 226;
+        int v227 = 227;
+        int v228 = 228;
+        int v229 = 229;
+        int v230 = 230;
+        int v231 = 231;
+        int v232 = 232;
+        int v233 = 233;
+        int v234 = 234;
+        int v235 = 235;
+        int v236 = 236;
+        int v237 = 237;
+        int v238 = 238;
+        int v239 = 239;
+        int v240 = 240;
+        int v241 = 241;
+        int v242 = 242;
+        int v243 = 243;
+        int v244 = 244;
+        int v245 = 245;
+        int v246 = 246;
+        int v247 = 247;
+        int v248 = 248;
+        int v249 = 249;
+        int v250 = 250;
+        int v251 = 251;
+        int v252 = 252;
+        int v253 = 253;
+        int v254 = 254;
+        int v255 = 255;
+        int v256 = 256;
+        int v257 = 257;
+        int v258 = 258;
+        int v259 = 259;
+        int v260 = 260;
+        int v261 = 261;
+        int v262 = 262;
+        int v263 = 263;
+        int v264 = 264;
+        int v265 = 265;
+        int v266 = 266;
+        int v267 = 267;
+        int v268 = 268;
+        int v269 = 269;
+        int v270 = 270;
+        int v271 = 271;
+        int v272 = 272;
+        int v273 = 273;
+        int v274 = 274;
+        int v275 = 275;
+        int v276 = 276;
+        int v277 = 277;
+        int v278 = 278;
+        int v279 = 279;
+        int v280 = 280;
+        int v281 = 281;
+        int v282 = 282;
+        int v283 = 283;
+        int v284 = 284;
+        int v285 = 285;
+        int v286 = 286;
+        int v287 = 287;
+        int v288 = 288;
+        int v289 = 289;
+        int v290 = 290;
+        int v291 = 291;
+        int v292 = 292;
+        int v293 = 293;
+        int v294 = 294;
+        int v295 = 295;
+        int v296 = 296;
+        int v297 = 297;
+        int v298 = 298;
+        int v299 = 299;
+        int sum = 0;
+        for (int i = 0; i < 300; i++) {
+            sum += i;
+        }
+        v299 += 1000;
+        return sum + v299 - v1 - 1298;
+    }
+
+    private static int intValue() {
+        return 1;
+    }
+
+    private static long longValue() {
+        return 2L;
+    }
+
+    private static double doubleValue() {
+        return 3.0;
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
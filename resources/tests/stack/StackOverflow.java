@@ -0,0 +1,42 @@
+package tests.stack;
+
+/**
+ * Recurses until the maximum stack depth of the JVM is reached, expects it to be at most 1000 frames.
+ */
+public class StackOverflow {
+    static int depth;
+
+    public static void main(String... args) {
+        try {
+            recurse();
+            fail();
+        } catch (StackOverflowError e) {
+            assertTrue(depth > 0 && depth < 1000);
+        }
+
+        //the stack is unwound, so the same depth is reached again
+        int firstDepth = depth;
+        depth = 0;
+        try {
+            recurse();
+            fail();
+        } catch (StackOverflowError e) {
+            assertTrue(depth == firstDepth);
+        }
+    }
+
+    static void recurse() {
+        depth++;
+        recurse();
+    }
+
+    static void fail() {
+        ((Object) null).hashCode();
+    }
+
+    static void assertTrue(boolean condition) {
+        if (!condition) {
+            fail();
+        }
+    }
+}
@@ -0,0 +1,129 @@
+package tests.threads;
+
+public class Threads {
+
+    public static void main(String... args) throws InterruptedException {
+        assertTrue(Thread.currentThread() != null);
+        assertTrue(Thread.activeCount() >= 1);
+        assertTrue(Thread.currentThread().getName() != null);
+
+        String name = "worker";
+        Thread named = new Thread(name);
+        assertTrue(named.getName() == name);
+        assertTrue(new Thread().getName() != null);
+
+        Counter counter = new Counter();
+        Thread[] workers = new Thread[4];
+        for (int i = 0; i < workers.length; i++) {
+            workers[i] = new Thread(new Incrementer(counter, 100));
+            workers[i].start();
+        }
+        for (int i = 0; i < workers.length; i++) {
+            workers[i].join();
+            assertTrue(!workers[i].isAlive());
+        }
+        assertEquals(400, counter.get());
+
+        SelfAware selfAware = new SelfAware();
+        selfAware.start();
+        selfAware.join();
+        assertTrue(selfAware.sawItself);
+        assertTrue(selfAware.sawOwnName);
+
+        Sleeper sleeper = new Sleeper(100000);
+        sleeper.start();
+        sleeper.interrupt();
+        sleeper.join();
+        assertTrue(sleeper.interrupted);
+
+        Thread.currentThread().interrupt();
+        assertTrue(Thread.interrupted());
+        assertTrue(!Thread.interrupted());
+
+        boolean startedTwice = false;
+        try {
+            selfAware.start();
+        } catch (IllegalThreadStateException e) {
+            startedTwice = true;
+        }
+        assertTrue(startedTwice);
+
+        Thread.yield();
+        Thread.sleep(1);
+
+        //a daemon thread does not keep the JVM alive, while a non-daemon thread does
+        Sleeper daemon = new Sleeper(100000);
+        daemon.setDaemon(true);
+        daemon.start();
+        new Thread(new Incrementer(counter, 1)).start();
+    }
+
+    static class Counter {
+        int value;
+
+        synchronized void increment() {
+            value++;
+        }
+
+        synchronized int get() {
+            return value;
+        }
+    }
+
+    static class Incrementer implements Runnable {
+        final Counter counter;
+        final int times;
+
+        Incrementer(Counter counter, int times) {
+            this.counter = counter;
+            this.times = times;
+        }
+
+        @Override
+        public void run() {
+            for (int i = 0; i < times; i++) {
+                counter.increment();
+                Thread.yield();
+            }
+        }
+    }
+
+    static class SelfAware extends Thread {
+        boolean sawItself;
+        boolean sawOwnName;
+
+        @Override
+        public void run() {
+            sawItself = Thread.currentThread() == this;
+            sawOwnName = Thread.currentThread().getName() == getName();
+        }
+    }
+
+    static class Sleeper extends Thread {
+        final long millis;
+        boolean interrupted;
+
+        Sleeper(long millis) {
+            this.millis = millis;
+        }
+
+        @Override
+        public void run() {
+            try {
+                Thread.sleep(millis);
+            } catch (InterruptedException e) {
+                interrupted = true;
+            }
+        }
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
@@ -0,0 +1,13 @@
+package tests.unit;
+
+public class FieldLayout {
+    byte byteField;
+    long longField;
+    Object referenceField;
+    int intField;
+    char charField;
+    boolean booleanField;
+    double doubleField;
+    short shortField;
+    float floatField;
+}
@@ -0,0 +1,178 @@
+package tests.waitnotify;
+
+public class ProducerConsumer {
+
+    public static void main(String... args) throws InterruptedException {
+        Buffer buffer = new Buffer();
+        Consumer consumer = new Consumer(buffer, 100);
+        Thread producer = new Thread(new Producer(buffer, 100));
+        consumer.start();
+        producer.start();
+        producer.join();
+        consumer.join();
+        assertEquals(5050, consumer.sum);
+
+        Gate gate = new Gate();
+        Thread[] waiters = new Thread[3];
+        for (int i = 0; i < waiters.length; i++) {
+            waiters[i] = new Thread(new GateWaiter(gate));
+            waiters[i].start();
+        }
+        gate.open();
+        for (int i = 0; i < waiters.length; i++) {
+            waiters[i].join();
+        }
+        assertEquals(3, gate.passed);
+
+        Object lock = new Object();
+        synchronized (lock) {
+            lock.wait(5);
+            synchronized (lock) {
+                lock.wait(1);
+            }
+        }
+
+        boolean notOwner = false;
+        try {
+            lock.notify();
+        } catch (IllegalMonitorStateException e) {
+            notOwner = true;
+        }
+        assertTrue(notOwner);
+
+        Gate closed = new Gate();
+        InterruptedWaiter interrupted = new InterruptedWaiter(closed);
+        interrupted.start();
+        interrupted.interrupt();
+        interrupted.join();
+        assertTrue(interrupted.interrupted);
+    }
+
+    static class Buffer {
+        int value;
+        boolean full;
+
+        synchronized void put(int newValue) throws InterruptedException {
+            while (full) {
+                wait();
+            }
+            value = newValue;
+            full = true;
+            notifyAll();
+        }
+
+        synchronized int take() throws InterruptedException {
+            while (!full) {
+                wait();
+            }
+            full = false;
+            notifyAll();
+            return value;
+        }
+    }
+
+    static class Producer implements Runnable {
+        final Buffer buffer;
+        final int count;
+
+        Producer(Buffer buffer, int count) {
+            this.buffer = buffer;
+            this.count = count;
+        }
+
+        @Override
+        public void run() {
+            try {
+                for (int i = 1; i <= count; i++) {
+                    buffer.put(i);
+                }
+            } catch (InterruptedException e) {
+                ((Object) null).hashCode();
+            }
+        }
+    }
+
+    static class Consumer extends Thread {
+        final Buffer buffer;
+        final int count;
+        int sum;
+
+        Consumer(Buffer buffer, int count) {
+            this.buffer = buffer;
+            this.count = count;
+        }
+
+        @Override
+        public void run() {
+            try {
+                for (int i = 0; i < count; i++) {
+                    sum += buffer.take();
+                }
+            } catch (InterruptedException e) {
+                ((Object) null).hashCode();
+            }
+        }
+    }
+
+    static class Gate {
+        boolean open;
+        int passed;
+
+        synchronized void open() {
+            open = true;
+            notifyAll();
+        }
+
+        synchronized void pass() throws InterruptedException {
+            while (!open) {
+                wait();
+            }
+            passed++;
+        }
+    }
+
+    static class GateWaiter implements Runnable {
+        final Gate gate;
+
+        GateWaiter(Gate gate) {
+            this.gate = gate;
+        }
+
+        @Override
+        public void run() {
+            try {
+                gate.pass();
+            } catch (InterruptedException e) {
+                ((Object) null).hashCode();
+            }
+        }
+    }
+
+    static class InterruptedWaiter extends Thread {
+        final Gate gate;
+        boolean interrupted;
+
+        InterruptedWaiter(Gate gate) {
+            this.gate = gate;
+        }
+
+        @Override
+        public void run() {
+            try {
+                gate.pass();
+            } catch (InterruptedException e) {
+                interrupted = true;
+            }
+        }
+    }
+
+    private static void assertTrue(boolean condition) {
+        if (!condition) {
+            ((Object) null).hashCode();
+        }
+    }
+
+    private static void assertEquals(int expected, int actual) {
+        assertTrue(expected == actual);
+    }
+}
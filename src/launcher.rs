@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[cfg(test)]
+#[path = "./launcher_test.rs"]
+mod launcher_test;
+
+pub const USAGE: &str = "Usage: rust-jvm [options] <mainclass> [args...]
+           (to execute a class)
+   or  rust-jvm [options] -jar <jarfile> [args...]
+           (to execute a jar file)
+where options include:
+    -cp <class search path of directories and zip/jar files>
+    -classpath <class search path of directories and zip/jar files>
+                  A : separated list of directories, JAR archives,
+                  and ZIP archives to search for class files.
+    -D<name>=<value>
+                  set a system property
+    -verbose:class
+                  print a message for every loaded class
+    -Xbootclasspath:<directories and zip/jar files separated by :>
+                  set the search path of the classes of the JDK,
+                  the resources directory next to rust-jvm by default
+    -Xmx<size>    set maximum heap size
+    -Xss<size>    set the stack size of Java threads
+    -XX:+UseGenerationalGC
+                  allocate objects on a heap with a young and an old generation
+    -? -h -help
+                  print this help message";
+
+/// What the launcher executes: the main method of a class, or the one declared by the manifest of a jar.
+#[derive(Debug, PartialEq)]
+pub enum MainTarget {
+    /// The name of the class in internal form, e.g. tests/Main.
+    Class(String),
+    Jar(PathBuf),
+}
+
+/// The command line of the launcher, parsed like the one of java.
+#[derive(Debug, PartialEq)]
+pub struct LauncherOptions {
+    pub target: MainTarget,
+    pub class_path: Option<String>,
+    pub boot_class_path: Option<String>,
+    pub system_properties: HashMap<String, String>,
+    pub max_heap_size: Option<usize>,
+    pub thread_stack_size: Option<usize>,
+    pub verbose_class: bool,
+    pub generational_heap: bool,
+    pub program_arguments: Vec<String>,
+}
+
+impl LauncherOptions {
+    /// Parses the arguments following the name of the executable. Returns None if the usage was requested,
+    /// and the message to print if the arguments are invalid.
+    pub fn parse(args: &[String]) -> Result<Option<LauncherOptions>, String> {
+        let mut class_path = None;
+        let mut boot_class_path = None;
+        let mut system_properties = HashMap::new();
+        let mut max_heap_size = None;
+        let mut thread_stack_size = None;
+        let mut verbose_class = false;
+        let mut generational_heap = false;
+
+        let mut args = args.iter();
+        let target = loop {
+            let arg = match args.next() {
+                Some(arg) => arg,
+                None => return Err(String::from("Error: no main class or jar file given")),
+            };
+            match arg.as_str() {
+                "-?" | "-h" | "-help" | "--help" => return Ok(None),
+                "-cp" | "-classpath" | "--class-path" => {
+                    let value = args.next().ok_or_else(|| format!("Error: {} requires class path specification", arg))?;
+                    class_path = Some(value.clone());
+                }
+                "-jar" => {
+                    let jar = args.next().ok_or_else(|| String::from("Error: -jar requires jar file specification"))?;
+                    break MainTarget::Jar(PathBuf::from(jar));
+                }
+                "-verbose:class" => verbose_class = true,
+                "-XX:+UseGenerationalGC" => generational_heap = true,
+                "-XX:-UseGenerationalGC" => generational_heap = false,
+                _ if arg.starts_with("-D") => {
+                    let property = &arg[2..];
+                    let (name, value) = match property.find('=') {
+                        Some(separator) => (&property[..separator], &property[separator + 1..]),
+                        None => (property, ""),
+                    };
+                    if name.is_empty() {
+                        return Err(format!("Error: invalid system property {}", arg));
+                    }
+                    system_properties.insert(String::from(name), String::from(value));
+                }
+                _ if arg.starts_with("-Xbootclasspath:") => {
+                    let value = &arg["-Xbootclasspath:".len()..];
+                    if value.is_empty() {
+                        return Err(format!("Error: {} requires class path specification", arg));
+                    }
+                    boot_class_path = Some(String::from(value));
+                }
+                _ if arg.starts_with("-Xmx") => max_heap_size = Some(parse_size(arg, &arg[4..])?),
+                _ if arg.starts_with("-Xss") => thread_stack_size = Some(parse_size(arg, &arg[4..])?),
+                _ if arg.starts_with('-') => return Err(format!("Unrecognized option: {}", arg)),
+                main_class => break MainTarget::Class(main_class.replace('.', "/")),
+            }
+        };
+
+        Ok(Some(LauncherOptions {
+            target,
+            class_path,
+            boot_class_path,
+            system_properties,
+            max_heap_size,
+            thread_stack_size,
+            verbose_class,
+            generational_heap,
+            program_arguments: args.cloned().collect(),
+        }))
+    }
+}
+
+/// Prints the errors logged by the JVM to the standard error, e.g. the exceptions which weren't caught by Java code.
+pub struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Error
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Parses a size in bytes, optionally followed by k, m or g like the sizes of -Xmx.
+fn parse_size(arg: &str, size: &str) -> Result<usize, String> {
+    let invalid_size = || format!("Invalid size: {}", arg);
+    let (digits, unit) = match size.chars().last() {
+        Some('k') | Some('K') => (&size[..size.len() - 1], 1024),
+        Some('m') | Some('M') => (&size[..size.len() - 1], 1024 * 1024),
+        Some('g') | Some('G') => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        _ => (size, 1),
+    };
+    let value: usize = digits.parse().map_err(|_| invalid_size())?;
+    match value.checked_mul(unit) {
+        Some(0) | None => Err(invalid_size()),
+        Some(bytes) => Ok(bytes),
+    }
+}
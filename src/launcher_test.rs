@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::launcher::{LauncherOptions, MainTarget};
+
+fn parse(args: &[&str]) -> Result<Option<LauncherOptions>, String> {
+    let args: Vec<String> = args.iter().map(|arg| String::from(*arg)).collect();
+    LauncherOptions::parse(&args)
+}
+
+#[test]
+pub fn main_class_is_converted_to_internal_form() {
+    let options = parse(&["tests.classpath.Packaged"]).unwrap().unwrap();
+
+    assert_eq!(LauncherOptions {
+        target: MainTarget::Class(String::from("tests/classpath/Packaged")),
+        class_path: None,
+        boot_class_path: None,
+        system_properties: HashMap::new(),
+        max_heap_size: None,
+        thread_stack_size: None,
+        verbose_class: false,
+        generational_heap: false,
+        program_arguments: Vec::new(),
+    }, options);
+}
+
+#[test]
+pub fn options_are_parsed() {
+    let options = parse(&[
+        "-cp", "a.jar:classes", "-Xbootclasspath:jdk:rt.jar", "-Dfirst=1", "-Dsecond=a=b", "-Dempty", "-Xmx64m",
+        "-Xss2M", "-verbose:class", "-XX:+UseGenerationalGC", "Main",
+    ]).unwrap().unwrap();
+
+    assert_eq!(Some(String::from("a.jar:classes")), options.class_path);
+    assert_eq!(Some(String::from("jdk:rt.jar")), options.boot_class_path);
+    assert_eq!(Some(&String::from("1")), options.system_properties.get("first"));
+    assert_eq!(Some(&String::from("a=b")), options.system_properties.get("second"));
+    assert_eq!(Some(&String::new()), options.system_properties.get("empty"));
+    assert_eq!(Some(64 * 1024 * 1024), options.max_heap_size);
+    assert_eq!(Some(2 * 1024 * 1024), options.thread_stack_size);
+    assert!(options.verbose_class);
+    assert!(options.generational_heap);
+}
+
+#[test]
+pub fn arguments_following_main_class_are_program_arguments() {
+    let options = parse(&["-classpath", ".", "Main", "-verbose:class", "second"]).unwrap().unwrap();
+
+    assert_eq!(MainTarget::Class(String::from("Main")), options.target);
+    assert!(!options.verbose_class);
+    assert_eq!(vec![String::from("-verbose:class"), String::from("second")], options.program_arguments);
+}
+
+#[test]
+pub fn arguments_following_jar_are_program_arguments() {
+    let options = parse(&["-Xmx1g", "-jar", "app.jar", "first"]).unwrap().unwrap();
+
+    assert_eq!(MainTarget::Jar(PathBuf::from("app.jar")), options.target);
+    assert_eq!(Some(1024 * 1024 * 1024), options.max_heap_size);
+    assert_eq!(vec![String::from("first")], options.program_arguments);
+}
+
+#[test]
+pub fn help_is_requested() {
+    assert_eq!(Ok(None), parse(&["-help"]));
+    assert_eq!(Ok(None), parse(&["-Xmx1m", "-?", "Main"]));
+}
+
+#[test]
+pub fn invalid_arguments_are_rejected() {
+    assert!(parse(&[]).is_err());
+    assert!(parse(&["-cp"]).is_err());
+    assert!(parse(&["-jar"]).is_err());
+    assert!(parse(&["-Xmx", "Main"]).is_err());
+    assert!(parse(&["-Xmx12x", "Main"]).is_err());
+    assert!(parse(&["-D=value", "Main"]).is_err());
+    assert!(parse(&["-Xbootclasspath:", "Main"]).is_err());
+    assert_eq!(Err(String::from("Unrecognized option: -unknown")), parse(&["-unknown", "Main"]));
+}
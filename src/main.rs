@@ -1,33 +1,83 @@
-use jvm::share::classfile::class_loader::{BootstrapClassLoader, ResourceLocator};
-use jvm::share::memory::heap::JvmHeap;
-use jvm::share::runtime::thread::MainJavaThread;
-use jvm::share::utilities::context::GlobalContext;
-use std::rc::Rc;
-use std::sync::Arc;
-use std::borrow::{Borrow, BorrowMut};
-use jvm::share::native::native_method_repo::NativeMethodRepo;
+use std::process::exit;
+
+use jvm::api::jvm_api::JvmApi;
+use jvm::api::jvm_builder::JvmBuilder;
+use jvm::share::classfile::class_path::ClassPath;
+
+use crate::launcher::{LauncherOptions, MainTarget, StderrLogger, USAGE};
+
+mod launcher;
 
 fn main() {
-    // log4rs::init_file(
-    //     "/home/barnab/projects/rust-jvm/log4rs.yml",
-    //     Default::default(),
-    // )
-    // .unwrap();
-    //
-    // let locator = ResourceLocator::new(String::from(
-    //     "/home/barnab/projects/rust-jvm/resources",
-    // ));
-    // let heap = Arc::new(JvmHeap::new());
-    // let context = Arc::new(GlobalContext::new(heap));
-    // let loader = Arc::new(BootstrapClassLoader::new(locator, context.clone()));
-    // context.set_class_loader(loader);
-    //
-    // let native_method_repo = Arc::new(NativeMethodRepo::new());
-    // context.set_native_method_repo(native_method_repo);
-    //
-    // let main_thread = MainJavaThread::new(context.clone());
-    // let handle = main_thread.start();
-    // let result = handle.join().unwrap();
-    // result.expect("JVM didn't exit correctly");
-    // println!("ABC")
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match LauncherOptions::parse(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            exit(0);
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", USAGE);
+            exit(1);
+        }
+    };
+
+    exit(run(options));
+}
+
+/// Runs the main method on a JVM configured by the options, and returns the exit code of the process.
+fn run(options: LauncherOptions) -> i32 {
+    let mut builder = JvmBuilder::new()
+        .log_sink(Box::new(StderrLogger), log::LevelFilter::Error)
+        .generational_heap(options.generational_heap)
+        .verbose_class(options.verbose_class);
+    if let Some(max_heap_size) = options.max_heap_size {
+        builder = builder.max_heap_size(max_heap_size);
+    }
+    if let Some(thread_stack_size) = options.thread_stack_size {
+        builder = builder.thread_stack_size(thread_stack_size);
+    }
+    for (name, value) in &options.system_properties {
+        builder = builder.system_property(name, value);
+    }
+    if let Some(boot_class_path) = &options.boot_class_path {
+        builder = match ClassPath::parse(boot_class_path) {
+            Ok(boot_class_path) => builder.boot_class_path(boot_class_path),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                return 1;
+            }
+        };
+    }
+
+    let program_arguments = options.program_arguments;
+    let result = match options.target {
+        //like java, the class path is ignored when running a jar
+        MainTarget::Jar(jar_path) => builder.build()
+            .and_then(|mut jvm| jvm.call_jar_main_method(&jar_path, program_arguments)),
+        MainTarget::Class(main_class) => {
+            //same default as the one of java: the CLASSPATH environment variable or the current directory
+            let class_path = options.class_path
+                .or_else(|| std::env::var("CLASSPATH").ok())
+                .unwrap_or_else(|| String::from("."));
+            let class_path = match ClassPath::parse(&class_path) {
+                Ok(class_path) => class_path,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    return 1;
+                }
+            };
+            builder.class_path(class_path).build()
+                .and_then(|mut jvm| jvm.call_main_method(main_class, program_arguments))
+        }
+    };
+
+    match result {
+        Ok(exit_code) => exit_code,
+        Err(exception) => {
+            eprintln!("Error: {}", exception);
+            1
+        }
+    }
 }
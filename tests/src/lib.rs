@@ -2,21 +2,26 @@
 mod tests {
     use std::sync::Arc;
 
-    use jvm::api::jvm_api;
     use jvm::api::jvm_api::JvmApi;
+    use jvm::api::jvm_builder::JvmBuilder;
+    use jvm::share::memory::heap::{Heap, JvmHeap};
     use jvm::share::utilities::jvm_exception::JvmException;
 
     mod jdk;
     mod integration;
 
     fn run_jvm(init_class_name: String) -> Result<i32, JvmException> {
-        log4rs::init_file(
+        run_jvm_with_heap(init_class_name, JvmHeap::new())
+    }
+
+    fn run_jvm_with_heap<H: Heap + 'static>(init_class_name: String, heap: H) -> Result<i32, JvmException> {
+        //the logger can only be initialized once per process, but tests share the same one
+        let _ = log4rs::init_file(
             "/home/barnab/projects/rust-jvm/log4rs.yml",
             Default::default(),
-        )
-            .unwrap();
+        );
 
-        let mut jvm = jvm_api::init_jvm();
-        jvm.call_main_method(init_class_name)
+        let mut jvm = JvmBuilder::new().heap(heap).build()?;
+        jvm.call_main_method(init_class_name, Vec::new())
     }
 }
@@ -1,7 +1,288 @@
-use crate::tests::run_jvm;
+use std::path::Path;
+
+use crate::tests::{run_jvm, run_jvm_with_heap};
+use jvm::api::jvm_api::JvmApi;
+use jvm::api::jvm_builder::JvmBuilder;
+use jvm::share::classfile::class_path::ClassPath;
+use jvm::share::memory::heap::JvmHeap;
+use jvm::share::memory::generational_heap::GenerationalHeap;
+use jvm::share::native::native_methods::NativeMethodArgs;
+use jvm::share::utilities::jvm_exception::JvmException;
+use jvm::share::utilities::jvm_value::{JvmValue, PrimitiveType};
 
 #[test]
 pub fn arrays_set_fields() {
     let return_code = run_jvm(String::from("tests/arrays/ArraysSetFields")).expect("JVM Should've exited normally!");
     assert_eq!(0, return_code);
-}
\ No newline at end of file
+}
+#[test]
+pub fn virtual_dispatch() {
+    let return_code = run_jvm(String::from("tests/dispatch/VirtualDispatch")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn incompatible_class_changes() {
+    let return_code = run_jvm(String::from("tests/dispatch/IncompatibleChanges")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn casting() {
+    let return_code = run_jvm(String::from("tests/casting/Casting")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn control_flow() {
+    let return_code = run_jvm(String::from("tests/controlflow/ControlFlow")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn primitive_arrays() {
+    let return_code = run_jvm(String::from("tests/arrays/PrimitiveArrays")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn stack_manipulation() {
+    let return_code = run_jvm(String::from("tests/stack/StackManipulation")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn synchronization() {
+    let return_code = run_jvm(String::from("tests/monitors/Synchronization")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn threads() {
+    let return_code = run_jvm(String::from("tests/threads/Threads")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn producer_consumer() {
+    let return_code = run_jvm(String::from("tests/waitnotify/ProducerConsumer")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn class_initialization() {
+    let return_code = run_jvm(String::from("tests/classinit/ClassInitialization")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn garbage_collection() {
+    let return_code = run_jvm(String::from("tests/gc/GarbageCollection")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn out_of_memory() {
+    let heap = JvmHeap::with_max_heap_size(4 * 1024 * 1024);
+    let return_code = run_jvm_with_heap(String::from("tests/oom/OutOfMemory"), heap).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn generational_collection() {
+    let heap = GenerationalHeap::with_max_heap_size(512 * 1024);
+    let return_code = run_jvm_with_heap(String::from("tests/gc/GenerationalCollection"), heap).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn garbage_collection_with_generational_heap() {
+    let heap = GenerationalHeap::with_max_heap_size(4 * 1024 * 1024);
+    let return_code = run_jvm_with_heap(String::from("tests/gc/GarbageCollection"), heap).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn out_of_memory_with_generational_heap() {
+    let heap = GenerationalHeap::with_max_heap_size(4 * 1024 * 1024);
+    let return_code = run_jvm_with_heap(String::from("tests/oom/OutOfMemory"), heap).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn identity_hash_code() {
+    let return_code = run_jvm(String::from("tests/hash/IdentityHashCode")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn identity_hash_code_with_generational_heap() {
+    let heap = GenerationalHeap::with_max_heap_size(512 * 1024);
+    let return_code = run_jvm_with_heap(String::from("tests/hash/IdentityHashCode"), heap).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn heap_dump() {
+    let path = std::env::temp_dir().join(format!("heap-dump-{}.hprof", std::process::id()));
+    let mut jvm = JvmBuilder::new().build().unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/gc/GarbageCollection"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+
+    jvm.dump_heap(&path).expect("Heap should've been dumped!");
+    let dump = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(dump.starts_with(b"JAVA PROFILE 1.0.2\0"));
+    assert!(dump.windows(b"java/lang/String".len()).any(|window| window == b"java/lang/String"));
+}
+
+#[test]
+pub fn class_path_archives() {
+    let class_path = ClassPath::parse(
+        "/home/barnab/projects/rust-jvm/resources/tests/classpath/packaged.jar:/home/barnab/projects/rust-jvm/resources/tests/classpath/library.zip"
+    ).unwrap();
+    let mut jvm = JvmBuilder::new().class_path(class_path).build().unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/classpath/Packaged"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn executable_jar() {
+    let mut jvm = JvmBuilder::new().build().unwrap();
+    let return_code = jvm.call_jar_main_method(Path::new("/home/barnab/projects/rust-jvm/resources/tests/classpath/executable.jar"), Vec::new())
+        .expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn explicit_boot_class_path() {
+    let boot_class_path = ClassPath::parse("/home/barnab/projects/rust-jvm/resources").unwrap();
+    let mut jvm = JvmBuilder::new().boot_class_path(boot_class_path).build().unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/dispatch/VirtualDispatch"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn system_properties() {
+    let mut jvm = JvmBuilder::new().system_property("tests.property", "value").build().unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/properties/SystemProperties"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn main_arguments() {
+    let mut jvm = JvmBuilder::new().system_property("tests.argument", "value").build().unwrap();
+    let args = vec![String::from("tests.argument"), String::from("second")];
+    let return_code = jvm.call_main_method(String::from("tests/args/MainArguments"), args).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn empty_main_arguments() {
+    let return_code = run_jvm(String::from("tests/args/MainArguments")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+fn add(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    match args.java_args().as_slice() {
+        [JvmValue::Int { val: first }, JvmValue::Int { val: second }] => Ok(JvmValue::Int { val: first + second }),
+        other => Err(JvmException::from(format!("add expects two ints, but got {:?}", other))),
+    }
+}
+
+fn negate(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    match args.java_args().as_slice() {
+        [JvmValue::ObjRef(_), JvmValue::Int { val }] => Ok(JvmValue::Int { val: -val }),
+        other => Err(JvmException::from(format!("negate expects a receiver and an int, but got {:?}", other))),
+    }
+}
+
+#[test]
+pub fn extra_native_methods() {
+    let mut jvm = JvmBuilder::new()
+        .native_method("tests/natives/ExtraNatives", "add", "(II)I", add)
+        .native_method("tests/natives/ExtraNatives", "negate", "(I)I", negate)
+        .build()
+        .unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/natives/ExtraNatives"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+/// Fills a new int array with the given value, allocating enough garbage meanwhile to collect it repeatedly.
+fn fill(args: NativeMethodArgs) -> Result<JvmValue, JvmException> {
+    let (length, value) = match args.java_args().as_slice() {
+        [JvmValue::Int { val: length }, JvmValue::Int { val: value }] => (*length, *value),
+        other => return Err(JvmException::from(format!("fill expects two ints, but got {:?}", other))),
+    };
+
+    let heap = args.context().heap();
+    let array = heap.allocate_primitive_array(PrimitiveType::Int, length)?;
+    for _ in 0..128 {
+        heap.allocate_primitive_array(PrimitiveType::Long, 1024)?;
+    }
+    for index in 0..length {
+        array.instance_data.put_field(index as usize, JvmValue::Int { val: value })?;
+    }
+    Ok(JvmValue::from(array))
+}
+
+#[test]
+pub fn native_method_allocating_during_garbage_collection() {
+    let mut jvm = JvmBuilder::new()
+        .max_heap_size(2 * 1024 * 1024)
+        .native_method("tests/natives/AllocatingNatives", "fill", "(II)[I", fill)
+        .build()
+        .unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/natives/AllocatingNatives"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn missing_native_method() {
+    let mut jvm = JvmBuilder::new().native_method("tests/natives/ExtraNatives", "add", "(II)I", add).build().unwrap();
+    assert!(jvm.call_main_method(String::from("tests/natives/ExtraNatives"), Vec::new()).is_err());
+}
+
+#[test]
+pub fn out_of_memory_with_configured_heap() {
+    let mut jvm = JvmBuilder::new()
+        .generational_heap(true)
+        .max_heap_size(4 * 1024 * 1024)
+        .thread_stack_size(16 * 1024 * 1024)
+        .build()
+        .unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/oom/OutOfMemory"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn stack_overflow() {
+    let mut jvm = JvmBuilder::new()
+        .max_stack_depth(200)
+        .thread_stack_size(64 * 1024 * 1024)
+        .build()
+        .unwrap();
+    let return_code = jvm.call_main_method(String::from("tests/stack/StackOverflow"), Vec::new()).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn exception_messages() {
+    let return_code = run_jvm(String::from("tests/exceptions/ExceptionMessages")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
+
+#[test]
+pub fn uncaught_exception() {
+    let class_path = ClassPath::parse("/home/barnab/projects/rust-jvm/resources/tests").unwrap();
+    let mut jvm = JvmBuilder::new().class_path(class_path).build().unwrap();
+    let return_code = jvm.call_main_method(String::from("ExceptionThrowing"), Vec::new()).expect("JVM Should've exited normally!");
+    //the RuntimeException isn't caught, which terminates the JVM with exit code 1 like HotSpot
+    assert_eq!(1, return_code);
+}
+
+#[test]
+pub fn throwable_api() {
+    let return_code = run_jvm(String::from("tests/exceptions/ThrowableApi")).expect("JVM Should've exited normally!");
+    assert_eq!(0, return_code);
+}
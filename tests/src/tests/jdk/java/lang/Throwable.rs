@@ -1,8 +1,8 @@
-use std::path::PathBuf;
 use crate::tests::run_jvm;
 
 #[test]
 pub fn test() {
     let return_code = run_jvm(String::from("tests/java/lang/ThrowableTest")).expect("JVM Should've exited normally!");
-    assert_eq!(0, return_code);
+    //the thrown Throwable isn't caught, which terminates the JVM with exit code 1 like HotSpot
+    assert_eq!(1, return_code);
 }
\ No newline at end of file